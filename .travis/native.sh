@@ -13,6 +13,22 @@ cargo build --release --all
 cargo build --release --benches
 cargo test --release --all
 
+# the C header must be regenerated when the ffi crate changes
+which cbindgen || cargo install --version 0.29.4 cbindgen
+(cd ffi && cbindgen -c cbindgen.toml -o tract.h)
+git diff --exit-code ffi/tract.h
+
+# python bindings, against a fresh build of the ffi library
+cargo build --release -p tract-ffi
+pip3 install --user numpy
+if [ `uname` = Darwin ]
+then
+    FFI_LIBRARY=libtract_ffi.dylib
+else
+    FFI_LIBRARY=libtract_ffi.so
+fi
+(cd python && TRACT_FFI_LIBRARY=../target/release/$FFI_LIBRARY python3 -m unittest discover tests)
+
 ./.travis/cache_file.sh \
     ARM-ML-KWS-CNN-M.pb \
     inception_v3_2016_08_28_frozen.pb \
@@ -4,9 +4,11 @@ use log::Level::Info;
 use tract_core::internal::*;
 
 use crate::display_graph::DisplayOptions;
+#[cfg(feature = "conform")]
 use crate::utils::*;
 use crate::*;
 
+#[cfg(feature = "conform")]
 pub fn handle(mut params: Parameters, output_params: DisplayOptions) -> CliResult<()> {
     let tf = params.tf_model.take().unwrap();
     match &params.tract_model {
@@ -17,6 +19,7 @@ pub fn handle(mut params: Parameters, output_params: DisplayOptions) -> CliResul
     }
 }
 
+#[cfg(feature = "conform")]
 pub fn handle_t<TI: TensorInfo>(
     tract: &Model<TI>,
     mut tf: tract_tensorflow::conform::tf::Tensorflow,
@@ -179,3 +182,190 @@ pub fn handle_t<TI: TensorInfo>(
     }
     Ok(())
 }
+
+/// Compares each node output to the activations found in a npz file.
+///
+/// Arrays are looked up by tensor name ("node" for the first output,
+/// "node:slot" for the others, "node:0" is also accepted), as written by
+/// `run --dump-all`. Model inputs are taken from the bundle when present.
+pub fn handle_npz(
+    params: Parameters,
+    npz: &str,
+    tolerance: f32,
+    output_params: DisplayOptions,
+) -> CliResult<()> {
+    let reference: HashMap<String, Tensor> = crate::npy::read_npz(npz)?.into_iter().collect();
+    match &params.tract_model {
+        SomeModel::Inference(m) => handle_npz_t(m, &reference, tolerance, &params, output_params),
+        SomeModel::Typed(m) => handle_npz_t(m, &reference, tolerance, &params, output_params),
+        SomeModel::Normalized(m) => handle_npz_t(m, &reference, tolerance, &params, output_params),
+        SomeModel::Pulsed(_, _) => bail!("Comparing pulsed models is not supported"),
+    }
+}
+
+fn reference_for<'a>(
+    reference: &'a HashMap<String, Tensor>,
+    node: &str,
+    slot: usize,
+) -> Option<&'a Tensor> {
+    if slot == 0 {
+        reference.get(node).or_else(|| reference.get(&format!("{}:0", node)))
+    } else {
+        reference.get(&format!("{}:{}", node, slot))
+    }
+}
+
+/// Checks |got - expected| <= tolerance * max(1, |expected|) on each value.
+///
+/// TDim values are compared as i64, the way they are written in npy files.
+fn close_enough(got: &Tensor, expected: &Tensor, tolerance: f32) -> CliResult<()> {
+    let as_i64 = |t: &Tensor| -> CliResult<Tensor> {
+        Ok(if t.datum_type() == DatumType::TDim {
+            t.cast_to::<i64>()?.into_owned()
+        } else {
+            t.clone()
+        })
+    };
+    if got.datum_type() == DatumType::TDim || expected.datum_type() == DatumType::TDim {
+        return close_enough(&as_i64(got)?, &as_i64(expected)?, tolerance);
+    }
+    if got.datum_type() != expected.datum_type() {
+        bail!("Wrong datum type: expected {:?}", expected.datum_type())
+    }
+    if got.shape() != expected.shape() {
+        bail!("Wrong shape: expected {:?}", expected.shape())
+    }
+    if [DatumType::F16, DatumType::F32, DatumType::F64].contains(&got.datum_type()) {
+        let got = got.cast_to::<f32>()?;
+        let expected = expected.cast_to::<f32>()?;
+        let worst = got
+            .to_array_view::<f32>()?
+            .iter()
+            .zip(expected.to_array_view::<f32>()?.iter())
+            .map(|(&g, &e)| {
+                if g.is_nan() && e.is_nan() || g == e {
+                    0.0
+                } else {
+                    (g - e).abs() / e.abs().max(1.0)
+                }
+            })
+            .fold(0.0f32, |a, b| if b.is_nan() { std::f32::INFINITY } else { a.max(b) });
+        if worst > tolerance {
+            bail!("Too far away (relative error up to {})", worst)
+        }
+    } else if got != expected {
+        bail!("Values differ")
+    }
+    Ok(())
+}
+
+fn handle_npz_t<TI: TensorInfo>(
+    tract: &Model<TI>,
+    reference: &HashMap<String, Tensor>,
+    tolerance: f32,
+    params: &Parameters,
+    output_params: DisplayOptions,
+) -> CliResult<()> {
+    use crate::format::Row;
+
+    let plan = SimplePlan::new(tract)?;
+    let mut state = SimpleState::new(&plan)?;
+    for (ix, input) in tract.input_outlets()?.iter().enumerate() {
+        let name = &tract.node(input.node).name;
+        let value = if let Some(t) = reference_for(reference, name, 0) {
+            t.clone()
+        } else if let Some(t) =
+            params.inputs.as_ref().and_then(|v| v.get(ix)).and_then(|t| t.as_ref())
+        {
+            t.as_ref().clone()
+        } else {
+            bail!("No value for input {} in the bundle or the command line", name)
+        };
+        state.set_input(ix, value)?;
+    }
+
+    let mut display_graph =
+        crate::display_graph::DisplayGraph::from_model_and_options(tract, output_params)?
+            .with_graph_def(&params.graph)?;
+
+    let mut failing = vec![];
+    let mut unchecked = 0;
+
+    for &n in &plan.order {
+        let node = tract.node(n);
+        if tract.input_outlets()?.iter().any(|o| o.node == n) {
+            continue;
+        }
+        if let Err(e) = state.compute_one(n) {
+            failing.push(n);
+            display_graph.add_node_label(n, format!("{}: {}", Red.paint("ERROR"), e))?;
+            break;
+        }
+        let got = state.values[n].clone().unwrap();
+        let mut rows = vec![];
+        let mut checked = false;
+        for (slot, got) in got.iter().enumerate() {
+            let expected = match reference_for(reference, &node.name, slot) {
+                Some(expected) => expected,
+                None => continue,
+            };
+            checked = true;
+            if let Err(e) = close_enough(got, expected, tolerance) {
+                rows.push(Row::Double(
+                    Red.paint(format!("Output {}: {}", slot, e)).to_string(),
+                    format!("expected {:?}\ntract    {:?}", expected, got),
+                ));
+            }
+            // carry on with the reference value to keep tract from drifting
+            state.values[n].as_mut().unwrap()[slot] = Arc::new(expected.clone());
+        }
+        if !checked {
+            unchecked += 1;
+            display_graph.add_node_label(n, "Unchecked".to_string())?;
+        } else if rows.len() > 0 {
+            failing.push(n);
+            let inputs = node
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(ix, o)| {
+                    let tensor = &state.values[o.node].as_ref().unwrap()[o.slot];
+                    Row::Double(format!("Input #{}", ix), format!("{:?}", tensor))
+                })
+                .collect::<Vec<_>>();
+            display_graph.add_node_section(n, inputs)?;
+            display_graph.add_node_section(n, rows)?;
+            display_graph.add_node_label(n, Red.paint("MISM.").to_string())?;
+        } else {
+            display_graph.add_node_label(n, Green.paint("OK").to_string())?;
+        }
+    }
+
+    if unchecked > 0 {
+        warn!("{} node(s) have no reference value", unchecked);
+    }
+    if let Some(first) = failing.first() {
+        for f in &failing {
+            display_graph.render_node(tract.node(*f))?;
+        }
+        bail!("{} error(s), first one at {}.", failing.len(), tract.node(*first))
+    } else if log_enabled!(Info) {
+        display_graph.render()?;
+    } else {
+        println!("{}", Green.paint("Each node passed the comparison."));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tdim_compares_with_its_npy_dump() {
+        let got = tensor1(&[TDim::from(2), TDim::from(3)]);
+        assert!(close_enough(&got, &tensor1(&[2i64, 3]), 0.0).is_ok());
+        assert!(close_enough(&got, &tensor1(&[2i64, 4]), 0.0).is_err());
+        assert!(close_enough(&got, &got, 0.0).is_ok());
+    }
+}
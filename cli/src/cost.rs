@@ -20,11 +20,16 @@ fn handle_t(
     options: DisplayOptions,
 ) -> CliResult<()> {
     let mut total: HashMap<Cost, TDim> = HashMap::default();
+    let mut report = crate::json::ModelReport::new(&params.name, model, &options)?;
+    let json = options.json;
     let mut display_graph =
         DisplayGraph::from_model_and_options(model, options)?.with_graph_def(&params.graph)?;
     for i in ::tract_core::model::eval_order(&model)? {
         let inputs = model.node_input_facts(i)?;
-        let cost = model.nodes()[i].op().cost(&*inputs)?;
+        let cost = merge(model.nodes()[i].op().cost(&*inputs)?);
+        if let Some(node) = report.node_mut(i) {
+            node.cost = Some(crate::json::costs_report(&cost));
+        }
         if !cost.is_empty() {
             let rows = cost
                 .iter()
@@ -34,9 +39,31 @@ fn handle_t(
             display_graph.add_node_section(i, rows)?;
         }
     }
+    if json {
+        let total: Vec<(Cost, TDim)> = total.into_iter().collect();
+        report.total_cost = Some(crate::json::costs_report(&total));
+        return report.print();
+    }
     display_graph.render()?;
+    let mut total: Vec<(String, TDim)> =
+        total.into_iter().map(|(c, i)| (format!("{:?}", c), i)).collect();
+    total.sort_by(|a, b| a.0.cmp(&b.0));
     for (c, i) in total {
-        println!("{:?}: {:?}", c, i);
+        println!("{}: {:?}", c, i);
     }
     Ok(())
 }
+
+/// Sums the entries of an op cost sharing the same kind, keeping the order
+/// of their first appearance.
+pub fn merge(costs: TVec<(Cost, TDim)>) -> TVec<(Cost, TDim)> {
+    let mut merged: TVec<(Cost, TDim)> = tvec!();
+    for (c, n) in costs {
+        if let Some(m) = merged.iter_mut().find(|m| m.0 == c) {
+            m.1 += n;
+        } else {
+            merged.push((c, n));
+        }
+    }
+    merged
+}
@@ -21,6 +21,7 @@ pub struct DisplayOptions {
     pub op_name: Option<String>,
     pub node_name: Option<String>,
     pub successors: Option<usize>,
+    pub json: bool,
 }
 
 impl DisplayOptions {
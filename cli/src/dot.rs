@@ -0,0 +1,58 @@
+use std::time::Instant;
+
+use crate::errors::*;
+use crate::{Parameters, SomeModel};
+use tract_core::internal::*;
+use tract_core::model::DotOptions;
+
+pub fn handle(params: Parameters, profile: bool) -> CliResult<()> {
+    match &params.tract_model {
+        SomeModel::Inference(m) => handle_t(m, profile),
+        SomeModel::Typed(m) => handle_t(m, profile),
+        SomeModel::Normalized(m) => handle_t(m, profile),
+        SomeModel::Pulsed(_, m) => handle_t(m, profile),
+    }
+}
+
+fn handle_t<TI: TensorInfo>(model: &Model<TI>, profile: bool) -> CliResult<()> {
+    let mut dot_options = DotOptions::default();
+    if profile {
+        let timings = node_timings(model)?;
+        let total: f64 = timings.values().sum();
+        let max = timings.values().cloned().fold(0.0, f64::max);
+        for (&node, &time) in &timings {
+            dot_options
+                .node_labels
+                .insert(node, vec![format!("{:.3} ms ({:.1}%)", time * 1e3, time / total * 100.0)]);
+            // white to red, as a graphviz "H S V" color
+            let saturation = if max > 0.0 { time / max } else { 0.0 };
+            dot_options.node_colors.insert(node, format!("0.0 {:.3} 1.0", saturation));
+        }
+    }
+    print!("{}", model.to_dot_with_options(&dot_options)?);
+    Ok(())
+}
+
+/// Average time spent in each node, in seconds, over a few runs.
+fn node_timings<TI: TensorInfo>(model: &Model<TI>) -> CliResult<HashMap<usize, f64>> {
+    let plan = SimplePlan::new(model)?;
+    let mut state = SimpleState::new(&plan)?;
+    let mut timings = HashMap::new();
+    let mut iters = 0;
+    let start = Instant::now();
+    while iters < 100 && start.elapsed().as_secs() < 1 {
+        let inputs = crate::profile::make_inputs_for_model(model)?;
+        let mut last = Instant::now();
+        state.run_with_hook(inputs, |node, _| {
+            let now = Instant::now();
+            let elapsed = now - last;
+            *timings.entry(node.id).or_insert(0.0) +=
+                elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1e-9;
+            last = now;
+            Ok(())
+        })?;
+        iters += 1;
+    }
+    timings.values_mut().for_each(|t| *t /= iters as f64);
+    Ok(timings)
+}
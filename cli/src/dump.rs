@@ -18,9 +18,13 @@ fn handle_t<TI: TensorInfo>(
     params: &Parameters,
     options: DisplayOptions,
 ) -> CliResult<()> {
-    let display_graph =
-        DisplayGraph::from_model_and_options(tract, options)?.with_graph_def(&params.graph)?;
-    display_graph.render()?;
+    if options.json {
+        crate::json::ModelReport::new(&params.name, tract, &options)?.print()?;
+    } else {
+        let display_graph =
+            DisplayGraph::from_model_and_options(tract, options)?.with_graph_def(&params.graph)?;
+        display_graph.render()?;
+    }
 
     if let Some(asserts) = &params.assertions {
         if let Some(asserts) = &asserts.assert_outputs {
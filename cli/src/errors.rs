@@ -16,6 +16,7 @@ error_chain! {
     foreign_links {
         Io(::std::io::Error);
         NumParseInt(::std::num::ParseIntError);
+        NumParseFloat(::std::num::ParseFloatError);
         NdarrayShape(ndarray::ShapeError);
     }
 }
@@ -0,0 +1,364 @@
+//! Model surgery: replacing nodes by constants, and writing the sub-model
+//! between the selected inputs and outputs back in its original format.
+
+use crate::errors::*;
+use crate::{Parameters, SomeGraphDef, SomeModel};
+use tract_core::internal::*;
+use tract_core::ops::konst::Const;
+
+/// Replace the output of a node by a constant, from a `name=@file.npy` spec.
+pub fn set_const(model: &mut InferenceModel, spec: &str) -> CliResult<()> {
+    let mut split = spec.splitn(2, '=');
+    let name = split.next().unwrap();
+    let value = split.next().ok_or_else(|| format!("Expected name=@file.npy, got {}", spec))?;
+    let value = crate::tensor::for_string(value)?
+        .value
+        .concretize()
+        .ok_or_else(|| format!("No constant value in {}", spec))?;
+    let node = model.node_by_name(name)?;
+    if node.outputs.len() != 1 {
+        bail!(
+            "Can only replace single output nodes by a constant, {} has {}",
+            name,
+            node.outputs.len()
+        )
+    }
+    if model.input_outlets()?.iter().any(|o| o.node == node.id) {
+        bail!("Can not replace input node {} by a constant", name)
+    }
+    let mut patch = InferenceModelPatch::default();
+    let konst = patch.add_const(name, value)?;
+    patch.shunt_outside(OutletId::new(node.id, 0), OutletId::new(konst, 0))?;
+    patch.apply(model)?;
+    Ok(())
+}
+
+/// Handles the `extract` subcommand.
+pub fn handle(params: Parameters, output: &str) -> CliResult<()> {
+    match &params.tract_model {
+        SomeModel::Inference(m) => extract(&params.graph, m, output),
+        SomeModel::Typed(m) => extract(&params.graph, m, output),
+        _ => bail!("Can only extract from a model before declutter or pulsification"),
+    }
+}
+
+fn extract<TI: TensorInfo>(graph: &SomeGraphDef, model: &Model<TI>, output: &str) -> CliResult<()> {
+    let plan = SimplePlan::new(model)?;
+    info!("Extracting {} nodes to {}", plan.order.len(), output);
+    match graph {
+        #[cfg(feature = "tf")]
+        SomeGraphDef::Tf(graph) => extract_tf(graph, model, &plan.order)?.save_to(output)?,
+        #[cfg(feature = "onnx")]
+        SomeGraphDef::Onnx(proto) => extract_onnx(proto, model, &plan.order)?.save_to(output)?,
+        SomeGraphDef::_NoGraph => bail!("No source graph to extract from"),
+    }
+    Ok(())
+}
+
+/// Keep the nodes in `order`, turning model inputs into placeholders and
+/// nodes replaced by constants into `Const` nodes.
+#[cfg(feature = "tf")]
+fn extract_tf<TI: TensorInfo>(
+    graph: &tract_tensorflow::tfpb::graph::GraphDef,
+    model: &Model<TI>,
+    order: &[usize],
+) -> CliResult<tract_tensorflow::tfpb::graph::GraphDef> {
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
+    use tract_tensorflow::tfpb;
+    use tract_tensorflow::tfpb::tensor::TensorProto;
+    use tract_tensorflow::tfpb::tensor_shape::TensorShapeProto;
+    use tract_tensorflow::tfpb::types::DataType;
+
+    let pbnodes: HashMap<&str, &tfpb::node_def::NodeDef> =
+        graph.get_node().iter().map(|n| (n.get_name(), n)).collect();
+    let inputs = model.input_outlets()?;
+    let kept: HashSet<&str> = order.iter().map(|&id| &*model.node(id).name).collect();
+    let mut extracted = graph.clone();
+    extracted.mut_node().clear();
+    for &id in order {
+        let node = model.node(id);
+        let pbnode = pbnodes
+            .get(&*node.name)
+            .ok_or_else(|| format!("Node {} not found in graph", node.name))?;
+        let pbnode = if inputs.iter().any(|o| o.node == id) && pbnode.get_op() != "Placeholder" {
+            if node.outputs.len() != 1 {
+                bail!(
+                    "Can not make a placeholder of {}, it has {} outputs",
+                    node.name,
+                    node.outputs.len()
+                )
+            }
+            let fact = node.outputs[0].fact.to_tensor_fact();
+            let dt = fact
+                .datum_type
+                .concretize()
+                .ok_or_else(|| format!("Unknown datum type for input {}", node.name))?;
+            tfpb::node()
+                .name(&node.name)
+                .op("Placeholder")
+                .attr("dtype", DataType::try_from(dt)?)
+                .attr("shape", TensorShapeProto::try_from(&fact.shape)?)
+        } else if let Some(value) = node.op_as::<Const>().map(|k| k.value()).filter(|v| {
+            pbnode.get_op() != "Const"
+                || pbnode.get_attr_tensor("value").ok().as_ref() != Some(&***v)
+        }) {
+            tfpb::node()
+                .name(&node.name)
+                .op("Const")
+                .attr("dtype", DataType::try_from(value.datum_type())?)
+                .attr("value", TensorProto::try_from(&**value)?)
+        } else {
+            let mut pbnode = (*pbnode).clone();
+            // control inputs may point to nodes upstream of the new inputs
+            pbnode.mut_input().retain(|i| !i.starts_with("^") || kept.contains(&i[1..]));
+            pbnode
+        };
+        extracted.mut_node().push(pbnode);
+    }
+    Ok(extracted)
+}
+
+/// Keep the nodes and initializers in `order`, turning model inputs into
+/// graph inputs and nodes replaced by constants into `Constant` nodes.
+#[cfg(feature = "onnx")]
+fn extract_onnx<TI: TensorInfo>(
+    proto: &tract_onnx::pb::ModelProto,
+    model: &Model<TI>,
+    order: &[usize],
+) -> CliResult<tract_onnx::pb::ModelProto> {
+    use std::convert::TryFrom;
+    use tract_onnx::pb::*;
+
+    let graph = proto.get_graph();
+    // node names as given by the onnx loader
+    let mut pbnodes: HashMap<String, &NodeProto> = HashMap::new();
+    for (ix, pbnode) in graph.get_node().iter().enumerate() {
+        let name = if pbnode.get_name() != "" {
+            pbnode.get_name().to_string()
+        } else if pbnode.get_output().len() > 0 && pbnode.get_output()[0] != "" {
+            pbnode.get_output()[0].to_owned()
+        } else {
+            format!("{}-{}", graph.get_input().len() + ix, pbnode.get_op_type())
+        };
+        pbnodes.insert(name, pbnode);
+    }
+    let initializers: HashMap<&str, &TensorProto> =
+        graph.get_initializer().iter().map(|i| (i.get_name(), i)).collect();
+    let graph_inputs: HashMap<&str, &ValueInfoProto> =
+        graph.get_input().iter().map(|i| (i.get_name(), i)).collect();
+
+    let tensor_name = |outlet: OutletId| -> String {
+        let node = model.node(outlet.node);
+        pbnodes
+            .get(&node.name)
+            .map(|n| n.get_output()[outlet.slot].to_string())
+            .unwrap_or_else(|| node.name.clone())
+    };
+    let value_info = |outlet: OutletId| -> CliResult<ValueInfoProto> {
+        let fact = model.outlet_fact(outlet)?.to_tensor_fact();
+        let mut field_type = TypeProto::new();
+        field_type.set_tensor_type(TypeProto_Tensor::try_from(&fact)?);
+        let mut info = ValueInfoProto::new();
+        info.set_name(tensor_name(outlet));
+        info.set_field_type(field_type);
+        Ok(info)
+    };
+
+    let inputs = model.input_outlets()?;
+    let mut extracted = graph.clone();
+    extracted.mut_node().clear();
+    extracted.mut_input().clear();
+    extracted.mut_output().clear();
+    extracted.mut_initializer().clear();
+    extracted.mut_value_info().clear();
+    for &id in order {
+        let node = model.node(id);
+        if inputs.iter().any(|o| o.node == id) {
+            for slot in 0..node.outputs.len() {
+                extracted.mut_input().push(value_info(OutletId::new(id, slot))?);
+            }
+        } else if let Some(init) = initializers.get(&*node.name) {
+            // the initializer value may have been overridden by a constant
+            let init = match node.op_as::<Const>() {
+                Some(konst) if Tensor::try_from(*init).ok().as_ref() != Some(&**konst.value()) => {
+                    let mut value = TensorProto::try_from(&**konst.value())?;
+                    value.set_name(node.name.clone());
+                    value
+                }
+                _ => (*init).clone(),
+            };
+            extracted.mut_initializer().push(init);
+            if let Some(input) = graph_inputs.get(&*node.name) {
+                extracted.mut_input().push((*input).clone());
+            }
+        } else if let Some(pbnode) = pbnodes.get(&node.name) {
+            match node.op_as::<Const>() {
+                Some(konst) if pbnode.get_op_type() != "Constant" => {
+                    let mut value = AttributeProto::new();
+                    value.set_name("value".to_string());
+                    value.set_field_type(AttributeProto_AttributeType::TENSOR);
+                    value.set_t(TensorProto::try_from(&**konst.value())?);
+                    let mut constant = NodeProto::new();
+                    constant.set_name(pbnode.get_name().to_string());
+                    constant.set_op_type("Constant".to_string());
+                    constant.mut_output().push(pbnode.get_output()[0].clone());
+                    constant.mut_attribute().push(value);
+                    extracted.mut_node().push(constant);
+                }
+                _ => extracted.mut_node().push((*pbnode).clone()),
+            }
+        } else {
+            bail!("Node {} not found in graph", node.name)
+        }
+    }
+    for &output in model.output_outlets()? {
+        extracted.mut_output().push(value_info(output)?);
+    }
+    let mut extracted_proto = proto.clone();
+    extracted_proto.set_graph(extracted);
+    Ok(extracted_proto)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(model: &InferenceModel, input: Tensor) -> Arc<Tensor> {
+        SimplePlan::new(model).unwrap().run(tvec!(input)).unwrap().remove(0)
+    }
+
+    #[test]
+    fn set_const_twice() {
+        let mut model = InferenceModel::default();
+        let input =
+            model.add_source("input", TensorFact::dt_shape(f32::datum_type(), [1])).unwrap();
+        let three = model.add_const("three", rctensor1(&[3f32])).unwrap();
+        let plus3 = model.add_node_default("plus3", tract_core::ops::math::Add::default()).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(plus3, 0)).unwrap();
+        model.add_edge(OutletId::new(three, 0), InletId::new(plus3, 1)).unwrap();
+        model.set_output_outlets(&[OutletId::new(plus3, 0)]).unwrap();
+        set_const(&mut model, "three=1xf32=5").unwrap();
+        set_const(&mut model, "three=1xf32=7").unwrap();
+        let konst = model.node_by_name("three").unwrap().op_as::<Const>().unwrap();
+        assert_eq!(konst.value(), &rctensor1(&[7f32]));
+        assert_eq!(run(&model, tensor1(&[1f32])), rctensor1(&[8f32]));
+        let order = SimplePlan::new(&model).unwrap().order;
+        let names: Vec<&str> = order.iter().map(|&n| &*model.node(n).name).collect();
+        assert_eq!(names, vec!["input", "three", "plus3"]);
+        set_const(&mut model, "plus3=1xf32=0").unwrap();
+        assert_eq!(run(&model, tensor1(&[1f32])), rctensor1(&[0f32]));
+    }
+
+    #[cfg(feature = "tf")]
+    mod tf {
+        use super::*;
+        use tract_tensorflow::tfpb;
+        use tract_tensorflow::tfpb::types::DataType::DT_FLOAT;
+
+        fn plus3() -> tfpb::graph::GraphDef {
+            let input = tfpb::node().name("input").op("Placeholder").attr("dtype", DT_FLOAT);
+            let three = tfpb::node()
+                .name("three")
+                .op("Const")
+                .attr("dtype", DT_FLOAT)
+                .attr("value", tfpb::tensor_f32(vec![1], vec![3.0]));
+            let plus3 = tfpb::node()
+                .name("plus3")
+                .op("Add")
+                .input("input")
+                .input("three")
+                .attr("T", DT_FLOAT);
+            tfpb::graph().node(input).node(three).node(plus3)
+        }
+
+        #[test]
+        fn extract_overridden_const() {
+            let graph = plus3();
+            let mut model = tract_tensorflow::tensorflow().model_for_proto_model(&graph).unwrap();
+            set_const(&mut model, "three=1xf32=5").unwrap();
+            let order = SimplePlan::new(&model).unwrap().order;
+            let extracted = extract_tf(&graph, &model, &order).unwrap();
+            let reloaded =
+                tract_tensorflow::tensorflow().model_for_proto_model(&extracted).unwrap();
+            assert_eq!(run(&reloaded, tensor1(&[1f32])), rctensor1(&[6f32]));
+        }
+
+        #[test]
+        fn extract_drops_dangling_control_inputs() {
+            let mut graph = plus3();
+            graph.mut_node().insert(0, tfpb::node().name("init").op("NoOp"));
+            graph.mut_node()[3].mut_input().push("^init".to_string());
+            let model = tract_tensorflow::tensorflow().model_for_proto_model(&plus3()).unwrap();
+            let order = SimplePlan::new(&model).unwrap().order;
+            let extracted = extract_tf(&graph, &model, &order).unwrap();
+            assert_eq!(extracted.get_node().len(), 3);
+            assert_eq!(extracted.get_node()[2].get_input(), &["input", "three"]);
+        }
+    }
+
+    #[cfg(feature = "onnx")]
+    mod onnx {
+        use super::*;
+        use std::convert::TryFrom;
+        use tract_onnx::pb::*;
+
+        fn value_info(name: &str) -> ValueInfoProto {
+            let mut field_type = TypeProto::new();
+            field_type.set_tensor_type(
+                TypeProto_Tensor::try_from(&TensorFact::dt_shape(DatumType::F32, shapefact!(1)))
+                    .unwrap(),
+            );
+            let mut info = ValueInfoProto::new();
+            info.set_name(name.to_string());
+            info.set_field_type(field_type);
+            info
+        }
+
+        fn plus3() -> ModelProto {
+            let mut three = TensorProto::try_from(&tensor1(&[3f32])).unwrap();
+            three.set_name("three".to_string());
+            let mut add = NodeProto::new();
+            add.set_op_type("Add".to_string());
+            add.set_input(vec!["input".to_string(), "three".to_string()].into());
+            add.set_output(vec!["plus3".to_string()].into());
+            let mut graph = GraphProto::new();
+            graph.mut_input().push(value_info("input"));
+            graph.mut_input().push(value_info("three"));
+            graph.mut_initializer().push(three);
+            graph.mut_node().push(add);
+            graph.mut_output().push(value_info("plus3"));
+            let mut opset = OperatorSetIdProto::new();
+            opset.set_version(9);
+            let mut proto = ModelProto::new();
+            proto.mut_opset_import().push(opset);
+            proto.set_graph(graph);
+            proto
+        }
+
+        #[test]
+        fn extract_overridden_initializer() {
+            let proto = plus3();
+            let mut model = tract_onnx::onnx().model_for_proto_model(&proto).unwrap();
+            assert_eq!(run(&model, tensor1(&[1f32])), rctensor1(&[4f32]));
+            set_const(&mut model, "three=1xf32=5").unwrap();
+            let order = SimplePlan::new(&model).unwrap().order;
+            let extracted = extract_onnx(&proto, &model, &order).unwrap();
+            let reloaded = tract_onnx::onnx().model_for_proto_model(&extracted).unwrap();
+            assert_eq!(run(&reloaded, tensor1(&[1f32])), rctensor1(&[6f32]));
+        }
+
+        #[test]
+        fn extract_keeps_original_initializer() {
+            let proto = plus3();
+            let model = tract_onnx::onnx().model_for_proto_model(&proto).unwrap();
+            let order = SimplePlan::new(&model).unwrap().order;
+            let extracted = extract_onnx(&proto, &model, &order).unwrap();
+            assert_eq!(
+                extracted.get_graph().get_initializer(),
+                proto.get_graph().get_initializer()
+            );
+            assert_eq!(extracted.get_graph().get_input(), proto.get_graph().get_input());
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Machine readable reports for dump, cost and profile.
+//!
+//! The document layout is meant to stay stable across releases: fields may
+//! be added, but existing ones are not renamed or removed.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use tract_core::internal::*;
+
+use crate::display_graph::DisplayOptions;
+use crate::rusage::Duration;
+use crate::CliResult;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OutletReport {
+    pub node: usize,
+    pub slot: usize,
+}
+
+impl From<OutletId> for OutletReport {
+    fn from(o: OutletId) -> OutletReport {
+        OutletReport { node: o.node, slot: o.slot }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FactReport {
+    /// Datum type, if known.
+    pub datum_type: Option<String>,
+    /// Dimensions (integers, or symbolic expressions as strings), if known.
+    pub shape: Option<Vec<Value>>,
+}
+
+impl FactReport {
+    pub fn from_fact(fact: &TensorFact) -> FactReport {
+        let shape = if fact.shape.is_open() {
+            None
+        } else {
+            fact.shape
+                .dims()
+                .map(|d| {
+                    d.concretize().map(|d| match d.to_integer() {
+                        Ok(i) => Value::from(i),
+                        Err(_) => Value::from(format!("{:?}", d)),
+                    })
+                })
+                .collect()
+        };
+        FactReport { datum_type: fact.datum_type.concretize().map(|dt| format!("{:?}", dt)), shape }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DurationReport {
+    /// Average real time, in seconds.
+    pub real: f64,
+    /// Average user time, in seconds.
+    pub user: f64,
+    /// Average system time, in seconds.
+    pub sys: f64,
+    pub iterations: u64,
+}
+
+impl From<Duration> for DurationReport {
+    fn from(d: Duration) -> DurationReport {
+        DurationReport {
+            real: d.avg_real(),
+            user: d.avg_user(),
+            sys: d.avg_sys(),
+            iterations: d.counter,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyReport {
+    /// Seconds.
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryReport {
+    /// Bytes, after the first measured iteration.
+    pub first: u64,
+    /// Bytes, after the last measured iteration.
+    pub last: u64,
+    /// Bytes, maximum over the measured iterations.
+    pub max: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    /// Averages over all measured iterations.
+    #[serde(flatten)]
+    pub entire: DurationReport,
+    /// Untimed runs per thread.
+    pub warmup: u64,
+    /// Number of threads.
+    pub concurrency: usize,
+    /// Runs per second, all threads included.
+    pub throughput: f64,
+    /// Per iteration latency.
+    pub latency: LatencyReport,
+    /// Resident memory, sampled after each iteration.
+    pub resident_size: MemoryReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeReport {
+    pub id: usize,
+    pub name: String,
+    pub op: String,
+    pub inputs: Vec<OutletReport>,
+    pub outputs: Vec<FactReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<BTreeMap<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<DurationReport>,
+    /// Floating point operations per run, when the node cost is known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flops: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileReport {
+    /// Whole network run.
+    pub entire: DurationReport,
+    /// Sum of the per-node measures.
+    pub accounted: DurationReport,
+    /// Resident memory at the end of the profiling, in bytes.
+    pub resident_size: u64,
+    /// Peak resident memory, in bytes.
+    pub resident_size_max: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelReport {
+    pub name: String,
+    pub inputs: Vec<OutletReport>,
+    pub outputs: Vec<OutletReport>,
+    pub nodes: Vec<NodeReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_cost: Option<BTreeMap<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ProfileReport>,
+}
+
+/// Costs as a JSON map, using integers where the dimension is known.
+/// Entries of the same kind are summed.
+pub fn costs_report<'a>(
+    costs: impl IntoIterator<Item = &'a (Cost, TDim)>,
+) -> BTreeMap<String, Value> {
+    let mut summed: BTreeMap<String, TDim> = BTreeMap::new();
+    for (c, n) in costs {
+        *summed.entry(format!("{:?}", c)).or_insert(0.to_dim()) += *n;
+    }
+    summed
+        .into_iter()
+        .map(|(c, n)| {
+            let n = match n.to_integer() {
+                Ok(i) => Value::from(i),
+                Err(_) => Value::from(format!("{:?}", n)),
+            };
+            (c, n)
+        })
+        .collect()
+}
+
+impl ModelReport {
+    /// Describe the nodes selected by `options`.
+    pub fn new<TI: TensorInfo>(
+        name: &str,
+        model: &Model<TI>,
+        options: &DisplayOptions,
+    ) -> CliResult<ModelReport> {
+        let mut nodes = vec![];
+        for n in ::tract_core::model::eval_order(model)? {
+            let node = model.node(n);
+            if !options.filter(model, node)? {
+                continue;
+            }
+            nodes.push(NodeReport {
+                id: node.id,
+                name: node.name.clone(),
+                op: node.op().name().to_string(),
+                inputs: node.inputs.iter().map(|&o| o.into()).collect(),
+                outputs: node
+                    .outputs
+                    .iter()
+                    .map(|o| FactReport::from_fact(&o.fact.to_tensor_fact()))
+                    .collect(),
+                info: node.op().info()?,
+                cost: None,
+                time: None,
+                flops: None,
+            });
+        }
+        Ok(ModelReport {
+            name: name.to_string(),
+            inputs: model.input_outlets()?.iter().map(|&o| o.into()).collect(),
+            outputs: model.output_outlets()?.iter().map(|&o| o.into()).collect(),
+            nodes,
+            total_cost: None,
+            profile: None,
+        })
+    }
+
+    pub fn node_mut(&mut self, id: usize) -> Option<&mut NodeReport> {
+        self.nodes.iter_mut().find(|n| n.id == id)
+    }
+
+    pub fn print(&self) -> CliResult<()> {
+        println!("{}", serde_json::to_string_pretty(self).map_err(|e| format!("{}", e))?);
+        Ok(())
+    }
+}
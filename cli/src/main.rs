@@ -14,8 +14,12 @@ extern crate env_logger;
 extern crate libc;
 extern crate pbr;
 extern crate rand;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate terminal_size;
 extern crate textwrap;
+extern crate zip;
 #[macro_use]
 extern crate tract_core;
 #[cfg(feature = "onnx")]
@@ -37,14 +41,17 @@ use tract_tensorflow::tfpb;
 use crate::display_graph::DisplayOptions;
 use crate::errors::*;
 
-#[cfg(feature = "conform")]
 mod compare;
 mod cost;
 mod display_graph;
+mod dot;
 mod draw;
 mod dump;
 mod errors;
+mod extract;
 mod format;
+mod json;
+mod npy;
 mod optimize_check;
 mod profile;
 mod run;
@@ -69,13 +76,17 @@ fn main() {
         (@setting UnifiedHelpMessage)
         (@setting DeriveDisplayOrder)
 
-        (@arg model: +takes_value "Sets the model to use")
+        (@arg model: +takes_value "Sets the model to use. ONNX models are memory-mapped: \
+            the file must not be modified while tract runs.")
 
         (@arg format: +takes_value
             "Hint the model format ('onnx' or 'tf') instead of guess from extension.")
 
         (@arg input: -i --input +takes_value +multiple number_of_values(1)
-            "Set input value (@file or 3x4xi32)")
+            "Set input value (@file.npy, @file.npz:name, @file.pb or 3x4xi32), or input shape \
+            and type hint (1xSxi64, Nx3x?x?xf32, 1x..xf32, f32), optionally prefixed by \
+            an input name (input_ids:1xSxi64). All symbols (S, N...) name the same streaming \
+            dimension. Use commas for symbols containing an x (1,max_len,i64).")
 
         (@arg stream_axis: -s --("stream-axis") +takes_value
             "Set Axis number to stream upon (first is 0)")
@@ -86,6 +97,9 @@ fn main() {
         (@arg output_node: --("output-node") +takes_value
             "Override output nodes name (auto-detects otherwise).")
 
+        (@arg const_node: --("const-node") +takes_value +multiple number_of_values(1)
+            "Replace a node output by a constant (name=@file.npy).")
+
         (@arg skip_analyse: --("skip-analyse") "Skip analyse after model build")
         (@arg skip_type: --("skip-type") "Analyse as much as possible, but do not enforce full typing")
 
@@ -100,8 +114,18 @@ fn main() {
         (@arg list_ops: --("list-ops") "List all known operators")
     );
 
-    let compare = clap::SubCommand::with_name("compare")
-        .help("Compares the output of tract and tensorflow on randomly generated input.");
+    let compare =
+        clap::SubCommand::with_name("compare")
+            .help("Compares the output of tract to tensorflow or to reference activations.")
+            .arg(Arg::with_name("npz").takes_value(true).long("npz").help(
+                "Compare against reference activations from a npz file instead of tensorflow",
+            ))
+            .arg(
+                Arg::with_name("tolerance")
+                    .takes_value(true)
+                    .long("tolerance")
+                    .help("Maximum relative error in npz comparison [default: 1e-4]"),
+            );
     app = app.subcommand(output_options(compare));
 
     let dump = clap::SubCommand::with_name("dump")
@@ -110,7 +134,7 @@ fn main() {
             Arg::with_name("assert-output")
                 .takes_value(true)
                 .long("assert-output")
-                .help("Fact to check the ouput tensor against (@filename, or 3x4xf32)"),
+                .help("Fact to check the ouput tensor against (@file.npy, or 3x4xf32)"),
         )
         .arg(
             Arg::with_name("assert-output-fact")
@@ -118,11 +142,28 @@ fn main() {
                 .long("assert-output-fact")
                 .help("Infered shape and datum type must match exactly this"),
         );
-    app = app.subcommand(output_options(dump));
+    app = app.subcommand(output_options(json_option(dump)));
+
+    let extract = clap::SubCommand::with_name("extract")
+        .help("Writes the sub-model between the input and output nodes, in its original format.")
+        .arg(
+            Arg::with_name("output")
+                .takes_value(true)
+                .long("output")
+                .short("o")
+                .required(true)
+                .help("Path of the extracted model"),
+        );
+    app = app.subcommand(extract);
 
     let draw = clap::SubCommand::with_name("draw");
     app = app.subcommand(output_options(draw));
 
+    let dot = clap::SubCommand::with_name("dot")
+        .help("Prints the graph in Graphviz DOT format.")
+        .arg(Arg::with_name("profile").long("profile").help("Color nodes by execution time"));
+    app = app.subcommand(dot);
+
     let profile =
         clap::SubCommand::with_name("profile")
             .help("Benchmarks tract on randomly generated input.")
@@ -138,12 +179,30 @@ fn main() {
                     .long("max-time")
                     .help("Sets the maximum execution time for each node (in ms) [default: 5000]."),
             )
+            .arg(
+                Arg::with_name("warmup")
+                    .takes_value(true)
+                    .long("warmup")
+                    .help("Untimed runs per thread before benching [default: 0]."),
+            )
+            .arg(
+                Arg::with_name("concurrency")
+                    .takes_value(true)
+                    .long("concurrency")
+                    .help("Number of threads running the bench [default: 1]."),
+            )
+            .arg(
+                Arg::with_name("peak-gflops")
+                    .takes_value(true)
+                    .long("peak-gflops")
+                    .help("Machine peak throughput, to spot underperforming nodes."),
+            )
             .arg(
                 Arg::with_name("buffering")
                     .short("b")
                     .help("Run the stream network without inner instrumentations"),
             );
-    app = app.subcommand(output_options(profile));
+    app = app.subcommand(output_options(json_option(profile)));
 
     let run = clap::SubCommand::with_name("run")
         .help("Run the graph")
@@ -151,18 +210,30 @@ fn main() {
             Arg::with_name("assert-output")
                 .takes_value(true)
                 .long("assert-output")
-                .help("Fact to check the ouput tensor against (@filename, or 3x4xf32)"),
+                .help("Fact to check the ouput tensor against (@file.npy, or 3x4xf32)"),
         )
         .arg(
             Arg::with_name("assert-output-fact")
                 .takes_value(true)
                 .long("assert-output-fact")
                 .help("Infered shape and datum type must match exactly this"),
+        )
+        .arg(
+            Arg::with_name("save-outputs")
+                .takes_value(true)
+                .long("save-outputs")
+                .help("Save the output tensors to a .npy (single output) or .npz file"),
+        )
+        .arg(
+            Arg::with_name("dump-all")
+                .takes_value(true)
+                .long("dump-all")
+                .help("Save every intermediate tensor as .npy to this directory"),
         );
     app = app.subcommand(output_options(run));
 
     let cost = clap::SubCommand::with_name("cost").help("Compute a cost on (some) operations.");
-    app = app.subcommand(output_options(cost));
+    app = app.subcommand(output_options(json_option(cost)));
 
     let optimize = clap::SubCommand::with_name("optimize").help("Optimize the graph");
     app = app.subcommand(output_options(optimize));
@@ -197,6 +268,10 @@ fn main() {
     }
 }
 
+fn json_option<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
+    command.arg(clap::Arg::with_name("json").long("json").help("Output a JSON report"))
+}
+
 fn output_options<'a, 'b>(command: clap::App<'a, 'b>) -> clap::App<'a, 'b> {
     use clap::*;
     command
@@ -284,8 +359,7 @@ impl Parameters {
             #[cfg(feature = "onnx")]
             {
                 let onnx = tract_onnx::onnx();
-                let graph = onnx.proto_model_for_path(&name)?;
-                let tract = onnx.model_for_proto_model(&graph)?;
+                let (graph, tract) = onnx.proto_and_model_for_path(&name)?;
                 (SomeGraphDef::Onnx(graph), tract)
             }
         } else {
@@ -323,14 +397,42 @@ impl Parameters {
             raw_model.set_output_names(outputs)?;
         };
 
+        if let Some(consts) = matches.values_of("const_node") {
+            for spec in consts {
+                extract::set_const(&mut raw_model, spec)?;
+            }
+        };
+
         let machine_friendly = matches.is_present("machine_friendly");
 
         let inputs = if let Some(inputs) = matches.values_of("input") {
-            let mut vs = vec![];
+            let outlets = raw_model.input_outlets()?.to_vec();
+            let mut vs = vec![None; outlets.len()];
+            tensor::check_symbols(inputs.clone().map(|v| tensor::split_input_name(v).1))?;
             for (ix, v) in inputs.enumerate() {
-                let t = tensor::for_string(v)?;
-                let outlet = raw_model.input_outlets()?[ix];
-                vs.push(t.value.concretize());
+                let (name, spec) = tensor::split_input_name(v);
+                let ix = if let Some(name) = name {
+                    outlets.iter().position(|o| raw_model.node(o.node).name == name).ok_or_else(
+                        || {
+                            format!(
+                                "No input named {}, inputs are: {}",
+                                name,
+                                outlets
+                                    .iter()
+                                    .map(|o| &*raw_model.node(o.node).name)
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            )
+                        },
+                    )?
+                } else {
+                    ix
+                };
+                let outlet = *outlets
+                    .get(ix)
+                    .ok_or_else(|| format!("Model has {} input(s), got {}", outlets.len(), v))?;
+                let t = tensor::for_string(spec)?;
+                vs[ix] = t.value.concretize();
                 raw_model.set_outlet_fact(outlet, t)?;
             }
             Some(vs)
@@ -406,8 +508,8 @@ impl Parameters {
 }
 
 pub enum ProfilingMode {
-    Regular { max_iters: u64, max_time: u64 },
-    RegularBenching { max_iters: u64, max_time: u64 },
+    Regular { max_iters: u64, max_time: u64, peak_gflops: Option<f64> },
+    RegularBenching { max_iters: u64, max_time: u64, warmup: u64, concurrency: usize },
 }
 
 impl ProfilingMode {
@@ -423,9 +525,13 @@ impl ProfilingMode {
             .transpose()?
             .unwrap_or(DEFAULT_MAX_TIME);
         let mode = if matches.is_present("bench") {
-            ProfilingMode::RegularBenching { max_iters, max_time }
+            let warmup = matches.value_of("warmup").map(u64::from_str).transpose()?.unwrap_or(0);
+            let concurrency =
+                matches.value_of("concurrency").map(usize::from_str).transpose()?.unwrap_or(1);
+            ProfilingMode::RegularBenching { max_iters, max_time, warmup, concurrency }
         } else {
-            ProfilingMode::Regular { max_iters, max_time }
+            let peak_gflops = matches.value_of("peak-gflops").map(f64::from_str).transpose()?;
+            ProfilingMode::Regular { max_iters, max_time, peak_gflops }
         };
         Ok(mode)
     }
@@ -440,6 +546,7 @@ pub fn display_options_from_clap(matches: &clap::ArgMatches) -> CliResult<Displa
         node_name: matches.value_of("node_name").map(String::from),
         op_name: matches.value_of("op_name").map(String::from),
         successors: matches.value_of("successors").map(|id| id.parse().unwrap()),
+        json: matches.is_present("json"),
     })
 }
 
@@ -485,14 +592,30 @@ fn handle(matches: clap::ArgMatches) -> CliResult<()> {
     let mut params = Parameters::from_clap(&matches)?;
 
     match matches.subcommand() {
-        #[cfg(feature = "conform")]
-        ("compare", Some(m)) => compare::handle(params, display_options_from_clap(m)?),
-        #[cfg(not(feature = "conform"))]
-        ("compare", _) => bail!("Need conform feature to be able to run comparison"),
+        ("compare", Some(m)) => {
+            if let Some(npz) = m.value_of("npz") {
+                let tolerance = m.value_of("tolerance").map(f32::from_str).transpose()?;
+                compare::handle_npz(
+                    params,
+                    npz,
+                    tolerance.unwrap_or(1e-4),
+                    display_options_from_clap(m)?,
+                )
+            } else {
+                #[cfg(feature = "conform")]
+                {
+                    compare::handle(params, display_options_from_clap(m)?)
+                }
+                #[cfg(not(feature = "conform"))]
+                {
+                    bail!("Need conform feature to be able to run comparison with tensorflow")
+                }
+            }
+        }
 
         ("run", Some(m)) => {
             params.assertions = Some(Assertions::from_clap(m)?);
-            run::handle(params)
+            run::handle(params, m.value_of("save-outputs"), m.value_of("dump-all"))
         }
 
         ("optimize-check", Some(m)) => {
@@ -507,6 +630,10 @@ fn handle(matches: clap::ArgMatches) -> CliResult<()> {
             crate::draw::render(&params.tract_model, display_options_from_clap(m)?)
         }
 
+        ("dot", Some(m)) => dot::handle(params, m.is_present("profile")),
+
+        ("extract", Some(m)) => extract::handle(params, m.value_of("output").unwrap()),
+
         ("dump", Some(m)) => {
             params.assertions = Some(Assertions::from_clap(m)?);
             dump::handle(params, display_options_from_clap(m)?)
@@ -0,0 +1,299 @@
+//! Reading and writing tensors in NumPy `.npy` and `.npz` formats.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::CliResult;
+use tract_core::internal::*;
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+fn descr(dt: DatumType) -> CliResult<&'static str> {
+    Ok(match dt {
+        DatumType::Bool => "|b1",
+        DatumType::U8 => "|u1",
+        DatumType::U16 => "<u2",
+        DatumType::I8 => "|i1",
+        DatumType::I16 => "<i2",
+        DatumType::I32 => "<i4",
+        DatumType::I64 => "<i8",
+        DatumType::F16 => "<f2",
+        DatumType::F32 => "<f4",
+        DatumType::F64 => "<f8",
+        _ => bail!("{:?} can not be stored in npy", dt),
+    })
+}
+
+fn datum_type(descr: &str) -> CliResult<DatumType> {
+    Ok(match descr.trim_start_matches(|c| c == '<' || c == '>' || c == '|' || c == '=') {
+        "b1" => DatumType::Bool,
+        "u1" => DatumType::U8,
+        "u2" => DatumType::U16,
+        "i1" => DatumType::I8,
+        "i2" => DatumType::I16,
+        "i4" => DatumType::I32,
+        "i8" => DatumType::I64,
+        "f2" => DatumType::F16,
+        "f4" => DatumType::F32,
+        "f8" => DatumType::F64,
+        _ => bail!("Unsupported npy type {}", descr),
+    })
+}
+
+/// Value of `key` in the python dict literal of a npy header.
+fn header_value<'h>(header: &'h str, key: &str) -> CliResult<&'h str> {
+    let pattern = format!("'{}':", key);
+    let start =
+        header.find(&pattern).ok_or_else(|| format!("No {} in npy header", key))? + pattern.len();
+    let value = header[start..].trim_start();
+    let end = if value.starts_with("(") {
+        value.find(")").map(|i| i + 1)
+    } else if value.starts_with("'") {
+        value[1..].find("'").map(|i| i + 2)
+    } else {
+        value.find(|c| c == ',' || c == '}')
+    };
+    Ok(&value[..end.ok_or_else(|| format!("Invalid npy header {}", header))?])
+}
+
+fn from_npy_data<T: Datum>(shape: &[usize], fortran_order: bool, data: &[u8]) -> CliResult<Tensor> {
+    if fortran_order {
+        let reversed: Vec<usize> = shape.iter().rev().cloned().collect();
+        let tensor = unsafe { Tensor::from_raw::<T>(&reversed, data)? };
+        let array = tensor.into_array::<T>()?.reversed_axes();
+        Ok(ndarray::ArrayD::from_shape_vec(shape, array.iter().cloned().collect())?.into())
+    } else {
+        Ok(unsafe { Tensor::from_raw::<T>(shape, data)? })
+    }
+}
+
+/// Read a tensor in npy format.
+pub fn read_npy<R: Read>(mut reader: R) -> CliResult<Tensor> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[0..6] != MAGIC {
+        bail!("Not a npy file")
+    }
+    let header_len = if preamble[6] == 1 {
+        let mut len = [0u8; 2];
+        reader.read_exact(&mut len)?;
+        u16::from_le_bytes(len) as usize
+    } else {
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len)?;
+        u32::from_le_bytes(len) as usize
+    };
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+    let descr = header_value(&header, "descr")?.trim_matches('\'');
+    let dt = datum_type(descr)?;
+    let fortran_order = header_value(&header, "fortran_order")? == "True";
+    let shape = header_value(&header, "shape")?
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(",")
+        .map(|d| d.trim())
+        .filter(|d| d.len() > 0)
+        .map(|d| Ok(d.parse()?))
+        .collect::<CliResult<Vec<usize>>>()?;
+    let mut data = vec![0u8; shape.iter().product::<usize>() * dt.size_of()];
+    reader.read_exact(&mut data)?;
+    if descr.starts_with(">") {
+        data.chunks_mut(dt.size_of()).for_each(|item| item.reverse());
+    }
+    if dt == DatumType::Bool && data.iter().any(|&b| b > 1) {
+        bail!("Invalid bool value in npy data")
+    }
+    dispatch_copy!(self::from_npy_data(dt)(&shape, fortran_order, &data))
+}
+
+fn raw_data<T: Datum>(tensor: &Tensor) -> CliResult<&[u8]> {
+    let slice = tensor.as_slice::<T>()?;
+    Ok(unsafe {
+        std::slice::from_raw_parts(
+            slice.as_ptr() as *const u8,
+            slice.len() * std::mem::size_of::<T>(),
+        )
+    })
+}
+
+/// Write a tensor in npy format.
+pub fn write_npy<W: Write>(mut writer: W, tensor: &Tensor) -> CliResult<()> {
+    let tensor = if tensor.datum_type() == DatumType::TDim {
+        tensor.cast_to::<i64>()?
+    } else {
+        std::borrow::Cow::Borrowed(tensor)
+    };
+    let shape = match tensor.shape().len() {
+        0 => "()".to_string(),
+        1 => format!("({},)", tensor.shape()[0]),
+        _ => format!(
+            "({})",
+            tensor.shape().iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")
+        ),
+    };
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        descr(tensor.datum_type())?,
+        shape
+    );
+    // magic, version, header length, header and final \n are aligned on 64
+    while (MAGIC.len() + 4 + header.len() + 1) % 64 != 0 {
+        header.push(' ');
+    }
+    header.push('\n');
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    writer.write_all(dispatch_copy!(self::raw_data(tensor.datum_type())(&tensor))?)?;
+    Ok(())
+}
+
+/// Read all arrays from a npz file.
+pub fn read_npz(path: impl AsRef<Path>) -> CliResult<Vec<(String, Tensor)>> {
+    let mut archive =
+        zip::ZipArchive::new(fs::File::open(path)?).map_err(|e| format!("Reading npz: {:?}", e))?;
+    let mut arrays = vec![];
+    for ix in 0..archive.len() {
+        let file = archive.by_index(ix).map_err(|e| format!("Reading npz: {:?}", e))?;
+        let name = file.name().trim_end_matches(".npy").to_string();
+        arrays.push((name, read_npy(file)?));
+    }
+    Ok(arrays)
+}
+
+/// Write named arrays to a npz file.
+pub fn write_npz(path: impl AsRef<Path>, arrays: &[(String, &Tensor)]) -> CliResult<()> {
+    let mut zip = zip::ZipWriter::new(fs::File::create(path)?);
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, tensor) in arrays {
+        zip.start_file(format!("{}.npy", name), options)
+            .map_err(|e| format!("Writing npz: {:?}", e))?;
+        write_npy(&mut zip, tensor)?;
+    }
+    zip.finish().map_err(|e| format!("Writing npz: {:?}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(tensor: Tensor) {
+        let mut buffer = vec![];
+        write_npy(&mut buffer, &tensor).unwrap();
+        assert_eq!((buffer.len() - tensor.len() * tensor.datum_type().size_of()) % 64, 0);
+        assert_eq!(read_npy(&*buffer).unwrap(), tensor);
+    }
+
+    #[test]
+    fn npy_round_trips() {
+        round_trip(tensor1(&[true, false, true]));
+        round_trip(tensor1(&[0u8, 1, 255]));
+        round_trip(tensor1(&[0u16, 1, 65535]));
+        round_trip(tensor1(&[-128i8, 0, 127]));
+        round_trip(tensor1(&[-32768i16, 0, 32767]));
+        round_trip(tensor1(&[i32::min_value(), 0, i32::max_value()]));
+        round_trip(tensor1(&[i64::min_value(), 0, i64::max_value()]));
+        round_trip(tensor1(&[f16::from(-1.5f32), f16::from(0.0f32), f16::from(65504.0f32)]));
+        round_trip(tensor1(&[-1.5f32, 0.0, 3.25]));
+        round_trip(tensor1(&[-1.5f64, 0.0, 1e100]));
+        round_trip(tensor0(42f32));
+        round_trip(tensor1(&[] as &[f32]));
+        round_trip(tensor2(&[[1i32, 2, 3], [4, 5, 6]]));
+    }
+
+    #[test]
+    fn npy_header_shapes() {
+        let mut buffer = vec![];
+        write_npy(&mut buffer, &tensor1(&[1f32, 2.0])).unwrap();
+        assert!(String::from_utf8_lossy(&buffer).contains("'shape': (2,)"));
+        buffer.clear();
+        write_npy(&mut buffer, &tensor0(1f32)).unwrap();
+        assert!(String::from_utf8_lossy(&buffer).contains("'shape': ()"));
+    }
+
+    #[test]
+    fn tdim_is_written_as_i64() {
+        let mut buffer = vec![];
+        write_npy(&mut buffer, &tensor1(&[TDim::from(2), TDim::from(3)])).unwrap();
+        assert_eq!(read_npy(&*buffer).unwrap(), tensor1(&[2i64, 3]));
+    }
+
+    fn npy(version: u8, header: &str, data: &[u8]) -> Vec<u8> {
+        let mut buffer = MAGIC.to_vec();
+        buffer.extend_from_slice(&[version, 0]);
+        if version == 1 {
+            buffer.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        } else {
+            buffer.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        }
+        buffer.extend_from_slice(header.as_bytes());
+        buffer.extend_from_slice(data);
+        buffer
+    }
+
+    #[test]
+    fn fortran_order() {
+        let data: Vec<u8> =
+            [1i32, 4, 2, 5, 3, 6].iter().flat_map(|i| i.to_le_bytes().to_vec()).collect();
+        let file = npy(1, "{'descr': '<i4', 'fortran_order': True, 'shape': (2, 3), }\n", &data);
+        assert_eq!(read_npy(&*file).unwrap(), tensor2(&[[1i32, 2, 3], [4, 5, 6]]));
+    }
+
+    #[test]
+    fn big_endian() {
+        let data: Vec<u8> =
+            [1.5f32, -2.0].iter().flat_map(|f| f.to_bits().to_be_bytes().to_vec()).collect();
+        let file = npy(1, "{'descr': '>f4', 'fortran_order': False, 'shape': (2,), }\n", &data);
+        assert_eq!(read_npy(&*file).unwrap(), tensor1(&[1.5f32, -2.0]));
+    }
+
+    #[test]
+    fn version_2_header() {
+        let data: Vec<u8> = [7i64].iter().flat_map(|i| i.to_le_bytes().to_vec()).collect();
+        let file = npy(2, "{'descr': '<i8', 'fortran_order': False, 'shape': (1,), }\n", &data);
+        assert_eq!(read_npy(&*file).unwrap(), tensor1(&[7i64]));
+    }
+
+    #[test]
+    fn invalid_bools() {
+        let file = npy(1, "{'descr': '|b1', 'fortran_order': False, 'shape': (2,), }\n", &[1, 2]);
+        assert!(read_npy(&*file).is_err());
+    }
+
+    #[test]
+    fn npz_round_trip() {
+        let path = std::env::temp_dir().join(format!("tract-npz-{}.npz", std::process::id()));
+        let a = tensor1(&[1f32, 2.0]);
+        let b = tensor0(3i64);
+        let c = tensor2(&[[true], [false]]);
+        write_npz(&path, &[("a".into(), &a), ("b".into(), &b), ("c".into(), &c)]).unwrap();
+        let arrays = read_npz(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(arrays, vec![("a".to_string(), a), ("b".to_string(), b), ("c".to_string(), c)]);
+    }
+
+    #[test]
+    fn compressed_npz() {
+        // as written by numpy.savez_compressed
+        let path = std::env::temp_dir().join(format!("tract-npz-z-{}.npz", std::process::id()));
+        let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, value) in &[("x", 1f32), ("y", 2f32)] {
+            zip.start_file(format!("{}.npy", name), options).unwrap();
+            write_npy(&mut zip, &tensor1(&[*value; 16])).unwrap();
+        }
+        zip.finish().unwrap();
+        let arrays = read_npz(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            arrays,
+            vec![("x".to_string(), tensor1(&[1f32; 16])), ("y".to_string(), tensor1(&[2f32; 16]))]
+        );
+    }
+}
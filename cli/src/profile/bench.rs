@@ -0,0 +1,207 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Instant as StdInstant;
+
+use crate::display_graph::DisplayOptions;
+use crate::errors::*;
+use crate::format::*;
+use crate::json::{BenchReport, DurationReport, LatencyReport, MemoryReport};
+use crate::rusage::{get_memory_usage, Duration, Instant};
+use crate::{Parameters, ProfilingMode, SomeModel};
+
+use tract_core::internal::*;
+
+use super::make_inputs_for_model;
+
+/// Measures of the iterations run by one thread.
+#[derive(Debug, Default)]
+struct Measures {
+    /// Latency of each iteration, in seconds.
+    latencies: Vec<f64>,
+    /// Resident memory after each iteration, in bytes.
+    resident_sizes: Vec<u64>,
+}
+
+/// Handles the `profile --bench` subcommand.
+pub fn handle(
+    params: Parameters,
+    profiling: ProfilingMode,
+    display_options: DisplayOptions,
+) -> CliResult<()> {
+    match params.tract_model {
+        SomeModel::Inference(ref m) => handle_t(m, &params, profiling, display_options),
+        SomeModel::Typed(ref m) => handle_t(m, &params, profiling, display_options),
+        SomeModel::Normalized(ref m) => handle_t(m, &params, profiling, display_options),
+        SomeModel::Pulsed(_, ref m) => handle_t(m, &params, profiling, display_options),
+    }
+}
+
+fn handle_t<TI: TensorInfo + Clone + Send + Sync + 'static>(
+    model: &Model<TI>,
+    params: &Parameters,
+    profiling: ProfilingMode,
+    display_options: DisplayOptions,
+) -> CliResult<()> {
+    let (max_iters, max_time, warmup, concurrency) =
+        if let ProfilingMode::RegularBenching { max_iters, max_time, warmup, concurrency } =
+            profiling
+        {
+            (max_iters, max_time, warmup, concurrency.max(1))
+        } else {
+            bail!("Expecting bench profile mode")
+        };
+
+    let model = Arc::new(model.clone());
+    let iters = Arc::new(AtomicU64::new(0));
+    let barrier = Arc::new(Barrier::new(concurrency + 1));
+    let threads = (0..concurrency)
+        .map(|_| {
+            let model = model.clone();
+            let iters = iters.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || -> CliResult<Measures> {
+                let setup = || -> CliResult<_> {
+                    let plan = SimplePlan::new(model.clone())?;
+                    let mut state = SimpleState::new(plan)?;
+                    let inputs = make_inputs_for_model(&model)?;
+                    for _ in 0..warmup {
+                        state.run(inputs.clone())?;
+                    }
+                    Ok((state, inputs))
+                };
+                // wait even if setup failed, or the other threads would hang
+                let setup = setup();
+                barrier.wait();
+                let (mut state, inputs) = setup?;
+                let start = StdInstant::now();
+                let mut measures = Measures::default();
+                while iters.fetch_add(1, Ordering::SeqCst) < max_iters
+                    && start.elapsed() < ::std::time::Duration::from_millis(max_time)
+                {
+                    let iter_start = StdInstant::now();
+                    state.run(inputs.clone())?;
+                    let elapsed = iter_start.elapsed();
+                    measures
+                        .latencies
+                        .push(elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1.0e-9);
+                    measures.resident_sizes.push(get_memory_usage()?.resident_size);
+                }
+                Ok(measures)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    info!("Starting bench itself");
+    barrier.wait();
+    let start = Instant::now();
+    let mut measures = Measures::default();
+    for thread in threads {
+        let m = thread.join().map_err(|_| "Bench thread panicked")??;
+        measures.latencies.extend(m.latencies);
+        measures.resident_sizes.extend(m.resident_sizes);
+    }
+    let iters = measures.latencies.len() as u64;
+    let dur = Duration::since(&start, iters);
+    if iters == 0 {
+        bail!("Bench did not run any iteration")
+    }
+
+    let report = BenchReport {
+        entire: DurationReport::from(dur),
+        warmup,
+        concurrency,
+        throughput: iters as f64 / dur.total_real,
+        latency: latency_report(&mut measures.latencies),
+        resident_size: MemoryReport {
+            first: measures.resident_sizes[0],
+            last: *measures.resident_sizes.last().unwrap(),
+            max: *measures.resident_sizes.iter().max().unwrap(),
+        },
+    };
+
+    if display_options.json {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| format!("{}", e))?);
+    } else if params.machine_friendly {
+        println!("real: {}", dur.avg_real());
+        println!("user: {}", dur.avg_user());
+        println!("sys: {}", dur.avg_sys());
+        println!("throughput: {}", report.throughput);
+        println!("p50: {}", report.latency.p50);
+        println!("p90: {}", report.latency.p90);
+        println!("p99: {}", report.latency.p99);
+        println!("resident_size_max: {}", report.resident_size.max);
+    } else {
+        println!(
+            "Bench ran {} times on {} thread(s), after {} warm-up run(s) per thread.",
+            iters, concurrency, warmup
+        );
+        println!("{}", dur_avg_multiline(dur));
+        println!("Throughput: {:.2} runs/s", report.throughput);
+        let l = &report.latency;
+        println!(
+            "Latency: min {}  p50 {}  p90 {}  p99 {}  max {}",
+            ms(l.min),
+            ms(l.p50),
+            ms(l.p90),
+            ms(l.p99),
+            ms(l.max)
+        );
+        let m = &report.resident_size;
+        println!("Resident memory: first {}  last {}  max {}", mb(m.first), mb(m.last), mb(m.max));
+    }
+
+    Ok(())
+}
+
+/// Summarizes latencies, sorting them in place.
+fn latency_report(latencies: &mut [f64]) -> LatencyReport {
+    latencies.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+    let percentile = |p: f64| {
+        let rank = (p * latencies.len() as f64).ceil() as usize;
+        latencies[rank.max(1).min(latencies.len()) - 1]
+    };
+    LatencyReport {
+        min: latencies[0],
+        mean: latencies.iter().sum::<f64>() / latencies.len() as f64,
+        p50: percentile(0.50),
+        p90: percentile(0.90),
+        p99: percentile(0.99),
+        max: latencies[latencies.len() - 1],
+    }
+}
+
+fn ms(seconds: f64) -> String {
+    format!("{:.3} ms", seconds * 1e3)
+}
+
+fn mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / 1e6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_percentiles() {
+        let mut latencies: Vec<f64> = (1..=100).rev().map(|x| x as f64).collect();
+        let report = latency_report(&mut latencies);
+        assert_eq!(report.min, 1.0);
+        assert_eq!(report.mean, 50.5);
+        assert_eq!(report.p50, 50.0);
+        assert_eq!(report.p90, 90.0);
+        assert_eq!(report.p99, 99.0);
+        assert_eq!(report.max, 100.0);
+    }
+
+    #[test]
+    fn latency_percentiles_of_few_runs() {
+        let report = latency_report(&mut [0.3, 0.1, 0.2]);
+        assert_eq!(report.min, 0.1);
+        assert_eq!(report.p50, 0.2);
+        assert_eq!(report.p90, 0.3);
+        assert_eq!(report.p99, 0.3);
+        assert_eq!(report.max, 0.3);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::rusage::Duration;
 use ansi_term::Color::*;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 use crate::errors::*;
 use crate::format::*;
@@ -10,7 +11,9 @@ use tract_core::internal::*;
 use crate::display_graph::DisplayOptions;
 use crate::{Parameters, ProfilingMode};
 
+mod bench;
 mod regular;
+pub use self::regular::make_inputs_for_model;
 //mod streaming;
 
 #[derive(Debug, Default)]
@@ -48,6 +51,7 @@ impl ProfileData {
         println!("Most time consuming operations:");
         let mut operations = HashMap::new();
         let mut counters = HashMap::new();
+        let mut flops = HashMap::new();
         for (node, dur) in &self.nodes {
             let node = &model.nodes()[*node];
             let mut cell =
@@ -59,6 +63,11 @@ impl ProfileData {
             cell.total_user += dur.avg_user();
             cell.counter = 1;
             *counters.entry(node.op.name().to_string()).or_insert(0) += 1;
+            if let Some(f) = node_flops(model, node) {
+                let cell = flops.entry(node.op.name().to_string()).or_insert((0.0, 0.0));
+                cell.0 += f;
+                cell.1 += dur.avg_real();
+            }
         }
         let mut operations: Vec<(&str, Duration)> =
             operations.iter().map(|(s, d)| (&**s, *d)).collect();
@@ -69,11 +78,59 @@ impl ProfileData {
                 .reverse()
         });
         for (operation, measure) in operations.iter().take(5) {
+            let throughput = flops
+                .get(*operation)
+                .map(|(f, t)| format!(" {}", gflops(*f, *t)))
+                .unwrap_or_default();
             println!(
-                "{:20} {:3} calls: {}",
+                "{:20} {:3} calls: {}{}",
                 Blue.bold().paint(*operation),
                 counters[&**operation],
-                dur_avg_oneline_ratio(*measure, sum)
+                dur_avg_oneline_ratio(*measure, sum),
+                throughput
+            );
+        }
+        Ok(())
+    }
+
+    /// Achieved throughput of the nodes whose cost is known, most time
+    /// consuming first. Nodes below a tenth of `peak` (in GFLOP/s) are
+    /// highlighted. Without a peak, the best node throughput is used.
+    pub fn print_throughput<TI: TensorInfo>(
+        &self,
+        model: &Model<TI>,
+        peak: Option<f64>,
+    ) -> CliResult<()> {
+        let mut nodes: Vec<(usize, f64, f64)> = self
+            .nodes
+            .iter()
+            .filter_map(|(&n, dur)| {
+                node_flops(model, &model.nodes()[n]).map(|f| (n, f, dur.avg_real()))
+            })
+            .filter(|(_, f, t)| *f > 0.0 && *t > 0.0)
+            .collect();
+        if nodes.is_empty() {
+            return Ok(());
+        }
+        nodes.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(::std::cmp::Ordering::Equal));
+        let best = nodes.iter().map(|(_, f, t)| f / t).fold(0.0, f64::max);
+        let (peak, origin) = match peak {
+            Some(p) => (p * 1e9, "given peak"),
+            None => (best, "best node"),
+        };
+        println!("Throughput ({} at {:.2} GFLOP/s):", origin, peak / 1e9);
+        for (n, f, t) in nodes.iter().take(10) {
+            let node = &model.nodes()[*n];
+            let ratio = f / t / peak;
+            let ratio = format!("{:5.1}%", ratio * 100.0);
+            let ratio =
+                if f / t < peak / 10.0 { Red.bold().paint(ratio) } else { Green.paint(ratio) };
+            println!(
+                "{:20} {:30} {} {} of peak",
+                Blue.bold().paint(node.op.name()),
+                node.name,
+                gflops(*f, *t),
+                ratio
             );
         }
         Ok(())
@@ -87,6 +144,32 @@ impl ProfileData {
     }
 }
 
+/// Floating point operations performed by a node, counting a FMA as two, if
+/// its cost can be computed.
+pub fn node_flops<TI: TensorInfo>(model: &Model<TI>, node: &Node<TI>) -> Option<f64> {
+    let facts = node
+        .inputs
+        .iter()
+        .map(|o| TypedTensorInfo::try_from(model.outlet_fact(*o).ok()?.to_tensor_fact()).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let facts: Vec<&TypedTensorInfo> = facts.iter().collect();
+    let mut flops = None;
+    for (cost, count) in node.op.cost(&*facts).ok()? {
+        let per_unit = match cost {
+            Cost::FMA(_) => 2.0,
+            Cost::FLOP(_) | Cost::Reduce(_) | Cost::Special(_) => 1.0,
+            _ => continue,
+        };
+        *flops.get_or_insert(0.0) += per_unit * count.to_integer().ok()? as f64;
+    }
+    flops
+}
+
+/// Formats a throughput, in GFLOP/s.
+pub fn gflops(flops: f64, seconds: f64) -> String {
+    format!("{:8.2} GFLOP/s", flops / seconds / 1e9)
+}
+
 /// Handles the `profile` subcommand.
 pub fn handle(
     params: Parameters,
@@ -95,6 +178,6 @@ pub fn handle(
 ) -> CliResult<()> {
     match &profiling {
         ProfilingMode::Regular { .. } => regular::handle(params, profiling, display_options),
-        ProfilingMode::RegularBenching { .. } => regular::handle_benching(params, profiling),
+        ProfilingMode::RegularBenching { .. } => bench::handle(params, profiling, display_options),
     }
 }
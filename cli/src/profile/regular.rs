@@ -15,15 +15,6 @@ use crate::tensor::make_inputs;
 
 use tract_core::internal::*;
 
-pub fn handle_benching(params: Parameters, profiling: ProfilingMode) -> CliResult<()> {
-    match &params.tract_model {
-        SomeModel::Inference(m) => handle_benching_t(m, &params, profiling),
-        SomeModel::Typed(m) => handle_benching_t(m, &params, profiling),
-        SomeModel::Normalized(m) => handle_benching_t(m, &params, profiling),
-        SomeModel::Pulsed(_, m) => handle_benching_t(m, &params, profiling),
-    }
-}
-
 pub fn make_inputs_for_model<TI: TensorInfo>(model: &Model<TI>) -> CliResult<TVec<Tensor>> {
     Ok(make_inputs(
         &*model
@@ -34,40 +25,6 @@ pub fn make_inputs_for_model<TI: TensorInfo>(model: &Model<TI>) -> CliResult<TVe
     )?)
 }
 
-fn handle_benching_t<TI: TensorInfo>(
-    model: &Model<TI>,
-    params: &Parameters,
-    profiling: ProfilingMode,
-) -> CliResult<()> {
-    let (max_iters, max_time) =
-        if let ProfilingMode::RegularBenching { max_iters, max_time } = profiling {
-            (max_iters, max_time)
-        } else {
-            bail!("Expecting bench profile mode")
-        };
-
-    let plan = SimplePlan::new(model)?;
-    let mut state = SimpleState::new(plan)?;
-    info!("Starting bench itself");
-    let mut iters = 0;
-    let start = Instant::now();
-    while iters < max_iters && start.elapsed_real() < (max_time as f64 * 1e-3) {
-        state.run(make_inputs_for_model(model)?)?;
-        iters += 1;
-    }
-    let dur = Duration::since(&start, iters);
-
-    if params.machine_friendly {
-        println!("real: {}", dur.avg_real());
-        println!("user: {}", dur.avg_user());
-        println!("sys: {}", dur.avg_sys());
-    } else {
-        println!("Bench ran {} times.\n{}", iters, dur_avg_multiline(dur));
-    }
-
-    Ok(())
-}
-
 pub fn handle(
     params: Parameters,
     profiling: ProfilingMode,
@@ -88,11 +45,12 @@ pub fn handle_t<TI: TensorInfo>(
     profiling: ProfilingMode,
     mut display_options: DisplayOptions,
 ) -> CliResult<()> {
-    let (max_iters, max_time) = if let ProfilingMode::Regular { max_iters, max_time } = profiling {
-        (max_iters, max_time)
-    } else {
-        bail!("Expecting regular profile mode")
-    };
+    let (max_iters, max_time, peak_gflops) =
+        if let ProfilingMode::Regular { max_iters, max_time, peak_gflops } = profiling {
+            (max_iters, max_time, peak_gflops)
+        } else {
+            bail!("Expecting regular profile mode")
+        };
 
     info!("Running entire network");
     let plan = SimplePlan::new(model)?;
@@ -113,6 +71,7 @@ pub fn handle_t<TI: TensorInfo>(
 
     let mut profile = ProfileData::default();
     let mut progress = ProgressBar::new(plan.order.len() as u64);
+    let show_progress = atty::is(atty::Stream::Stdout) && !display_options.json;
 
     if log_enabled!(Info) {
         println!();
@@ -123,7 +82,7 @@ pub fn handle_t<TI: TensorInfo>(
     for &n in &plan.order {
         let node = &model.nodes()[n];
 
-        if atty::is(atty::Stream::Stdout) {
+        if show_progress {
             progress.inc();
         }
 
@@ -158,6 +117,28 @@ pub fn handle_t<TI: TensorInfo>(
         profile.add(&node, measure)?;
     }
 
+    if show_progress {
+        progress.finish_print("");
+    }
+
+    if display_options.json {
+        let mut report = crate::json::ModelReport::new(&params.name, model, &display_options)?;
+        for (ix, measure) in profile.nodes.iter() {
+            if let Some(node) = report.node_mut(*ix) {
+                node.time = Some((*measure).into());
+                node.flops = crate::profile::node_flops(model, model.node(*ix));
+            }
+        }
+        let memory = crate::rusage::get_memory_usage()?;
+        report.profile = Some(crate::json::ProfileReport {
+            entire: entire.into(),
+            accounted: profile.summed().into(),
+            resident_size: memory.resident_size,
+            resident_size_max: memory.resident_size_max,
+        });
+        return report.print();
+    }
+
     if display_options == DisplayOptions::default() {
         display_options.node_ids = Some(profile.most_consuming_nodes()?);
     };
@@ -169,16 +150,17 @@ pub fn handle_t<TI: TensorInfo>(
     let sum = profile.summed();
     for (ix, measure) in profile.nodes.iter() {
         display_graph.add_node_label(*ix, dur_avg_oneline_ratio(*measure, sum))?;
-    }
-
-    if atty::is(atty::Stream::Stdout) {
-        progress.finish_print("");
+        if let Some(flops) = crate::profile::node_flops(model, model.node(*ix)) {
+            display_graph.add_node_label(*ix, crate::profile::gflops(flops, measure.avg_real()))?;
+        }
     }
 
     display_graph.render()?;
     println!();
 
     profile.print_most_consuming_ops(model)?;
+    println!();
+    profile.print_throughput(model, peak_gflops)?;
 
     println!("Entire network performance: {}", dur_avg_oneline(entire));
     println!("Accounted by ops: {}", dur_avg_oneline_ratio(profile.summed(), entire));
@@ -1,19 +1,45 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path};
+
 use crate::errors::*;
 use crate::{Parameters, SomeModel};
 use tract_core::internal::*;
 
-pub fn handle(params: Parameters) -> CliResult<()> {
-    let outputs = match &params.tract_model {
-        SomeModel::Inference(ref m) => run_regular_t(m, &params)?,
-        SomeModel::Typed(ref m) => run_regular_t(m, &params)?,
-        SomeModel::Normalized(ref m) => run_regular_t(m, &params)?,
-        SomeModel::Pulsed(_, m) => run_pulse_t(m, &params)?,
+pub fn handle(
+    params: Parameters,
+    save_outputs: Option<&str>,
+    dump_all: Option<&str>,
+) -> CliResult<()> {
+    let dump_all = dump_all.map(Path::new);
+    let (outputs, names) = match &params.tract_model {
+        SomeModel::Inference(ref m) => (run_regular_t(m, &params, dump_all)?, output_names(m)?),
+        SomeModel::Typed(ref m) => (run_regular_t(m, &params, dump_all)?, output_names(m)?),
+        SomeModel::Normalized(ref m) => (run_regular_t(m, &params, dump_all)?, output_names(m)?),
+        SomeModel::Pulsed(_, m) => {
+            if dump_all.is_some() {
+                bail!("--dump-all is not supported on pulsed models")
+            }
+            (run_pulse_t(m, &params)?, output_names(m)?)
+        }
     };
 
     for (ix, output) in outputs.iter().enumerate() {
         println!("output #{}\n{}\n", ix, output.dump(true)?);
     }
 
+    if let Some(path) = save_outputs {
+        if path.ends_with(".npz") {
+            let arrays: Vec<(String, &Tensor)> =
+                names.into_iter().zip(outputs.iter().map(|t| &**t)).collect();
+            crate::npy::write_npz(path, &*arrays)?;
+        } else if outputs.len() == 1 {
+            crate::npy::write_npy(std::fs::File::create(path)?, &outputs[0])?;
+        } else {
+            bail!("Model has {} outputs, save them to a .npz file", outputs.len())
+        }
+    }
+
     if let Some(asserts) = &params.assertions {
         if let Some(asserts) = &asserts.assert_outputs {
             crate::utils::check_outputs(&*outputs, &asserts)?;
@@ -28,9 +54,72 @@ pub fn handle(params: Parameters) -> CliResult<()> {
     Ok(())
 }
 
+/// Tensor name, in TensorFlow style ("node" or "node:slot").
+fn tensor_name(node: &str, slot: usize) -> String {
+    if slot == 0 {
+        node.to_string()
+    } else {
+        format!("{}:{}", node, slot)
+    }
+}
+
+fn output_names<TI: TensorInfo>(model: &Model<TI>) -> CliResult<Vec<String>> {
+    Ok(model
+        .output_outlets()?
+        .iter()
+        .map(|o| tensor_name(&model.node(o.node).name, o.slot))
+        .collect())
+}
+
+/// Save each node output to `dir`, as `<tensor name>.npy`, and list them in
+/// `dir/index.txt` with their node id, shape and datum type.
+///
+/// Tensors that can not be stored in npy, or whose name would escape `dir`,
+/// are skipped with a warning.
+fn dump_all_hook<'a, TI: TensorInfo>(
+    dir: &'a Path,
+) -> CliResult<impl FnMut(&Node<TI>, &[Arc<Tensor>]) -> TractResult<()> + 'a> {
+    fs::create_dir_all(dir)?;
+    let mut index = fs::File::create(dir.join("index.txt"))?;
+    Ok(move |node: &Node<TI>, outputs: &[Arc<Tensor>]| {
+        for (slot, tensor) in outputs.iter().enumerate() {
+            let name = tensor_name(&node.name, slot);
+            let relative = Path::new(&name);
+            if relative.components().any(|c| match c {
+                Component::Normal(_) | Component::CurDir => false,
+                _ => true,
+            }) {
+                warn!("Not dumping {}: name is not a relative path below the dump directory", name);
+                continue;
+            }
+            let mut buffer = vec![];
+            if let Err(e) = crate::npy::write_npy(&mut buffer, tensor) {
+                warn!("Not dumping {}: {}", name, e);
+                continue;
+            }
+            let path = dir.join(format!("{}.npy", name));
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, buffer)?;
+            writeln!(
+                index,
+                "{}\t{}\t{:?}\t{:?}\t{}",
+                node.id,
+                name,
+                tensor.shape(),
+                tensor.datum_type(),
+                path.display()
+            )?;
+        }
+        Ok(())
+    })
+}
+
 fn run_regular_t<TI: TensorInfo>(
     tract: &Model<TI>,
     params: &Parameters,
+    dump_all: Option<&Path>,
 ) -> CliResult<TVec<Arc<Tensor>>> {
     let plan = SimplePlan::new(tract)?;
     let mut inputs: TVec<Tensor> = tvec!();
@@ -44,7 +133,12 @@ fn run_regular_t<TI: TensorInfo>(
         }
     }
     info!("Running");
-    Ok(plan.run(inputs)?)
+    if let Some(dir) = dump_all {
+        let mut state = SimpleState::new(&plan)?;
+        Ok(state.run_with_hook(inputs, dump_all_hook(dir)?)?)
+    } else {
+        Ok(plan.run(inputs)?)
+    }
 }
 
 fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<Tensor>>> {
@@ -97,3 +191,39 @@ fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<T
     result.slice_axis_inplace(::ndarray::Axis(output_fact.axis), (..output_dim as usize).into());
     Ok(tvec!(result.into_arc_tensor()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_core::ops::identity::Identity;
+
+    #[test]
+    fn dump_all_stays_in_dir() {
+        let dir = std::env::temp_dir().join(format!("tract-dump-all-{}", std::process::id()));
+        let mut model = InferenceModel::default();
+        let input =
+            model.add_source("input", TensorFact::dt_shape(f32::datum_type(), [2])).unwrap();
+        let mut outputs = vec![];
+        for name in &["sub/dir", "../escape", "/absolute"] {
+            let node = model.add_node_default(*name, Identity).unwrap();
+            model.add_edge(OutletId::new(input, 0), InletId::new(node, 0)).unwrap();
+            outputs.push(OutletId::new(node, 0));
+        }
+        let strings = model.add_const("strings", rctensor1(&["a".to_string()])).unwrap();
+        outputs.push(OutletId::new(strings, 0));
+        model.set_output_outlets(&outputs).unwrap();
+        let plan = SimplePlan::new(&model).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let hook = dump_all_hook::<TensorFact>(&dir).unwrap();
+        state.run_with_hook(tvec!(tensor1(&[1f32, 2.0])), hook).unwrap();
+        let npy = |name: &str| dir.join(format!("{}.npy", name));
+        assert!(npy("input").exists());
+        assert!(npy("sub/dir").exists());
+        assert!(!npy("strings").exists());
+        assert!(!npy("../escape").exists());
+        assert!(!Path::new("/absolute.npy").exists());
+        let index = fs::read_to_string(dir.join("index.txt")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(index.lines().count(), 2);
+    }
+}
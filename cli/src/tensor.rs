@@ -5,37 +5,142 @@ use std::str::FromStr;
 use crate::CliResult;
 use tract_core::internal::*;
 
-pub fn parse_spec(size: &str) -> CliResult<TensorFact> {
-    let splits = size.split("x").collect::<Vec<_>>();
+fn parse_dt(dt: &str) -> Option<DatumType> {
+    let dt = match dt.to_lowercase().as_str() {
+        "f64" => DatumType::F64,
+        "f32" => DatumType::F32,
+        "f16" => DatumType::F16,
+        "i64" => DatumType::I64,
+        "i32" => DatumType::I32,
+        "i16" => DatumType::I16,
+        "i8" => DatumType::I8,
+        "u16" => DatumType::U16,
+        "u8" => DatumType::U8,
+        "bool" => DatumType::Bool,
+        _ => return None,
+    };
+    Some(dt)
+}
 
-    if splits.len() < 1 {
-        bail!("The <size> argument should be formatted as {size}x{...}x{type}.");
+/// Parses one dimension of a shape spec.
+///
+/// Integers are concrete dimensions, `_` and `?` are unknown dimensions, and
+/// any other name (`S`, `N`, `batch`...) is the streaming dimension. A range
+/// is only accepted when both its bounds are equal (`8..8`).
+fn parse_dim(dim: &str) -> CliResult<DimFact> {
+    if let Ok(i) = dim.parse::<i32>() {
+        if i < 0 {
+            bail!("Negative dimension {}", dim)
+        }
+        return Ok(GenericFact::Only(i.into()));
+    }
+    if dim.contains("..") {
+        let mut bounds = dim.splitn(2, "..");
+        if let (Ok(low), Ok(high)) =
+            (bounds.next().unwrap().parse::<usize>(), bounds.next().unwrap().parse::<usize>())
+        {
+            if low == high {
+                return Ok(GenericFact::Only(low.into()));
+            }
+        }
+        bail!("Dimension ranges are not supported ({}), use a symbol like S instead", dim)
     }
+    if dim == "_" || dim == "?" {
+        return Ok(GenericFact::Any);
+    }
+    if is_symbol(dim) {
+        return Ok(GenericFact::Only(TDim::s()));
+    }
+    bail!("Invalid dimension {}", dim)
+}
 
-    let (datum_type, shape) = if splits.last().unwrap().parse::<i32>().is_ok() {
-        (None, &*splits)
+fn is_symbol(dim: &str) -> bool {
+    dim.chars().all(|c| c.is_alphanumeric() || c == '_')
+        && dim.chars().next().map(|c| c.is_alphabetic()).unwrap_or(false)
+}
+
+/// Splits a shape and type spec in its dimensions, its optional datum type,
+/// and whether the rank is left open.
+///
+/// Dimensions are separated by `x`, or by `,` if the spec contains one, so
+/// that symbol names may contain an `x` (`1,max_len,i64`).
+fn split_spec(spec: &str) -> CliResult<(Vec<&str>, Option<DatumType>, bool)> {
+    if spec == "" {
+        bail!("The <size> argument should be formatted as {size}x{...}x{type}.");
+    }
+    let mut splits = if spec.contains(",") {
+        spec.split(",").collect::<Vec<_>>()
     } else {
-        let datum_type = match splits.last().unwrap().to_lowercase().as_str() {
-            "f64" => DatumType::F64,
-            "f32" => DatumType::F32,
-            "i32" => DatumType::I32,
-            "i8" => DatumType::I8,
-            "u8" => DatumType::U8,
-            _ => bail!("Type of the input should be f64, f32, i32, i8 or u8."),
-        };
-        (Some(datum_type), &splits[0..splits.len() - 1])
+        spec.split("x").collect::<Vec<_>>()
     };
 
-    let shape = shape.iter().map(|s| Ok(s.parse()?)).collect::<TractResult<Vec<TDim>>>()?;
+    let datum_type = parse_dt(splits.last().unwrap());
+    if datum_type.is_some() {
+        splits.pop();
+    }
+
+    let open = splits.last() == Some(&"..");
+    if open {
+        splits.pop();
+    }
+    Ok((splits, datum_type, open))
+}
+
+/// Parses a shape and type spec, like `1x3x224x224xf32`.
+///
+/// All parts are optional: `f32` only gives the type, `?x?x?` only the rank,
+/// and a trailing `..` leaves the rank open (`1x..xi64`).
+pub fn parse_spec(spec: &str) -> CliResult<TensorFact> {
+    let (splits, datum_type, open) = split_spec(spec)?;
+
+    let dims = splits.iter().map(|d| parse_dim(d)).collect::<CliResult<TVec<DimFact>>>()?;
 
-    if shape.iter().filter(|o| o.is_stream()).count() > 1 {
+    if dims.iter().filter(|d| d.concretize().map(|d| d.is_stream()).unwrap_or(false)).count() > 1 {
         bail!("The <size> argument doesn't support more than one streaming dimension.");
     }
 
-    if let Some(dt) = datum_type {
-        Ok(TensorFact::dt_shape(dt, shape))
-    } else {
-        Ok(TensorFact::shape(shape))
+    let shape =
+        if open || dims.is_empty() { ShapeFact::open(dims) } else { ShapeFact::closed(dims) };
+    let fact = TensorFact::shape(shape);
+    Ok(if let Some(dt) = datum_type { fact.with_datum_type(dt) } else { fact })
+}
+
+/// Checks that the input specs use at most one symbol name.
+///
+/// Every symbol stands for the streaming dimension, so giving the same name
+/// to dimensions of several inputs links them, and different names can not
+/// be told apart.
+pub fn check_symbols<'a>(specs: impl IntoIterator<Item = &'a str>) -> CliResult<()> {
+    let mut symbol: Option<&str> = None;
+    for spec in specs {
+        if spec.starts_with("@") {
+            continue;
+        }
+        let (splits, _, _) = split_spec(spec.split("=").next().unwrap())?;
+        for dim in splits {
+            if !is_symbol(dim) {
+                continue;
+            }
+            match symbol {
+                Some(s) if s != dim => {
+                    bail!("Only one symbolic dimension is supported, got {} and {}", s, dim)
+                }
+                _ => symbol = Some(dim),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits an optional input name from an input spec, as in
+/// `input_ids:1xSxi64` or `mask:@mask.npy`.
+pub fn split_input_name(value: &str) -> (Option<&str>, &str) {
+    if value.starts_with("@") {
+        return (None, value);
+    }
+    match value.find(':') {
+        Some(ix) => (Some(&value[..ix]), &value[ix + 1..]),
+        None => (None, value),
     }
 }
 
@@ -55,7 +160,7 @@ fn tensor_for_text_data(filename: &str) -> CliResult<Tensor> {
 
     let mut lines = data.lines();
     let proto = parse_spec(lines.next().ok_or("Empty data file")?)?;
-    let shape = proto.shape.concretize().unwrap();
+    let shape = proto.shape.concretize().ok_or("Text data needs a complete shape")?;
 
     let values = lines.flat_map(|l| l.split_whitespace()).collect::<Vec<&str>>();
 
@@ -66,12 +171,43 @@ fn tensor_for_text_data(filename: &str) -> CliResult<Tensor> {
 
     let shape: Vec<_> =
         shape.iter().map(|d| d.to_integer().map(|i| i as usize).unwrap_or(missing)).collect();
-    dispatch_copy!(parse_values(proto.datum_type.concretize().unwrap())(&*shape, values))
+    let dt = proto.datum_type.concretize().ok_or("Text data needs a type")?;
+    dispatch_copy!(parse_values(dt)(&*shape, values))
+}
+
+fn tensor_for_npz(filename: &str) -> CliResult<Tensor> {
+    let (filename, name) = match filename.rfind(".npz:") {
+        Some(ix) => (&filename[..ix + 4], Some(&filename[ix + 5..])),
+        None => (filename, None),
+    };
+    let mut arrays = crate::npy::read_npz(filename)?;
+    if let Some(name) = name {
+        let ix = arrays.iter().position(|a| a.0 == name).ok_or_else(|| {
+            format!(
+                "No array {} in {}, found: {}",
+                name,
+                filename,
+                arrays.iter().map(|a| &*a.0).collect::<Vec<_>>().join(", ")
+            )
+        })?;
+        Ok(arrays.swap_remove(ix).1)
+    } else if arrays.len() == 1 {
+        Ok(arrays.pop().unwrap().1)
+    } else {
+        bail!("{} contains {} arrays, pick one with {}:name", filename, arrays.len(), filename)
+    }
 }
 
 /// Parses the `data` command-line argument.
+///
+/// Accepts ONNX TensorProto (.pb), NumPy arrays (.npy, or .npz with an
+/// optional :name suffix to pick an array) and the text format.
 fn for_data(filename: &str) -> CliResult<TensorFact> {
-    let tensor = if filename.ends_with(".pb") {
+    let tensor = if filename.ends_with(".npy") {
+        crate::npy::read_npy(fs::File::open(filename)?)?
+    } else if filename.ends_with(".npz") || filename.contains(".npz:") {
+        tensor_for_npz(filename)?
+    } else if filename.ends_with(".pb") {
         #[cfg(feature = "onnx")]
         {
             let file = fs::File::open(filename)?;
@@ -98,10 +234,13 @@ pub fn for_string(value: &str) -> CliResult<TensorFact> {
             let value = split.next().unwrap().split(",");
             let dt =
                 spec.datum_type.concretize().ok_or("Must specify type when giving tensor value")?;
-            let shape = spec
-                .shape
-                .as_concrete_finite()?
-                .ok_or("Must specify concrete shape when giving tensor value")?;
+            // values given with a type only make a scalar
+            let shape = if spec.shape == ShapeFact::default() {
+                Some(tvec!())
+            } else {
+                spec.shape.as_concrete_finite()?
+            };
+            let shape = shape.ok_or("Must specify concrete shape when giving tensor value")?;
             let tensor = dispatch_copy!(parse_values(dt)(&*shape, value.collect()))?;
             Ok(tensor.into())
         } else {
@@ -152,11 +291,67 @@ pub fn random(sizes: Vec<usize>, datum_type: DatumType) -> Tensor {
     }
 
     match datum_type {
+        DatumType::Bool => make::<bool>(sizes),
         DatumType::F64 => make::<f64>(sizes),
         DatumType::F32 => make::<f32>(sizes),
+        DatumType::F16 => make::<f32>(sizes).cast_to::<f16>().unwrap().into_owned(),
+        DatumType::I64 => make::<i64>(sizes),
         DatumType::I32 => make::<i32>(sizes),
+        DatumType::I16 => make::<i16>(sizes),
         DatumType::I8 => make::<i8>(sizes),
+        DatumType::U16 => make::<u16>(sizes),
         DatumType::U8 => make::<u8>(sizes),
         _ => unimplemented!("missing type"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_only() {
+        assert_eq!(parse_spec("f32").unwrap(), TensorFact::dt(DatumType::F32));
+    }
+
+    #[test]
+    fn symbol() {
+        let fact = parse_spec("1xSxf32").unwrap();
+        assert_eq!(fact, TensorFact::dt_shape(DatumType::F32, shapefact!(1, S)));
+        assert_eq!(parse_spec("1xNxf32").unwrap(), fact);
+    }
+
+    #[test]
+    fn symbol_with_an_x() {
+        assert_eq!(
+            parse_spec("1,max_len,i64").unwrap(),
+            TensorFact::dt_shape(DatumType::I64, shapefact!(1, S))
+        );
+    }
+
+    #[test]
+    fn open_rank() {
+        let fact = parse_spec("1x..xi64").unwrap();
+        assert_eq!(fact, TensorFact::dt_shape(DatumType::I64, shapefact!(1; ..)));
+    }
+
+    #[test]
+    fn range() {
+        assert!(parse_spec("1x1..10xf32").is_err());
+        assert_eq!(
+            parse_spec("1x8..8xf32").unwrap(),
+            TensorFact::dt_shape(DatumType::F32, shapefact!(1, 8))
+        );
+    }
+
+    #[test]
+    fn shared_symbol() {
+        assert!(check_symbols(vec!["1xNxf32", "Nx4xf32", "@input.npy", "2xf32=1,2"]).is_ok());
+        assert!(check_symbols(vec!["1xSxf32", "1xNxf32"]).is_err());
+    }
+
+    #[test]
+    fn scalar_value() {
+        assert_eq!(for_string("f32=5").unwrap(), TensorFact::from(tensor0(5f32)));
+    }
+}
@@ -0,0 +1,149 @@
+#![allow(dead_code)]
+#[macro_use]
+extern crate criterion;
+#[macro_use]
+extern crate derive_new;
+extern crate ndarray;
+extern crate tract_core;
+use criterion::Criterion;
+
+use tract_core::internal::*;
+use tract_core::ops::cnn::ConvUnary;
+use tract_core::ops::cnn::PaddingSpec;
+use tract_core::ops::cnn::PaddingSpec::SameUpper as Same;
+use tract_core::ops::cnn::PaddingSpec::Valid;
+
+use std::convert::TryInto;
+
+#[derive(Debug, new)]
+struct Problem {
+    h: usize,
+    w: usize,
+    ci: usize,
+    kh: usize,
+    kw: usize,
+    co: usize,
+    stride: usize,
+    group: usize,
+    padding: PaddingSpec,
+}
+
+impl Problem {
+    pub fn image(&self) -> Tensor {
+        Tensor::from(ndarray::ArrayD::<f32>::zeros(&*self.image_shape()))
+    }
+
+    pub fn image_shape(&self) -> TVec<usize> {
+        tvec!(1, self.h, self.w, self.ci)
+    }
+
+    pub fn to_unary(&self) -> ConvUnary {
+        let kernel = Tensor::from(ndarray::Array4::<f32>::zeros((
+            self.kh,
+            self.kw,
+            self.ci,
+            self.co / self.group,
+        )));
+        let bias = Tensor::from(ndarray::Array1::<f32>::zeros(self.co));
+        let conv = tract_core::ops::cnn::Conv::new(
+            tract_core::ops::nn::DataFormat::NHWC,
+            tract_core::ops::cnn::KernelFormat::HWIO,
+            None,
+            Some(kernel.shape()[0..2].into()),
+            self.padding.clone(),
+            Some(tvec!(self.stride, self.stride)),
+            self.group,
+        );
+        let image_fact: TypedTensorInfo =
+            TensorFact::dt_shape(DatumType::F32, self.image_shape()).try_into().unwrap();
+        let kernel_fact = TypedTensorInfo::from(kernel);
+        let mut unary = conv.to_unary(&[&image_fact, &kernel_fact]).unwrap().unwrap();
+        unary.bias = Some(bias.into());
+        unary
+    }
+
+    fn fact(shape: &[usize]) -> TypedTensorInfo {
+        TensorFact::dt_shape(DatumType::F32, shape).try_into().unwrap()
+    }
+
+    /// The packed im2col pair is typed only, so the model is built typed.
+    pub fn to_im2col(&self) -> SimplePlan<TypedTensorInfo, TypedModel> {
+        let unary = self.to_unary();
+        let output_shape: TVec<usize> =
+            unary.full_output_shape.iter().map(|d| d.to_integer().unwrap() as usize).collect();
+        let (im2col, packed_shape, cvgemm) =
+            unary.to_boxed_im2col_pair::<f32>(&*self.image_shape()).unwrap();
+        let mut model = TypedModel::default();
+        model.add_source("input", Self::fact(&*self.image_shape())).unwrap();
+        model.chain("im2col", im2col, tvec!(Self::fact(&*packed_shape))).unwrap();
+        model.chain("gemm", cvgemm, tvec!(Self::fact(&*output_shape))).unwrap();
+        SimplePlan::new(model).unwrap()
+    }
+
+    /// The plan picked by codegen: direct for grouped convolutions, after an
+    /// explicit pad for SAME ones, depthwise kernels when there is one
+    /// channel per group.
+    pub fn to_optimized(&self) -> SimplePlan<TypedTensorInfo, TypedModel> {
+        let mut model = InferenceModel::default();
+        model.add_source_default("input").unwrap();
+        model.chain_default("conv", self.to_unary()).unwrap();
+        model.set_input_fact(0, TensorFact::dt_shape(DatumType::F32, self.image_shape())).unwrap();
+        SimplePlan::new(model.into_optimized().unwrap()).unwrap()
+    }
+}
+
+fn b(c: &mut Criterion, name: &str, pbs: Vec<Problem>) {
+    c.bench(
+        name,
+        criterion::ParameterizedBenchmark::new(
+            "im2col",
+            move |b, pb| {
+                let plan = pb.to_im2col();
+                let args = tvec!(pb.image().into());
+                b.iter(|| plan.run(args.clone()).unwrap())
+            },
+            pbs,
+        )
+        .with_function("optimized", move |b, pb| {
+            let plan = pb.to_optimized();
+            let args = tvec!(pb.image().into());
+            b.iter(|| plan.run(args.clone()).unwrap())
+        }),
+    );
+}
+
+fn group(c: &mut Criterion) {
+    let pbs = [1, 2, 4, 8, 16, 32]
+        .iter()
+        .map(|&g| Problem::new(28, 28, 128, 3, 3, 128, 1, g, Same))
+        .collect();
+    b(c, "group", pbs);
+}
+
+macro_rules! b {
+    ($id:ident, $($args:expr),*) => {
+        #[allow(non_snake_case)]
+        fn $id(c: &mut Criterion) {
+            b(c, stringify!($id), vec!(Problem::new($($args),*)));
+        }
+    }
+}
+
+b!(ResNeXt_conv2_3x3, 56, 56, 128, 3, 3, 128, 1, 32, Same);
+b!(ResNeXt_conv3_3x3_s2, 56, 56, 256, 3, 3, 256, 2, 32, Same);
+b!(ShuffleNet_g3_1x1, 28, 28, 240, 1, 1, 240, 1, 3, Valid);
+b!(ShuffleNet_g8_1x1, 28, 28, 384, 1, 1, 384, 1, 8, Valid);
+b!(MobileNet_dw_3x3, 112, 112, 32, 3, 3, 32, 1, 32, Same);
+b!(MobileNet_dw_3x3_s2, 112, 112, 64, 3, 3, 64, 2, 64, Same);
+
+criterion_group!(
+    benches,
+    group,
+    ResNeXt_conv2_3x3,
+    ResNeXt_conv3_3x3_s2,
+    ShuffleNet_g3_1x1,
+    ShuffleNet_g8_1x1,
+    MobileNet_dw_3x3,
+    MobileNet_dw_3x3_s2,
+);
+criterion_main!(benches);
@@ -70,6 +70,7 @@ extern crate num_integer;
 extern crate num_traits;
 #[macro_use]
 extern crate maplit;
+pub extern crate memmap;
 #[cfg(not(debug_assertions))]
 extern crate no_panic;
 #[macro_use]
@@ -0,0 +1,108 @@
+//! Graphviz rendering of models.
+
+use std::fmt::Write;
+
+use crate::internal::*;
+use crate::ops::konst::Const;
+
+/// Extra information to render in a DOT graph.
+#[derive(Debug, Clone, Default)]
+pub struct DotOptions {
+    /// Additional lines in node labels, by node id.
+    pub node_labels: HashMap<usize, Vec<String>>,
+    /// Fill colors (any Graphviz color), by node id.
+    pub node_colors: HashMap<usize, String>,
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+impl<TI: TensorInfo> Model<TI> {
+    /// Render the model as a Graphviz digraph.
+    pub fn to_dot(&self) -> TractResult<String> {
+        self.to_dot_with_options(&DotOptions::default())
+    }
+
+    /// Render the model as a Graphviz digraph, with extra labels and colors.
+    ///
+    /// Nodes show their name and op, with the tensor size for constants.
+    /// Edges show the output facts.
+    pub fn to_dot_with_options(&self, options: &DotOptions) -> TractResult<String> {
+        let mut dot = String::new();
+        writeln!(dot, "digraph model {{")?;
+        writeln!(dot, "  node [shape=box, style=\"rounded,filled\", fillcolor=white];")?;
+        for node in self.nodes() {
+            let mut label = vec![node.name.clone(), node.op().name().to_string()];
+            if let Some(konst) = node.op_as::<Const>() {
+                let value = konst.value();
+                label.push(format!(
+                    "{:?} {:?} ({} bytes)",
+                    value.shape(),
+                    value.datum_type(),
+                    value.shape().iter().product::<usize>() * value.datum_type().size_of()
+                ));
+            }
+            if let Some(extra) = options.node_labels.get(&node.id) {
+                label.extend(extra.iter().cloned());
+            }
+            let label = label.iter().map(|l| escape(l)).collect::<Vec<_>>().join("\\n");
+            write!(dot, "  n{} [label=\"{}\"", node.id, label)?;
+            if self.input_outlets()?.iter().any(|o| o.node == node.id) {
+                write!(dot, ", shape=invhouse")?;
+            }
+            if let Some(color) = options.node_colors.get(&node.id) {
+                write!(dot, ", fillcolor=\"{}\"", escape(color))?;
+            }
+            writeln!(dot, "];")?;
+        }
+        for node in self.nodes() {
+            for (ix, input) in node.inputs.iter().enumerate() {
+                let fact = &self.node(input.node).outputs[input.slot].fact;
+                let mut label = format!("{:?}", fact);
+                if node.inputs.len() > 1 {
+                    label = format!("#{} {}", ix, label);
+                }
+                writeln!(dot, "  n{} -> n{} [label=\"{}\"];", input.node, node.id, escape(&label))?;
+            }
+        }
+        for (ix, output) in self.output_outlets()?.iter().enumerate() {
+            let fact = &self.node(output.node).outputs[output.slot].fact;
+            writeln!(dot, "  output_{} [label=\"output #{}\", shape=house];", ix, ix)?;
+            writeln!(
+                dot,
+                "  n{} -> output_{} [label=\"{}\"];",
+                output.node,
+                ix,
+                escape(&format!("{:?}", fact))
+            )?;
+        }
+        writeln!(dot, "}}")?;
+        Ok(dot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math::Add;
+
+    #[test]
+    fn dot_for_add_const() {
+        let mut model = InferenceModel::default();
+        let a = model.add_source("a", TensorFact::dt_shape(DatumType::F32, shapefact!(3))).unwrap();
+        let b = model.add_const("b", rctensor1(&[1.0f32, 2.0, 3.0])).unwrap();
+        let add = model.add_node_default("add", Add::default()).unwrap();
+        model.add_edge(OutletId::new(a, 0), InletId::new(add, 0)).unwrap();
+        model.add_edge(OutletId::new(b, 0), InletId::new(add, 1)).unwrap();
+        let mut options = DotOptions::default();
+        options.node_colors.insert(add, "red".to_string());
+        let dot = model.to_dot_with_options(&options).unwrap();
+        assert!(dot.starts_with("digraph model {"));
+        assert!(dot.contains("n0 [label=\"a\\nSource\", shape=invhouse];"));
+        assert!(dot.contains("[3] F32 (12 bytes)"));
+        assert!(dot.contains("n2 [label=\"add\\nAdd::Binary\", fillcolor=\"red\"];"));
+        assert!(dot.contains("n1 -> n2 [label=\"#1 "));
+        assert!(dot.contains("n2 -> output_0"));
+    }
+}
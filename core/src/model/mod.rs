@@ -43,6 +43,7 @@ use std::collections::HashMap;
 use std::str;
 
 pub(crate) mod compact;
+pub mod dot;
 mod dsl;
 mod model;
 mod node;
@@ -50,6 +51,7 @@ pub mod order;
 mod patch;
 mod tensor_info;
 
+pub use self::dot::DotOptions;
 pub use self::dsl::*;
 pub use self::model::*;
 pub use self::node::*;
@@ -65,6 +67,8 @@ use crate::TractResult;
 pub type InferenceModel = Model<TensorFact>;
 /// Node for InferenceModel graph
 pub type InferenceNode = Node<TensorFact>;
+/// A ModelPatch for InferenceModel.
+pub type InferenceModelPatch = ModelPatch<TensorFact>;
 
 /// A model with completely determined types and shapes.
 pub type TypedModel = Model<TypedTensorInfo>;
@@ -115,6 +115,11 @@ impl ShapeInfo {
         })
     }
 
+    /// Number of elements in the tensor.
+    pub fn size(&self) -> TDim {
+        self.iter().product()
+    }
+
     /// Convert the shape to an array of extended dimensions.
     pub fn to_tvec(&self) -> TVec<TDim> {
         self.iter().collect::<TVec<TDim>>()
@@ -26,6 +26,11 @@ impl Op for AddDims {
         "AddDims".into()
     }
 
+    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        // the data is reused as is
+        Ok(tvec!())
+    }
+
     fn pulsify(
         &self,
         _source: &NormalizedModel,
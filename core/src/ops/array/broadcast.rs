@@ -15,6 +15,18 @@ impl Op for MultiBroadcastTo {
     fn name(&self) -> Cow<str> {
         "MultiBroadcastTo".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = tvec!(Cost::read(&inputs[0..1]));
+        if let Some(dims) = &inputs[1].konst {
+            let dims: TVec<TDim> =
+                dims.cast_to::<i64>()?.as_slice::<i64>()?.iter().map(|&d| d.to_dim()).collect();
+            let shape = crate::broadcast::multi_broadcast(&[dims, inputs[0].shape.to_tvec()])
+                .ok_or("incompatible shapes")?;
+            cost.push(Cost::write(inputs[0].datum_type, shape.iter().cloned().product()));
+        }
+        Ok(cost)
+    }
 }
 
 impl StatelessOp for MultiBroadcastTo {
@@ -38,6 +38,10 @@ impl Op for Concat {
         "Concat".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(Cost::copy(inputs))
+    }
+
     fn declutter(
         &self,
         model: &TypedModel,
@@ -153,6 +157,20 @@ impl<T> NormConcatSlice<T> {
     }
 }
 
+/// Copying the variable inputs and the constant slices to the output.
+fn concat_cost<'a, T: Datum>(
+    inputs: &[&TypedTensorInfo],
+    consts: impl Iterator<Item = ArrayViewD<'a, T>>,
+) -> TVec<(Cost, TDim)> {
+    let len = consts.map(|c| c.len()).sum::<usize>().to_dim();
+    let mut cost = Cost::copy(inputs);
+    let (_, bytes) = Cost::write(T::datum_type(), len);
+    cost.push((Cost::Params(T::datum_type()), len));
+    cost.push((Cost::MemoryRead, bytes));
+    cost.push((Cost::MemoryWrite, bytes));
+    cost
+}
+
 #[derive(new, Debug, Clone)]
 pub struct NormConcat<T> {
     axis: usize,
@@ -164,6 +182,10 @@ impl<T: Datum + Copy> Op for NormConcat<T> {
         format!("NormConcat<{:?}>", T::datum_type()).into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(concat_cost(inputs, self.slices.iter().filter_map(|s| s.as_const())))
+    }
+
     fn pulsify(
         &self,
         source: &NormalizedModel,
@@ -404,6 +426,14 @@ impl<T: Datum + Copy> Op for PulsedSameAxisConcat<T> {
     fn name(&self) -> Cow<str> {
         format!("PulsedSameAxisConcat<{:?}>", T::datum_type()).into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let slices = [self.pre_slice.view(), self.post_slice.view()];
+        let len = slices.iter().map(|s| s.len()).sum::<usize>().to_dim();
+        let mut cost = Cost::copy(inputs);
+        cost.push((Cost::Params(T::datum_type()), len));
+        Ok(cost)
+    }
 }
 
 impl<T: Datum + Copy> StatefullOp for PulsedSameAxisConcat<T> {
@@ -523,6 +553,14 @@ impl<T: Datum + Copy> Op for FixedConcat<T> {
     fn name(&self) -> Cow<str> {
         format!("FixedConcat<{:?}>", T::datum_type()).into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let consts = self.slices.iter().filter_map(|s| match s {
+            FixedConcatSlice::Const(c) => Some(c.view()),
+            FixedConcatSlice::Var(_) => None,
+        });
+        Ok(concat_cost(inputs, consts))
+    }
 }
 
 impl<T: Datum + Copy> InferenceRulesOp for FixedConcat<T> {
@@ -21,6 +21,10 @@ impl Op for ConstantLike {
     fn name(&self) -> Cow<str> {
         "ConstantLike".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(tvec!(Cost::write(inputs[0].datum_type, inputs[0].shape.size())))
+    }
 }
 
 impl StatelessOp for ConstantLike {
@@ -82,6 +86,11 @@ impl Op for EyeLike {
     fn name(&self) -> Cow<str> {
         "EyeLike".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = self.dt.unwrap_or(inputs[0].datum_type);
+        Ok(tvec!(Cost::write(dt, inputs[0].shape.size())))
+    }
 }
 
 impl StatelessOp for EyeLike {
@@ -22,6 +22,14 @@ impl Op for ConstantOfShape {
     fn name(&self) -> Cow<str> {
         "ConstantOfShape".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        if let Some(shape) = &inputs[0].konst {
+            let len: i64 = shape.cast_to::<i64>()?.as_slice::<i64>()?.iter().product();
+            return Ok(tvec!(Cost::write(self.value.datum_type(), (len as i32).to_dim())));
+        }
+        Ok(tvec!())
+    }
 }
 
 impl StatelessOp for ConstantOfShape {
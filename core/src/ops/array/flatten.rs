@@ -20,6 +20,11 @@ impl Op for Flatten {
     fn name(&self) -> Cow<str> {
         "Flatten".into()
     }
+
+    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        // the data is reused as is
+        Ok(tvec!())
+    }
 }
 
 impl StatelessOp for Flatten {
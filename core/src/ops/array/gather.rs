@@ -10,6 +10,13 @@ impl Op for Gather {
     fn name(&self) -> Cow<str> {
         "Gather".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let rank = inputs[0].shape.rank() as i64;
+        let axis = if self.axis < 0 { self.axis + rank } else { self.axis } as usize;
+        let len = inputs[0].shape.size() / inputs[0].shape.dim(axis) * inputs[1].shape.size();
+        Ok(super::gathered(inputs[0].datum_type, inputs[1], len))
+    }
 }
 
 impl Gather {
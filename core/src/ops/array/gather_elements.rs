@@ -0,0 +1,90 @@
+use super::scatter::{resolve_axis, resolve_index};
+use crate::internal::*;
+use ndarray::*;
+
+/// Element-wise gather along one axis: the output has the shape of indices.
+///
+/// Used for ONNX GatherElements.
+#[derive(Debug, Clone, new, Default)]
+pub struct GatherElements {
+    axis: i64,
+}
+
+impl GatherElements {
+    fn eval_t<T: Datum>(&self, data: &Tensor, indices: &Tensor) -> TractResult<Arc<Tensor>> {
+        let data = data.to_array_view::<T>()?;
+        let indices = indices.cast_to::<i64>()?;
+        let indices = indices.to_array_view::<i64>()?;
+        if data.ndim() != indices.ndim() {
+            bail!("GatherElements data and indices must have the same rank")
+        }
+        let axis = resolve_axis(self.axis, data.ndim())?;
+        let mut output = ArrayD::<T>::default(indices.shape());
+        for (coords, &ix) in indices.indexed_iter() {
+            let mut src: TVec<usize> = coords.slice().into();
+            src[axis] = resolve_index(ix, data.shape()[axis])?;
+            output[coords.slice()] = data[&*src].clone();
+        }
+        Ok(output.into_arc_tensor())
+    }
+}
+
+impl Op for GatherElements {
+    fn name(&self) -> Cow<str> {
+        "GatherElements".into()
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(super::gathered(inputs[0].datum_type, inputs[1], inputs[1].shape.size()))
+    }
+}
+
+impl StatelessOp for GatherElements {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (data, indices) = args_2!(inputs);
+        Ok(tvec!(dispatch_datum!(Self::eval_t(data.datum_type())(self, &data, &indices))?))
+    }
+}
+
+impl InferenceRulesOp for GatherElements {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, &inputs[1].rank)?;
+        s.equals(&outputs[0].shape, &inputs[1].shape)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gather_elements_axis_1() {
+        let g = GatherElements::new(1);
+        assert_eq!(
+            g.eval(tvec!(rctensor2(&[[1, 2], [3, 4]]), rctensor2(&[[0i64, 0], [1, 0]]))).unwrap(),
+            tvec!(rctensor2(&[[1, 1], [4, 3]]))
+        );
+    }
+
+    #[test]
+    fn gather_elements_axis_0_negative() {
+        let g = GatherElements::new(0);
+        assert_eq!(
+            g.eval(tvec!(
+                rctensor2(&[[1, 2, 3], [4, 5, 6], [7, 8, 9]]),
+                rctensor2(&[[-1i64, -2, 0], [-2, 0, 0]])
+            ))
+            .unwrap(),
+            tvec!(rctensor2(&[[7, 5, 3], [4, 2, 3]]))
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use super::scatter::resolve_index;
+use crate::internal::*;
+use ndarray::*;
+
+/// Gather slices of data addressed by the innermost dimension of indices.
+///
+/// Used for TensorFlow GatherNd and ONNX GatherND.
+#[derive(Debug, Clone, new, Default)]
+pub struct GatherNd {}
+
+impl GatherNd {
+    /// Coordinates prefixes (in data) of the slices to gather, one for each
+    /// index tuple.
+    pub(super) fn coords(
+        indices: &Tensor,
+        data_shape: &[usize],
+    ) -> TractResult<Vec<(TVec<usize>, TVec<usize>)>> {
+        let indices = indices.cast_to::<i64>()?;
+        let indices = indices.to_array_view::<i64>()?;
+        if indices.ndim() == 0 {
+            bail!("GatherNd/ScatterNd indices must be at least of rank 1")
+        }
+        let n = indices.shape()[indices.ndim() - 1];
+        if n > data_shape.len() {
+            bail!("Index tuples of length {} are too long for data of rank {}", n, data_shape.len())
+        }
+        ndarray::indices(&indices.shape()[0..indices.ndim() - 1])
+            .into_iter()
+            .map(|prefix| {
+                let mut coords = indices.view();
+                for &x in prefix.slice().iter() {
+                    coords.index_axis_inplace(Axis(0), x);
+                }
+                let coords = coords
+                    .iter()
+                    .zip(data_shape.iter())
+                    .map(|(&x, &d)| resolve_index(x, d))
+                    .collect::<TractResult<TVec<usize>>>()?;
+                Ok((coords, prefix.slice().into()))
+            })
+            .collect()
+    }
+
+    fn eval_t<T: Datum>(&self, data: &Tensor, indices: &Tensor) -> TractResult<Arc<Tensor>> {
+        let data = data.to_array_view::<T>()?;
+        let mut shape: TVec<usize> = indices.shape().into();
+        let n = shape.pop().unwrap_or(0);
+        shape.extend(data.shape()[n.min(data.ndim())..].iter().cloned());
+        let mut array = ArrayD::<T>::default(&*shape);
+        for (coords, prefix) in Self::coords(indices, data.shape())? {
+            let mut dst = array.view_mut();
+            for &x in prefix.iter() {
+                dst.index_axis_inplace(Axis(0), x);
+            }
+            let mut src = data.view();
+            for &x in coords.iter() {
+                src.index_axis_inplace(Axis(0), x);
+            }
+            dst.assign(&src);
+        }
+        Ok(array.into_arc_tensor())
+    }
+}
+
+impl Op for GatherNd {
+    fn name(&self) -> Cow<str> {
+        "GatherNd".into()
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let indices = inputs[1].shape.to_tvec();
+        let n = indices.last().map(|n| n.to_integer()).transpose()?.unwrap_or(0) as usize;
+        let len = indices[..indices.len().saturating_sub(1)].iter().cloned().product::<TDim>()
+            * inputs[0].shape.iter().skip(n).product::<TDim>();
+        Ok(super::gathered(inputs[0].datum_type, inputs[1], len))
+    }
+}
+
+impl StatelessOp for GatherNd {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (data, indices) = args_2!(inputs);
+        Ok(tvec!(dispatch_datum!(Self::eval_t(data.datum_type())(self, &data, &indices))?))
+    }
+}
+
+impl InferenceRulesOp for GatherNd {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.given(&inputs[1].rank, move |s, indices_rank| {
+            if indices_rank < 1 {
+                bail!("GatherNd indices must be at least of rank 1")
+            }
+            let indices_rank = indices_rank as usize;
+            for i in 0..(indices_rank - 1) {
+                s.equals(&outputs[0].shape[i], &inputs[1].shape[i])?;
+            }
+            s.given_2(
+                &inputs[1].shape[indices_rank - 1],
+                &inputs[0].rank,
+                move |s, n, data_rank| {
+                    if let Ok(n) = n.to_integer() {
+                        if n as i32 > data_rank {
+                            bail!("GatherNd index tuples are longer than data rank")
+                        }
+                        s.equals(
+                            &outputs[0].rank,
+                            (indices_rank as i32) - 1 + data_rank - n as i32,
+                        )?;
+                        for i in 0..(data_rank - n as i32) as usize {
+                            s.equals(
+                                &outputs[0].shape[indices_rank - 1 + i],
+                                &inputs[0].shape[n as usize + i],
+                            )?;
+                        }
+                    }
+                    Ok(())
+                },
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // https://www.tensorflow.org/api_docs/python/tf/gather_nd
+    #[test]
+    fn simple_indexing() {
+        let g = GatherNd::new();
+        assert_eq!(
+            g.eval(tvec!(rctensor2(&[[1, 2], [3, 4]]), rctensor2(&[[0, 0], [1, 1]]))).unwrap(),
+            tvec!(rctensor1(&[1, 4]))
+        );
+    }
+
+    #[test]
+    fn slice_indexing() {
+        let g = GatherNd::new();
+        assert_eq!(
+            g.eval(tvec!(rctensor2(&[[1, 2], [3, 4]]), rctensor2(&[[1], [0]]))).unwrap(),
+            tvec!(rctensor2(&[[3, 4], [1, 2]]))
+        );
+    }
+
+    #[test]
+    fn tensor_3d_1() {
+        let g = GatherNd::new();
+        let t = rctensor3(&[[[10, 20], [30, 40]], [[11, 21], [31, 41]]]);
+        assert_eq!(
+            g.eval(tvec!(t.clone(), rctensor2(&[[1]]))).unwrap(),
+            tvec!(rctensor3(&[[[11, 21], [31, 41]]]))
+        );
+    }
+
+    #[test]
+    fn tensor_3d_2() {
+        let g = GatherNd::new();
+        let t = rctensor3(&[[[10, 20], [30, 40]], [[11, 21], [31, 41]]]);
+        assert_eq!(
+            g.eval(tvec!(t.clone(), rctensor2(&[[0, 1], [1, 0]]))).unwrap(),
+            tvec!(rctensor2(&[[30, 40], [11, 21]]))
+        );
+    }
+
+    #[test]
+    fn tensor_3d_3() {
+        let g = GatherNd::new();
+        let t = rctensor3(&[[[10, 20], [30, 40]], [[11, 21], [31, 41]]]);
+        assert_eq!(
+            g.eval(tvec!(t.clone(), rctensor2(&[[0, 0, 1], [1, 0, 1]]))).unwrap(),
+            tvec!(rctensor1(&[20, 21]))
+        );
+    }
+
+    #[test]
+    fn negative_indices() {
+        let g = GatherNd::new();
+        assert_eq!(
+            g.eval(tvec!(rctensor2(&[[1, 2], [3, 4]]), rctensor2(&[[-1, 0]]))).unwrap(),
+            tvec!(rctensor1(&[3]))
+        );
+    }
+}
@@ -41,6 +41,17 @@
 /// ### Ours
 ///
 /// * Slice, unary, mandatory attrs are begin and end.
+///
+/// ## Gathering and scattering
+///
+/// * Gather, binary (data, indices), with an axis attribute (ONNX Gather)
+/// * GatherNd, binary (data, indices), indices innermost dimension addresses
+///     slices of data (TF GatherNd, ONNX GatherND)
+/// * GatherElements, binary (data, indices), with an axis attribute
+/// * ScatterElements, ternary (data, indices, updates), with an axis and a
+///     reduction attribute (ONNX Scatter and ScatterElements)
+/// * ScatterNd, ternary (data, indices, updates), with a reduction attribute
+///     (ONNX ScatterND, TF TensorScatterUpdate and friends)
 mod add_dims;
 mod broadcast;
 mod concat;
@@ -48,10 +59,15 @@ mod constant_like;
 mod constant_of_shape;
 mod flatten;
 mod gather;
+mod gather_elements;
+mod gather_nd;
 mod pad;
 mod permute_axes;
 mod reshape;
 mod rm_dims;
+mod scatter;
+mod scatter_elements;
+mod scatter_nd;
 mod shape;
 mod size;
 mod slice;
@@ -67,13 +83,26 @@ pub use self::constant_like::EyeLike;
 pub use self::constant_of_shape::ConstantOfShape;
 pub use self::flatten::Flatten;
 pub use self::gather::Gather;
+pub use self::gather_elements::GatherElements;
+pub use self::gather_nd::GatherNd;
 pub use self::pad::{Pad, PadMode};
 pub use self::permute_axes::PermuteAxes;
 pub use self::reshape::Reshape;
 pub use self::rm_dims::RmDims;
+pub use self::scatter::ScatterReduction;
+pub use self::scatter_elements::ScatterElements;
+pub use self::scatter_nd::ScatterNd;
 pub use self::shape::Shape;
 pub use self::size::Size;
 pub use self::slice::Slice;
 pub use self::split::Split;
 pub use self::squeeze::Squeeze;
 pub use self::tile::Tile;
+
+use crate::internal::*;
+
+/// Reading `indices`, and moving `len` elements of `dt` around.
+fn gathered(dt: DatumType, indices: &TypedTensorInfo, len: TDim) -> TVec<(Cost, TDim)> {
+    let (_, bytes) = Cost::write(dt, len);
+    tvec!((Cost::MemoryRead, Cost::read(&[indices]).1 + bytes), (Cost::MemoryWrite, bytes))
+}
@@ -90,6 +90,16 @@ impl Op for Pad {
         "Pad".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let len: TDim = inputs[0]
+            .shape
+            .iter()
+            .zip(self.pads.iter())
+            .map(|(d, &(a, b))| d + a + b)
+            .product();
+        Ok(tvec!(Cost::read(inputs), Cost::write(inputs[0].datum_type, len)))
+    }
+
     fn pulsify(
         &self,
         _source: &NormalizedModel,
@@ -223,6 +233,10 @@ impl<T: Datum + Copy> Op for PulsePad<T> {
     fn name(&self) -> Cow<str> {
         "Pad".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(Cost::copy(inputs))
+    }
 }
 
 impl<T: Datum + Copy> StatefullOp for PulsePad<T> {
@@ -39,6 +39,10 @@ impl Op for PermuteAxes {
         "PermuteAxes".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(Cost::copy(inputs))
+    }
+
     fn pulsify(
         &self,
         _source: &NormalizedModel,
@@ -40,6 +40,11 @@ impl Op for Reshape {
     fn name(&self) -> Cow<str> {
         "Reshape".into()
     }
+
+    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        // the data is reused as is
+        Ok(tvec!())
+    }
 }
 
 impl StatelessOp for Reshape {
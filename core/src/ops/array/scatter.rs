@@ -0,0 +1,155 @@
+use crate::internal::*;
+use ndarray::*;
+
+/// How a scatter operation combines updates with the values already in place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScatterReduction {
+    None,
+    Add,
+    Mul,
+    Min,
+    Max,
+}
+
+impl Default for ScatterReduction {
+    fn default() -> ScatterReduction {
+        ScatterReduction::None
+    }
+}
+
+impl ScatterReduction {
+    pub fn parse(s: &str) -> TractResult<ScatterReduction> {
+        Ok(match s {
+            "none" => ScatterReduction::None,
+            "add" => ScatterReduction::Add,
+            "mul" => ScatterReduction::Mul,
+            "min" => ScatterReduction::Min,
+            "max" => ScatterReduction::Max,
+            _ => bail!("Unsupported scatter reduction {}", s),
+        })
+    }
+
+    /// Apply updates to a copy of data.
+    ///
+    /// Each moves item is a pair of coordinate prefixes: the first one
+    /// addresses a sub-array of data, the second one the matching sub-array
+    /// of updates.
+    /// Copying data, and scattering the updates in it.
+    pub(super) fn cost(&self, inputs: &[&TypedTensorInfo]) -> TVec<(Cost, TDim)> {
+        let dt = inputs[0].datum_type;
+        let mut cost = tvec!(Cost::read(inputs), Cost::write(dt, inputs[0].shape.size()));
+        if *self != ScatterReduction::None {
+            cost.push((Cost::FLOP(dt), inputs[2].shape.size()));
+        }
+        cost
+    }
+
+    pub(super) fn scatter(
+        &self,
+        data: Arc<Tensor>,
+        updates: &Tensor,
+        moves: &[(TVec<usize>, TVec<usize>)],
+    ) -> TractResult<Arc<Tensor>> {
+        if data.datum_type() != updates.datum_type() {
+            bail!(
+                "Scatter data and updates must have the same type, got {:?} and {:?}",
+                data.datum_type(),
+                updates.datum_type()
+            );
+        }
+        let dt = data.datum_type();
+        if *self == ScatterReduction::None {
+            dispatch_datum!(Self::assign_t(dt)(data, updates, moves))
+        } else {
+            dispatch_numbers!(Self::reduce_t(dt)(self, data, updates, moves))
+        }
+    }
+
+    fn assign_t<T: Datum>(
+        data: Arc<Tensor>,
+        updates: &Tensor,
+        moves: &[(TVec<usize>, TVec<usize>)],
+    ) -> TractResult<Arc<Tensor>> {
+        scatter_t(data, updates, moves, |a: &mut T, b: &T| *a = b.clone())
+    }
+
+    fn reduce_t<T>(
+        &self,
+        data: Arc<Tensor>,
+        updates: &Tensor,
+        moves: &[(TVec<usize>, TVec<usize>)],
+    ) -> TractResult<Arc<Tensor>>
+    where
+        T: Datum + Copy + PartialOrd + std::ops::Add<Output = T> + std::ops::Mul<Output = T>,
+    {
+        match self {
+            ScatterReduction::Add => scatter_t(data, updates, moves, |a: &mut T, &b| *a = *a + b),
+            ScatterReduction::Mul => scatter_t(data, updates, moves, |a: &mut T, &b| *a = *a * b),
+            ScatterReduction::Min => scatter_t(data, updates, moves, |a: &mut T, &b| {
+                if b < *a {
+                    *a = b
+                }
+            }),
+            ScatterReduction::Max => scatter_t(data, updates, moves, |a: &mut T, &b| {
+                if b > *a {
+                    *a = b
+                }
+            }),
+            ScatterReduction::None => Self::assign_t::<T>(data, updates, moves),
+        }
+    }
+}
+
+fn scatter_t<T, F>(
+    data: Arc<Tensor>,
+    updates: &Tensor,
+    moves: &[(TVec<usize>, TVec<usize>)],
+    combine: F,
+) -> TractResult<Arc<Tensor>>
+where
+    T: Datum,
+    F: Fn(&mut T, &T),
+{
+    let mut output = data.into_tensor().into_array::<T>()?;
+    let updates = updates.to_array_view::<T>()?;
+    for (dst, src) in moves {
+        let mut dst_view = output.view_mut();
+        for &x in dst {
+            dst_view.index_axis_inplace(Axis(0), x);
+        }
+        let mut src_view = updates.view();
+        for &x in src {
+            src_view.index_axis_inplace(Axis(0), x);
+        }
+        if dst_view.shape() != src_view.shape() {
+            bail!(
+                "Scatter update shape mismatch: {:?} into {:?}",
+                src_view.shape(),
+                dst_view.shape()
+            );
+        }
+        Zip::from(&mut dst_view).and(&src_view).apply(|a, b| combine(a, b));
+    }
+    Ok(output.into_arc_tensor())
+}
+
+/// Resolve a possibly negative index against an axis dimension.
+pub(super) fn resolve_index(ix: i64, dim: usize) -> TractResult<usize> {
+    let resolved = if ix < 0 { ix + dim as i64 } else { ix };
+    if resolved < 0 || resolved >= dim as i64 {
+        bail!("Index {} is out of bounds for axis of size {}", ix, dim)
+    }
+    Ok(resolved as usize)
+}
+
+/// Resolve a possibly negative axis against a rank.
+pub(super) fn resolve_axis(axis: i64, rank: usize) -> TractResult<usize> {
+    let rank = rank as i64;
+    if 0 <= axis && axis < rank {
+        Ok(axis as usize)
+    } else if -rank <= axis && axis < 0 {
+        Ok((axis + rank) as usize)
+    } else {
+        bail!("Illegal combination of values for rank and axis: {} and {}", rank, axis)
+    }
+}
@@ -0,0 +1,127 @@
+use super::scatter::{resolve_axis, resolve_index, ScatterReduction};
+use crate::internal::*;
+use ndarray::Dimension;
+
+/// Element-wise scatter along one axis, the dual of GatherElements.
+///
+/// Used for ONNX Scatter and ScatterElements.
+#[derive(Debug, Clone, new, Default)]
+pub struct ScatterElements {
+    axis: i64,
+    reduction: ScatterReduction,
+}
+
+impl ScatterElements {
+    fn moves(
+        &self,
+        data_shape: &[usize],
+        indices: &Tensor,
+    ) -> TractResult<Vec<(TVec<usize>, TVec<usize>)>> {
+        let indices = indices.cast_to::<i64>()?;
+        let indices = indices.to_array_view::<i64>()?;
+        if data_shape.len() != indices.ndim() {
+            bail!("ScatterElements data and indices must have the same rank")
+        }
+        let axis = resolve_axis(self.axis, data_shape.len())?;
+        indices
+            .indexed_iter()
+            .map(|(coords, &ix)| {
+                let src: TVec<usize> = coords.slice().into();
+                let mut dst = src.clone();
+                dst[axis] = resolve_index(ix, data_shape[axis])?;
+                Ok((dst, src))
+            })
+            .collect()
+    }
+}
+
+impl Op for ScatterElements {
+    fn name(&self) -> Cow<str> {
+        "ScatterElements".into()
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(self.reduction.cost(inputs))
+    }
+}
+
+impl StatelessOp for ScatterElements {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (data, indices, updates) = args_3!(inputs);
+        if indices.shape() != updates.shape() {
+            bail!(
+                "ScatterElements indices and updates must have the same shape, got {:?} and {:?}",
+                indices.shape(),
+                updates.shape()
+            );
+        }
+        let moves = self.moves(data.shape(), &indices)?;
+        Ok(tvec!(self.reduction.scatter(data, &updates, &moves)?))
+    }
+}
+
+impl InferenceRulesOp for ScatterElements {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[2].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[0].rank, &inputs[1].rank)?;
+        s.equals(&inputs[1].shape, &inputs[2].shape)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // https://github.com/onnx/onnx/blob/master/docs/Operators.md#ScatterElements
+    #[test]
+    fn scatter_elements_axis_1() {
+        let op = ScatterElements::new(1, ScatterReduction::None);
+        assert_eq!(
+            op.eval(tvec!(
+                rctensor2(&[[1.0f32, 2.0, 3.0, 4.0, 5.0]]),
+                rctensor2(&[[1i64, 3]]),
+                rctensor2(&[[1.1f32, 2.1]])
+            ))
+            .unwrap(),
+            tvec!(rctensor2(&[[1.0f32, 1.1, 3.0, 2.1, 5.0]]))
+        );
+    }
+
+    #[test]
+    fn scatter_elements_axis_0() {
+        let op = ScatterElements::new(0, ScatterReduction::None);
+        assert_eq!(
+            op.eval(tvec!(
+                rctensor2(&[[0, 0, 0], [0, 0, 0], [0, 0, 0]]),
+                rctensor2(&[[1i64, 0, 2], [0, 2, 1]]),
+                rctensor2(&[[1, 2, 3], [4, 5, 6]])
+            ))
+            .unwrap(),
+            tvec!(rctensor2(&[[4, 2, 0], [1, 0, 6], [0, 5, 3]]))
+        );
+    }
+
+    #[test]
+    fn scatter_elements_add_duplicates() {
+        let op = ScatterElements::new(1, ScatterReduction::Add);
+        assert_eq!(
+            op.eval(tvec!(
+                rctensor2(&[[1.0f32, 2.0, 3.0, 4.0, 5.0]]),
+                rctensor2(&[[1i64, 1]]),
+                rctensor2(&[[1.5f32, 2.5]])
+            ))
+            .unwrap(),
+            tvec!(rctensor2(&[[1.0f32, 6.0, 3.0, 4.0, 5.0]]))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use super::scatter::ScatterReduction;
+use super::GatherNd;
+use crate::internal::*;
+
+/// Scatter slices of updates at the positions addressed by the innermost
+/// dimension of indices, the dual of GatherNd.
+///
+/// Used for ONNX ScatterND and TensorFlow TensorScatter* operators.
+#[derive(Debug, Clone, new, Default)]
+pub struct ScatterNd {
+    reduction: ScatterReduction,
+}
+
+impl Op for ScatterNd {
+    fn name(&self) -> Cow<str> {
+        "ScatterNd".into()
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(self.reduction.cost(inputs))
+    }
+}
+
+impl StatelessOp for ScatterNd {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (data, indices, updates) = args_3!(inputs);
+        let moves = GatherNd::coords(&indices, data.shape())?;
+        Ok(tvec!(self.reduction.scatter(data, &updates, &moves)?))
+    }
+}
+
+impl InferenceRulesOp for ScatterNd {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.equals(&inputs[2].datum_type, &inputs[0].datum_type)?;
+        s.equals(&outputs[0].shape, &inputs[0].shape)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // https://github.com/onnx/onnx/blob/master/docs/Operators.md#ScatterND
+    #[test]
+    fn scatter_nd_elements() {
+        let op = ScatterNd::new(ScatterReduction::None);
+        assert_eq!(
+            op.eval(tvec!(
+                rctensor1(&[1, 2, 3, 4, 5, 6, 7, 8]),
+                rctensor2(&[[4i64], [3], [1], [7]]),
+                rctensor1(&[9, 10, 11, 12])
+            ))
+            .unwrap(),
+            tvec!(rctensor1(&[1, 11, 3, 10, 9, 6, 7, 12]))
+        );
+    }
+
+    #[test]
+    fn scatter_nd_slices() {
+        let op = ScatterNd::new(ScatterReduction::None);
+        assert_eq!(
+            op.eval(tvec!(
+                rctensor3(&[[[1, 2], [3, 4]], [[5, 6], [7, 8]]]),
+                rctensor2(&[[1i64]]),
+                rctensor3(&[[[0, 0], [0, 0]]])
+            ))
+            .unwrap(),
+            tvec!(rctensor3(&[[[1, 2], [3, 4]], [[0, 0], [0, 0]]]))
+        );
+    }
+
+    #[test]
+    fn scatter_nd_mul() {
+        let op = ScatterNd::new(ScatterReduction::Mul);
+        assert_eq!(
+            op.eval(tvec!(
+                rctensor1(&[1, 2, 3, 4]),
+                rctensor2(&[[1i64], [1], [3]]),
+                rctensor1(&[2, 3, 4])
+            ))
+            .unwrap(),
+            tvec!(rctensor1(&[1, 12, 3, 16]))
+        );
+    }
+}
@@ -23,6 +23,11 @@ impl Op for Shape {
     fn name(&self) -> Cow<str> {
         "Shape".into()
     }
+
+    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        // only the input shape is looked at
+        Ok(tvec!())
+    }
 }
 
 impl StatelessOp for Shape {
@@ -21,6 +21,11 @@ impl Op for Size {
     fn name(&self) -> Cow<str> {
         "Size".into()
     }
+
+    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        // only the input shape is looked at
+        Ok(tvec!())
+    }
 }
 
 impl StatelessOp for Size {
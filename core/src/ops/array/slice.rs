@@ -30,6 +30,17 @@ impl Op for Slice {
         "Slice".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let len: TDim = inputs[0]
+            .shape
+            .iter()
+            .zip(self.prune.iter())
+            .map(|(d, &(a, b))| d - a - b)
+            .product();
+        let (_, bytes) = Cost::write(inputs[0].datum_type, len);
+        Ok(tvec!((Cost::MemoryRead, bytes), (Cost::MemoryWrite, bytes)))
+    }
+
     fn pulsify(
         &self,
         _source: &NormalizedModel,
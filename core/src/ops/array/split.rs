@@ -41,6 +41,10 @@ impl Op for Split {
     fn name(&self) -> Cow<str> {
         "Split".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(Cost::copy(inputs))
+    }
 }
 
 impl StatelessOp for Split {
@@ -34,6 +34,11 @@ impl Op for Squeeze {
         "Squeeze".into()
     }
 
+    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        // the data is reused as is
+        Ok(tvec!())
+    }
+
     fn declutter(
         &self,
         model: &TypedModel,
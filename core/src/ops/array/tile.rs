@@ -27,6 +27,16 @@ impl Op for Tile {
     fn name(&self) -> Cow<str> {
         "Tile".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = tvec!(Cost::read(&inputs[0..1]));
+        if let Some(multipliers) = &inputs[1].konst {
+            let multipliers = multipliers.cast_to::<i64>()?;
+            let factor: i64 = multipliers.as_slice::<i64>()?.iter().product();
+            cost.push(Cost::write(inputs[0].datum_type, inputs[0].shape.size() * factor as i32));
+        }
+        Ok(cost)
+    }
 }
 
 impl StatelessOp for Tile {
@@ -16,6 +16,10 @@ impl Op for Cast {
     fn name(&self) -> Cow<str> {
         "Cast".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(Cost::element_wise(inputs, tvec!(), inputs[0].shape.size(), self.to))
+    }
 }
 
 impl StatelessOp for Cast {
@@ -3,9 +3,8 @@ use ndarray::prelude::*;
 use num_traits::{AsPrimitive, Float};
 use std::iter::Sum;
 
-use crate::ops::cnn::pools::PoolSpec;
-use crate::ops::cnn::Patch;
-use crate::ops::nn::DataShape;
+use crate::ops::cnn::pools::{PoolScan, PoolSpec};
+use crate::ops::nn::{DataFormat, DataShape};
 
 #[derive(Debug, Clone, new, Default)]
 pub struct AvgPool {
@@ -19,7 +18,12 @@ impl AvgPool {
         usize: AsPrimitive<T>,
     {
         let (input_shape, patch, output_shape) = self.pool_spec.compute_geo(input_shape);
-        let op = AvgPoolFixed::<T>::new(patch, input_shape, output_shape, self.count_include_pad);
+        let op = AvgPoolFixed::<T>::new(
+            input_shape,
+            output_shape,
+            PoolScan::new(&patch),
+            self.count_include_pad,
+        );
         Ok(Box::new(op))
     }
 }
@@ -29,6 +33,14 @@ impl Op for AvgPool {
         "AvgPool".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        if let Some(shape) = inputs[0].shape.as_finite() {
+            let op = dispatch_floatlike!(AvgPool::to_fixed(inputs[0].datum_type)(self, shape))?;
+            return op.cost(inputs);
+        }
+        Ok(tvec!(Cost::read(inputs)))
+    }
+
     fn codegen(
         &self,
         model: &TypedModel,
@@ -73,13 +85,79 @@ pub struct AvgPoolFixed<T: Datum + Float + Sum>
 where
     usize: AsPrimitive<T>,
 {
-    patch: Patch,
     input_shape: DataShape,
     output_shape: DataShape,
+    scan: PoolScan,
     count_include_pad: bool,
     _casper: PhantomData<T>,
 }
 
+impl<T: Datum + Float + Sum> AvgPoolFixed<T>
+where
+    usize: AsPrimitive<T>,
+{
+    unsafe fn eval_t<F: AsRef<[isize]>>(&self, field: F, input: *const T, values: *mut T) {
+        let field = field.as_ref();
+        let (ishape, oshape) = (&self.input_shape, &self.output_shape);
+        let full_div = field.len().as_().recip();
+        let div = |valid: usize| {
+            if self.count_include_pad {
+                full_div
+            } else {
+                valid.as_().recip()
+            }
+        };
+        for n in 0..ishape.n() {
+            let input = input.offset((ishape.n_stride() * n) as isize);
+            let values = values.offset((oshape.n_stride() * n) as isize);
+            match ishape.fmt {
+                DataFormat::NHWC => {
+                    let channels = ishape.c();
+                    for &(o, center) in &self.scan.full {
+                        let field = field.iter().map(|&t| t + center);
+                        avg_hwc(channels, field, full_div, input, values.offset(o));
+                    }
+                    for (o, offsets) in &self.scan.partial {
+                        let field = offsets.iter().cloned();
+                        avg_hwc(channels, field, div(offsets.len()), input, values.offset(*o));
+                    }
+                }
+                DataFormat::NCHW => {
+                    for c in 0..ishape.c() {
+                        let input = input.offset((ishape.c_stride() * c) as isize);
+                        let values = values.offset((oshape.c_stride() * c) as isize);
+                        for &(o, center) in &self.scan.full {
+                            *values.offset(o) = sum_taps(field, input.offset(center)) * full_div;
+                        }
+                        for (o, offsets) in &self.scan.partial {
+                            *values.offset(*o) = sum_taps(offsets, input) * div(offsets.len());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn sum_taps<T: Float>(taps: &[isize], input: *const T) -> T {
+    taps.iter().fold(T::zero(), |acc, &t| acc + *input.offset(t))
+}
+
+/// Average over the taps of `channels` interleaved windows.
+#[inline(always)]
+unsafe fn avg_hwc<T: Float>(
+    channels: usize,
+    taps: impl Iterator<Item = isize> + Clone,
+    div: T,
+    input: *const T,
+    values: *mut T,
+) {
+    for c in 0..channels as isize {
+        *values.offset(c) = taps.clone().fold(T::zero(), |acc, t| acc + *input.offset(t + c)) * div;
+    }
+}
+
 impl<T: Datum + Float + Sum> Op for AvgPoolFixed<T>
 where
     usize: AsPrimitive<T>,
@@ -87,6 +165,21 @@ where
     fn name(&self) -> Cow<str> {
         format!("AvgPool::Fixed<{:?}>", T::datum_type()).into()
     }
+
+    fn info(&self) -> TractResult<Option<String>> {
+        Ok(Some(format!("{:?}", self.scan)))
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let output_len = self.output_shape.shape.iter().product::<usize>();
+        let kernel_len = self.scan.field.len();
+        Ok(tvec!(
+            (Cost::Reduce(T::datum_type()), (output_len * kernel_len).to_dim()),
+            (Cost::Special(T::datum_type()), output_len.to_dim()),
+            Cost::read(inputs),
+            Cost::write(T::datum_type(), output_len.to_dim())
+        ))
+    }
 }
 
 impl<T: Datum + Float + Sum> StatelessOp for AvgPoolFixed<T>
@@ -99,31 +192,13 @@ where
         let input_ptr = input.as_ptr();
 
         let mut values = unsafe { ArrayD::<T>::uninitialized(&*self.output_shape.shape) };
+        let values_ptr = values.as_mut_ptr();
         unsafe {
-            self.patch.visit_output(|visitor| {
-                let div = if self.count_include_pad {
-                    self.patch.standard_layout_data_field.len()
-                } else {
-                    visitor.valid_count()
-                };
-                let div = div.as_().recip();
-                for n in 0..self.input_shape.n() {
-                    let input_offset = self.input_shape.n_stride() * n;
-                    let output_offset = self.output_shape.n_stride() * n;
-                    for c in 0..self.input_shape.c() {
-                        let input_offset = input_offset + self.input_shape.c_stride() * c;
-                        let output_offset = output_offset + self.output_shape.c_stride() * c;
-                        let sum = visitor
-                            .valid_offsets()
-                            .map(|v| *input_ptr.offset(v + input_offset as isize))
-                            .sum::<T>();
-
-                        *values
-                            .as_mut_ptr()
-                            .offset(output_offset as isize + visitor.output_offset) = sum * div;
-                    }
-                }
-            });
+            match self.scan.field.len() {
+                4 => self.eval_t(self.scan.fixed_field::<[isize; 4]>(), input_ptr, values_ptr),
+                9 => self.eval_t(self.scan.fixed_field::<[isize; 9]>(), input_ptr, values_ptr),
+                _ => self.eval_t(&*self.scan.field, input_ptr, values_ptr),
+            }
         }
         Ok(tvec!(values.into_arc_tensor()))
     }
@@ -1,5 +1,6 @@
 use crate::internal::*;
 use crate::ops::cnn::Patch;
+use crate::ops::nn::DataFormat;
 use crate::ops::nn::DataShape;
 use ndarray::*;
 use std::iter::Sum;
@@ -24,12 +25,24 @@ where
         format!("Conv::DepthWise<{:?}>", T::datum_type()).into()
     }
 
-    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
         let n_output_points = self.patch.output_shape.iter().cloned().product::<usize>();
-        Ok(tvec!((
-            Cost::FMA(T::datum_type()),
-            (self.input_shape.n() * n_output_points * self.kernel_chw.len()).to_dim()
-        )))
+        let mut cost = tvec!(
+            (
+                Cost::FMA(T::datum_type()),
+                (self.input_shape.n() * n_output_points * self.kernel_chw.len()).to_dim()
+            ),
+            Cost::read(inputs),
+            Cost::write(
+                T::datum_type(),
+                self.output_shape.shape.iter().product::<usize>().to_dim()
+            )
+        );
+        cost.extend(Cost::params_len(T::datum_type(), self.kernel_chw.len()));
+        if let Some(bias) = &self.bias {
+            cost.extend(Cost::params_len(T::datum_type(), bias.len()));
+        }
+        Ok(cost)
     }
 }
 
@@ -92,3 +105,363 @@ where
         unreachable!()
     }
 }
+
+/// Depthwise convolution running the tract-linalg kernels. The input is
+/// padded explicitly, so every output point sees all the kernel taps.
+#[derive(CustomDebug, Clone)]
+pub struct PaddedDepthWise {
+    patch: Patch,
+    input_shape: DataShape,
+    output_shape: DataShape,
+    padded_hw: TVec<usize>,
+    padded_hw_strides: TVec<usize>,
+    #[debug(skip)]
+    kernel: Arc<Tensor>,
+    #[debug(skip)]
+    bias: Arc<Tensor>,
+    dw: Box<tract_linalg::DepthWise<f32>>,
+}
+
+impl PaddedDepthWise {
+    /// `kernel_chw` is [c, taps], with the taps in kernel layout order.
+    pub fn new(
+        patch: Patch,
+        input_shape: DataShape,
+        output_shape: DataShape,
+        kernel_chw: ArrayView2<f32>,
+        bias: Option<ArrayView1<f32>>,
+    ) -> TractResult<PaddedDepthWise> {
+        let channels = input_shape.c();
+        let padded_hw: TVec<usize> =
+            itertools::izip!(input_shape.hw_dims(), &patch.pad_before, &patch.pad_after)
+                .map(|(d, b, a)| d + b + a)
+                .collect();
+        let mut padded_hw_strides: TVec<usize> = tvec!(0; padded_hw.len());
+        let mut stride = if input_shape.fmt == DataFormat::NHWC { channels } else { 1 };
+        for ax in (0..padded_hw.len()).rev() {
+            padded_hw_strides[ax] = stride;
+            stride *= padded_hw[ax];
+        }
+        let offsets = patch
+            .data_field
+            .outer_iter()
+            .map(|coords| {
+                coords
+                    .iter()
+                    .zip(patch.pad_before.iter())
+                    .zip(padded_hw_strides.iter())
+                    .map(|((&x, &pad), &stride)| (x + pad as isize) * stride as isize)
+                    .sum::<isize>()
+            })
+            .collect();
+        let inner_stride = *patch.spec.strides.last().unwrap();
+        let dw = (tract_linalg::ops().sdepth_wise)(offsets, inner_stride);
+        let kernel = if input_shape.fmt == DataFormat::NHWC {
+            kernel_chw.t().to_owned().into_arc_tensor()
+        } else {
+            kernel_chw.to_owned().into_arc_tensor()
+        };
+        let bias = bias
+            .map(|b| b.to_owned())
+            .unwrap_or_else(|| Array1::<f32>::zeros(channels))
+            .into_arc_tensor();
+        Ok(PaddedDepthWise {
+            patch,
+            input_shape,
+            output_shape,
+            padded_hw,
+            padded_hw_strides,
+            kernel,
+            bias,
+            dw,
+        })
+    }
+
+    fn padded(&self) -> bool {
+        self.patch.padded
+    }
+
+    /// Copies one image of the batch into a zero-padded buffer, laid out
+    /// without the N axis.
+    unsafe fn pad(&self, input: *const f32, buffer: &mut [f32]) {
+        let channels = self.input_shape.c();
+        let hw_strides = self.input_shape.hw_strides();
+        let c_stride = self.input_shape.c_stride() as isize;
+        let padded_c_stride =
+            if self.input_shape.fmt == DataFormat::NHWC { 1 } else { buffer.len() / channels };
+        for coords in ndarray::indices(self.input_shape.hw_dims()) {
+            let coords = coords.slice();
+            let src = itertools::izip!(coords, hw_strides)
+                .map(|(&x, &s)| (x * s) as isize)
+                .sum::<isize>();
+            let dst = itertools::izip!(coords, &self.patch.pad_before, &self.padded_hw_strides)
+                .map(|(&x, &p, &s)| (x + p) * s)
+                .sum::<usize>();
+            for c in 0..channels {
+                *buffer.get_unchecked_mut(dst + c * padded_c_stride) =
+                    *input.offset(src + c as isize * c_stride);
+            }
+        }
+    }
+
+    unsafe fn eval_image(&self, kernel: &[f32], bias: &[f32], image: *const f32, output: *mut f32) {
+        let channels = self.input_shape.c();
+        let (kernel, bias) = (kernel.as_ptr(), bias.as_ptr());
+        let strides = &self.patch.spec.strides;
+        let output_hw = self.output_shape.hw_dims();
+        let output_hw_strides = self.output_shape.hw_strides();
+        if self.input_shape.fmt == DataFormat::NHWC {
+            for coords in ndarray::indices(output_hw) {
+                let coords = coords.slice();
+                let i = itertools::izip!(coords, strides, &self.padded_hw_strides)
+                    .map(|(&x, &s, &ps)| x * s * ps)
+                    .sum::<usize>();
+                let o = itertools::izip!(coords, output_hw_strides)
+                    .map(|(&x, &s)| x * s)
+                    .sum::<usize>();
+                self.dw.hwc(
+                    channels,
+                    kernel,
+                    bias,
+                    image.offset(i as isize),
+                    output.offset(o as isize),
+                );
+            }
+        } else {
+            let rank = output_hw.len();
+            let taps = self.dw.offsets().len();
+            let padded_plane = self.padded_hw.iter().product::<usize>();
+            let output_c_stride = self.output_shape.c_stride();
+            for c in 0..channels {
+                for coords in ndarray::indices(&output_hw[..rank - 1]) {
+                    let coords = coords.slice();
+                    let i = c * padded_plane
+                        + itertools::izip!(coords, strides, &self.padded_hw_strides)
+                            .map(|(&x, &s, &ps)| x * s * ps)
+                            .sum::<usize>();
+                    let o = c * output_c_stride
+                        + itertools::izip!(coords, output_hw_strides)
+                            .map(|(&x, &s)| x * s)
+                            .sum::<usize>();
+                    self.dw.chw_row(
+                        output_hw[rank - 1],
+                        kernel.offset((c * taps) as isize),
+                        *bias.offset(c as isize),
+                        image.offset(i as isize),
+                        output.offset(o as isize),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Op for PaddedDepthWise {
+    fn name(&self) -> Cow<str> {
+        "Conv::PaddedDepthWise".into()
+    }
+
+    fn info(&self) -> TractResult<Option<String>> {
+        Ok(Some(format!("{:?}", self.dw)))
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let n_output_points = self.patch.output_shape.iter().cloned().product::<usize>();
+        let mut cost = tvec!(
+            (
+                Cost::FMA(f32::datum_type()),
+                (self.input_shape.n() * n_output_points * self.kernel.len()).to_dim()
+            ),
+            Cost::read(inputs),
+            Cost::write(
+                f32::datum_type(),
+                self.output_shape.shape.iter().product::<usize>().to_dim()
+            )
+        );
+        cost.extend(Cost::params(&self.kernel));
+        cost.extend(Cost::params(&self.bias));
+        Ok(cost)
+    }
+}
+
+impl StatelessOp for PaddedDepthWise {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let img = args_1!(inputs);
+        let img = img.to_array_view::<f32>()?;
+        let kernel = self.kernel.as_slice::<f32>()?;
+        let bias = self.bias.as_slice::<f32>()?;
+        let mut output = unsafe { ArrayD::<f32>::uninitialized(&*self.output_shape.shape) };
+        let mut buffer = if self.padded() {
+            vec![0.0f32; self.padded_hw.iter().product::<usize>() * self.input_shape.c()]
+        } else {
+            vec![]
+        };
+        for n in 0..self.input_shape.n() {
+            unsafe {
+                let image = img.as_ptr().offset((n * self.input_shape.n_stride()) as isize);
+                let image = if self.padded() {
+                    self.pad(image, &mut buffer);
+                    buffer.as_ptr()
+                } else {
+                    image
+                };
+                let output =
+                    output.as_mut_ptr().offset((n * self.output_shape.n_stride()) as isize);
+                self.eval_image(kernel, bias, image, output);
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for PaddedDepthWise {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        _s: &mut Solver<'r>,
+        _inputs: &'p [TensorProxy],
+        _outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::conv::KernelFormat;
+    use crate::ops::cnn::*;
+    use proptest::prelude::*;
+    use proptest::*;
+    use std::convert::TryFrom;
+
+    #[derive(Debug)]
+    struct Problem {
+        data_format: DataFormat,
+        padding: PaddingSpec,
+        stride: usize,
+        input: ArrayD<f32>,
+        kernel: Array4<f32>,
+        bias: Option<Array1<f32>>,
+    }
+
+    impl Problem {
+        fn check(&self) -> Result<(), TestCaseError> {
+            let channels = self.kernel.shape()[0];
+            let conv = Conv::new(
+                self.data_format,
+                KernelFormat::OIHW,
+                None,
+                Some(self.kernel.shape()[2..].into()),
+                self.padding.clone(),
+                Some(tvec!(self.stride, self.stride)),
+                channels,
+            );
+            let input = TypedTensorInfo::try_from(TensorFact::dt_shape(
+                f32::datum_type(),
+                self.input.shape(),
+            ))
+            .unwrap();
+            let kernel = TypedTensorInfo::from(Tensor::from(self.kernel.clone()));
+            let mut unary = conv.to_unary(&[&input, &kernel]).unwrap().unwrap();
+            unary.bias = self.bias.clone().map(|b| b.into());
+            let input = tvec!(Tensor::from(self.input.clone()).into());
+            let expected = unary.eval(input.clone()).unwrap();
+            let op = unary.to_depth_wise::<f32>(self.input.shape()).unwrap();
+            prop_assert_eq!(op.name(), "Conv::PaddedDepthWise");
+            let found = op.as_stateless().unwrap().eval(input).unwrap();
+            prop_assert!(
+                found[0].close_enough(&expected[0], true),
+                "found: {:?} expected: {:?}",
+                found[0],
+                expected[0]
+            );
+            Ok(())
+        }
+    }
+
+    impl Arbitrary for Problem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Problem>;
+        fn arbitrary_with(_args: ()) -> Self::Strategy {
+            (
+                prop_oneof!(Just(DataFormat::NCHW), Just(DataFormat::NHWC)),
+                prop_oneof!(Just(PaddingSpec::Valid), Just(PaddingSpec::SameUpper)),
+                1usize..3,
+                1usize..3,
+                1usize..12,
+                1usize..4,
+                1usize..4,
+                any::<bool>(),
+            )
+                .prop_flat_map(|(fmt, padding, stride, n, c, kh, kw, bias)| {
+                    (Just((fmt, padding, stride, n, c, kh, kw, bias)), kh..kh + 6, kw..kw + 12)
+                })
+                .prop_flat_map(|((fmt, padding, stride, n, c, kh, kw, bias), h, w)| {
+                    let shape = fmt.from_n_c_hw(n, c, [h, w].as_ref()).shape;
+                    let input = proptest::collection::vec(-5i8..5, shape.iter().product::<usize>());
+                    let kernel = proptest::collection::vec(-5i8..5, c * kh * kw);
+                    let bias = proptest::collection::vec(-5i8..5, if bias { c } else { 0 });
+                    (Just((fmt, padding, stride, shape, c, kh, kw)), input, kernel, bias)
+                })
+                .prop_map(
+                    |((data_format, padding, stride, shape, c, kh, kw), input, kernel, bias)| {
+                        let input = input.into_iter().map(|x| x as f32).collect();
+                        let kernel = kernel.into_iter().map(|x| x as f32).collect();
+                        Problem {
+                            data_format,
+                            padding,
+                            stride,
+                            input: ArrayD::from_shape_vec(&*shape, input).unwrap(),
+                            kernel: Array4::from_shape_vec((c, 1, kh, kw), kernel).unwrap(),
+                            bias: if bias.len() > 0 {
+                                Some(bias.into_iter().map(|x| x as f32).collect())
+                            } else {
+                                None
+                            },
+                        }
+                    },
+                )
+                .boxed()
+        }
+    }
+
+    #[test]
+    fn model_clones_share_kernel_and_bias() {
+        let conv = Conv::new(
+            DataFormat::NCHW,
+            KernelFormat::OIHW,
+            None,
+            None,
+            PaddingSpec::SameUpper,
+            None,
+            2,
+        );
+        let mut model = InferenceModel::default();
+        let input = model
+            .add_source("input", TensorFact::dt_shape(f32::datum_type(), tvec!(1, 2, 5, 5)))
+            .unwrap();
+        let kernel = model.add_const("kernel", Array4::<f32>::zeros((2, 1, 3, 3))).unwrap();
+        let bias = model.add_const("bias", Array1::<f32>::zeros(2)).unwrap();
+        let conv = model.add_node_default("conv", conv).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(conv, 0)).unwrap();
+        model.add_edge(OutletId::new(kernel, 0), InletId::new(conv, 1)).unwrap();
+        model.add_edge(OutletId::new(bias, 0), InletId::new(conv, 2)).unwrap();
+        model.set_output_outlets(&[OutletId::new(conv, 0)]).unwrap();
+        let model = model.into_optimized().unwrap();
+        let other = model.clone();
+        let dw = |model: &TypedModel| {
+            let op = model.nodes().iter().find_map(|n| n.op_as::<PaddedDepthWise>()).unwrap();
+            (op.kernel.clone(), op.bias.clone())
+        };
+        let ((k1, b1), (k2, b2)) = (dw(&model), dw(&other));
+        assert!(Arc::ptr_eq(&k1, &k2));
+        assert!(Arc::ptr_eq(&b1, &b2));
+    }
+
+    proptest! {
+        #[test]
+        fn padded_depth_wise_vs_im2col(pb in any::<Problem>()) {
+            pb.check()?
+        }
+    }
+}
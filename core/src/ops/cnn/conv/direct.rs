@@ -8,8 +8,11 @@ pub struct Direct {
     conv: Box<Conv<f32>>,
     input_shape: DataShape,
     output_shape: DataShape,
+    group: usize,
     #[debug(skip)]
-    packed_filters: Tensor,
+    packed_filters: Vec<Arc<Tensor>>,
+    #[debug(skip)]
+    bias: Option<ArrayD<f32>>,
 }
 
 impl Op for Direct {
@@ -23,10 +26,24 @@ impl Op for Direct {
 
     fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
         let batch = inputs[0].shape.dim(0);
-        Ok(tvec!((
-            Cost::FMA(f32::datum_type()),
-            batch * self.conv.n() * self.conv.co() * self.conv.k()
-        )))
+        let mut cost = tvec!(
+            (
+                Cost::FMA(f32::datum_type()),
+                batch * self.group * self.conv.n() * self.conv.co() * self.conv.k()
+            ),
+            Cost::read(inputs),
+            Cost::write(
+                f32::datum_type(),
+                self.output_shape.shape.iter().product::<usize>().to_dim()
+            )
+        );
+        for filters in &self.packed_filters {
+            cost.extend(Cost::params(filters));
+        }
+        if let Some(bias) = &self.bias {
+            cost.extend(Cost::params_len(f32::datum_type(), bias.len()));
+        }
+        Ok(cost)
     }
 
     fn rounding_errors(&self) -> bool {
@@ -40,16 +57,27 @@ impl StatelessOp for Direct {
         unsafe {
             let input = input.to_array_view::<f32>()?;
             let mut output = ArrayD::<f32>::uninitialized(&*self.output_shape.shape);
+            let ci_per_group = self.input_shape.c() / self.group;
+            let co_per_group = self.output_shape.c() / self.group;
             for n in 0..self.input_shape.n() {
                 let input = input.slice_axis(Axis(0), (n..=n).into());
                 let mut output = output.slice_axis_mut(Axis(0), (n..=n).into());
-                self.conv.conv(
-                    self.packed_filters.as_slice::<f32>()?.as_ptr(),
-                    input.as_ptr(),
-                    output.as_mut_ptr(),
-                    self.output_shape.c_stride() as isize,
-                    self.output_shape.w_stride() as isize,
-                );
+                for g in 0..self.group {
+                    self.conv.conv(
+                        self.packed_filters[g].as_slice::<f32>()?.as_ptr(),
+                        input
+                            .as_ptr()
+                            .offset((g * ci_per_group * self.input_shape.c_stride()) as isize),
+                        output
+                            .as_mut_ptr()
+                            .offset((g * co_per_group * self.output_shape.c_stride()) as isize),
+                        self.output_shape.c_stride() as isize,
+                        self.output_shape.w_stride() as isize,
+                    );
+                }
+            }
+            if let Some(ref bias) = self.bias {
+                output += bias;
             }
             Ok(tvec!(output.into_arc_tensor()))
         }
@@ -66,3 +94,254 @@ impl InferenceRulesOp for Direct {
         unreachable!()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::conv::KernelFormat;
+    use crate::ops::cnn::*;
+    use crate::ops::nn::DataFormat;
+    use proptest::prelude::*;
+    use proptest::*;
+    use std::convert::TryFrom;
+
+    #[derive(Debug)]
+    struct Problem {
+        data_format: DataFormat,
+        kernel_format: KernelFormat,
+        group: usize,
+        stride: usize,
+        padding: PaddingSpec,
+        input: ArrayD<f32>,
+        kernel: Array4<f32>,
+        bias: Option<Array1<f32>>,
+    }
+
+    impl Problem {
+        /// The OIHW kernel, or its [h, w, ci, co/group] HWIO equivalent.
+        fn kernel(&self) -> ArrayD<f32> {
+            if self.kernel_format == KernelFormat::OIHW {
+                return self.kernel.clone().into_dyn();
+            }
+            let (co, ci_per_group, kh, kw) = self.kernel.dim();
+            let co_per_group = co / self.group;
+            Array4::from_shape_fn(
+                (kh, kw, ci_per_group * self.group, co_per_group),
+                |(y, x, i, o)| {
+                    let g = i / ci_per_group;
+                    self.kernel[(g * co_per_group + o, i % ci_per_group, y, x)]
+                },
+            )
+            .into_dyn()
+        }
+
+        fn unary(&self, kernel_format: KernelFormat, kernel: ArrayD<f32>) -> ConvUnary {
+            let conv = crate::ops::cnn::Conv::new(
+                self.data_format,
+                kernel_format,
+                None,
+                Some(self.kernel.shape()[2..].into()),
+                self.padding.clone(),
+                Some(tvec!(self.stride, self.stride)),
+                self.group,
+            );
+            let input = TypedTensorInfo::try_from(TensorFact::dt_shape(
+                f32::datum_type(),
+                self.input.shape(),
+            ))
+            .unwrap();
+            let kernel = TypedTensorInfo::from(Tensor::from(kernel));
+            let mut unary = conv.to_unary(&[&input, &kernel]).unwrap().unwrap();
+            unary.bias = self.bias.clone().map(|b| b.into());
+            unary
+        }
+
+        fn check(&self) -> Result<(), TestCaseError> {
+            let input = tvec!(Tensor::from(self.input.clone()).into());
+            // the reference always runs on the OIHW kernel, so HWIO cases
+            // check the kernel layout handling too
+            let reference = self.unary(KernelFormat::OIHW, self.kernel.clone().into_dyn());
+            let expected = reference.eval(input.clone()).unwrap();
+            let unary = self.unary(self.kernel_format, self.kernel());
+            let found = if self.padding == PaddingSpec::Valid {
+                unary.to_direct(self.input.shape()).unwrap().eval(input).unwrap()
+            } else {
+                let (pad, _, direct) = unary.to_padded_direct(self.input.shape()).unwrap();
+                direct.eval(pad.eval(input).unwrap()).unwrap()
+            };
+            prop_assert!(
+                found[0].close_enough(&expected[0], true),
+                "found: {:?} expected: {:?}",
+                found[0],
+                expected[0]
+            );
+            Ok(())
+        }
+    }
+
+    impl Arbitrary for Problem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Problem>;
+        fn arbitrary_with(_args: ()) -> Self::Strategy {
+            (
+                (
+                    prop_oneof!(Just(DataFormat::NCHW), Just(DataFormat::NHWC)),
+                    prop_oneof!(Just(KernelFormat::OIHW), Just(KernelFormat::HWIO)),
+                ),
+                1usize..4,
+                1usize..3,
+                1usize..3,
+                1usize..4,
+                1usize..4,
+                1usize..4,
+                1usize..4,
+                any::<bool>(),
+                prop_oneof!(Just(PaddingSpec::Valid), Just(PaddingSpec::SameUpper)),
+            )
+                .prop_flat_map(
+                    |(fmt, group, ci_per_group, co_per_group, n, stride, kh, kw, bias, padding)| {
+                        (
+                            Just((
+                                (fmt, padding),
+                                group,
+                                ci_per_group,
+                                co_per_group,
+                                n,
+                                stride,
+                                kh,
+                                kw,
+                                bias,
+                            )),
+                            kh..kh + 5,
+                            kw..kw + 8,
+                        )
+                    },
+                )
+                .prop_flat_map(
+                    |(
+                        (
+                            ((fmt, kfmt), padding),
+                            group,
+                            ci_per_group,
+                            co_per_group,
+                            n,
+                            stride,
+                            kh,
+                            kw,
+                            bias,
+                        ),
+                        h,
+                        w,
+                    )| {
+                        let (ci, co) = (group * ci_per_group, group * co_per_group);
+                        let shape = fmt.from_n_c_hw(n, ci, [h, w].as_ref()).shape;
+                        let input =
+                            proptest::collection::vec(-5i8..5, shape.iter().product::<usize>());
+                        let kernel =
+                            proptest::collection::vec(-5i8..5, co * ci_per_group * kh * kw);
+                        let bias = proptest::collection::vec(-5i8..5, if bias { co } else { 0 });
+                        (
+                            Just((
+                                (fmt, kfmt),
+                                group,
+                                (stride, padding),
+                                shape,
+                                (co, ci_per_group, kh, kw),
+                            )),
+                            input,
+                            kernel,
+                            bias,
+                        )
+                    },
+                )
+                .prop_map(
+                    |(
+                        ((data_format, kernel_format), group, (stride, padding), shape, kshape),
+                        input,
+                        kernel,
+                        bias,
+                    )| {
+                        let input = input.into_iter().map(|x| x as f32).collect();
+                        let kernel = kernel.into_iter().map(|x| x as f32).collect();
+                        Problem {
+                            data_format,
+                            kernel_format,
+                            group,
+                            stride,
+                            padding,
+                            input: ArrayD::from_shape_vec(&*shape, input).unwrap(),
+                            kernel: Array4::from_shape_vec(kshape, kernel).unwrap(),
+                            bias: if bias.len() > 0 {
+                                Some(bias.into_iter().map(|x| x as f32).collect())
+                            } else {
+                                None
+                            },
+                        }
+                    },
+                )
+                .boxed()
+        }
+    }
+
+    #[test]
+    fn model_clones_share_packed_filters() {
+        let conv = crate::ops::cnn::Conv::new(
+            DataFormat::NCHW,
+            KernelFormat::OIHW,
+            None,
+            None,
+            PaddingSpec::Valid,
+            None,
+            1,
+        );
+        let mut model = InferenceModel::default();
+        let input = model
+            .add_source("input", TensorFact::dt_shape(f32::datum_type(), tvec!(1, 2, 5, 5)))
+            .unwrap();
+        let kernel = model.add_const("kernel", Array4::<f32>::zeros((3, 2, 2, 2))).unwrap();
+        let conv = model.add_node_default("conv", conv).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(conv, 0)).unwrap();
+        model.add_edge(OutletId::new(kernel, 0), InletId::new(conv, 1)).unwrap();
+        model.set_output_outlets(&[OutletId::new(conv, 0)]).unwrap();
+        let model = model.into_optimized().unwrap();
+        let other = model.clone();
+        let packed = |model: &TypedModel| {
+            model.nodes().iter().find_map(|n| n.op_as::<Direct>()).unwrap().packed_filters[0]
+                .clone()
+        };
+        assert!(Arc::ptr_eq(&packed(&model), &packed(&other)));
+    }
+
+    #[test]
+    fn same_grouped_conv_is_padded_direct() {
+        let conv = crate::ops::cnn::Conv::new(
+            DataFormat::NHWC,
+            KernelFormat::HWIO,
+            None,
+            None,
+            PaddingSpec::SameUpper,
+            None,
+            2,
+        );
+        let mut model = InferenceModel::default();
+        let input = model
+            .add_source("input", TensorFact::dt_shape(f32::datum_type(), tvec!(1, 5, 5, 4)))
+            .unwrap();
+        let kernel = model.add_const("kernel", Array4::<f32>::zeros((3, 3, 4, 2))).unwrap();
+        let conv = model.add_node_default("conv", conv).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(conv, 0)).unwrap();
+        model.add_edge(OutletId::new(kernel, 0), InletId::new(conv, 1)).unwrap();
+        model.set_output_outlets(&[OutletId::new(conv, 0)]).unwrap();
+        let model = model.into_optimized().unwrap();
+        assert!(model.nodes().iter().any(|n| n.op_is::<crate::ops::array::Pad>()));
+        let direct = model.nodes().iter().find(|n| n.op_is::<Direct>()).unwrap();
+        assert_eq!(direct.outputs[0].fact.shape, ShapeInfo::from(&[1, 5, 5, 4][..]));
+    }
+
+    proptest! {
+        #[test]
+        fn grouped_direct_vs_im2col(pb in any::<Problem>()) {
+            pb.check()?
+        }
+    }
+}
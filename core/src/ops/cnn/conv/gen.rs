@@ -167,11 +167,12 @@ impl InferenceRulesOp for Conv {
             } else {
                 &inputs[0].shape[1]
             };
-            let filter_i = match self.kernel_fmt {
-                KernelFormat::OIHW => &inputs[1].shape[1],
-                KernelFormat::HWIO => &inputs[1].shape[krank as usize - 2],
-            };
-            s.equals(input_c.bex(), self.group as i32 * filter_i.bex())
+            match self.kernel_fmt {
+                KernelFormat::OIHW => {
+                    s.equals(input_c.bex(), self.group as i32 * inputs[1].shape[1].bex())
+                }
+                KernelFormat::HWIO => s.equals(input_c, &inputs[1].shape[krank as usize - 2]),
+            }
         })?;
         s.given_2(&inputs[0].shape, &inputs[1].shape, move |s, ishape, kshape| {
             if kshape.iter().all(|d| d.to_integer().is_ok()) {
@@ -88,6 +88,13 @@ impl<T: Copy + Datum + Mul + Zero> Op for Im2Col<T> {
     fn info(&self) -> TractResult<Option<String>> {
         Ok(Some(format!("Pack: {:?}\nMatMul: {:?}", self.patch, self.b_pack)))
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(tvec!(
+            Cost::read(inputs),
+            Cost::write(T::datum_type(), self.output_shape.shape.iter().product::<usize>().to_dim())
+        ))
+    }
 }
 
 impl<T: Copy + Datum + Mul + Zero> StatelessOp for Im2Col<T> {
@@ -48,7 +48,7 @@ where
     pub n: usize,
     pub kernel_fmt: KernelFormat,
     #[debug(skip)]
-    pub packed_kernels: Vec<Tensor>,
+    pub packed_kernels: Vec<Arc<Tensor>>,
     pub bias: Option<ArrayD<T>>,
     pub group: usize,
     pub mm: Box<MatMul<T>>,
@@ -117,10 +117,18 @@ where
 
     fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
         let batch = inputs[0].shape.dim(0);
-        Ok(tvec!((
-            Cost::FMA(f32::datum_type()),
-            batch * self.group * self.mm.m() * self.mm.k() * self.mm.n()
-        )))
+        let mut cost = tvec!(
+            (Cost::FMA(f32::datum_type()), batch * self.group * self.mm.m() * self.mm.k() * self.mm.n()),
+            Cost::read(inputs),
+            Cost::write(D::datum_type(), self.output_shape.shape.iter().product::<usize>().to_dim())
+        );
+        for kernel in &self.packed_kernels {
+            cost.extend(Cost::params(kernel));
+        }
+        if let Some(bias) = &self.bias {
+            cost.extend(Cost::params_len(D::datum_type(), bias.len()));
+        }
+        Ok(cost)
     }
 }
 
@@ -5,14 +5,19 @@ mod im2col;
 mod mat_mat;
 mod unary;
 mod vec_mat;
+mod winograd;
 
 pub use self::direct::Direct;
 pub use self::gen::Conv;
 pub use self::unary::ConvUnary;
+pub use self::winograd::Winograd;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum KernelFormat {
+    /// [output channels, input channels / group, spatial dims...]
     OIHW,
+    /// [spatial dims..., input channels, output channels / group], as
+    /// TensorFlow depthwise kernels.
     HWIO,
 }
 
@@ -3,10 +3,11 @@ use ndarray::*;
 use crate::internal::*;
 use crate::model::*;
 
-use super::depth_wise::DepthWise;
+use super::depth_wise::{DepthWise, PaddedDepthWise};
 use super::im2col::Im2Col;
 use super::mat_mat::MatMat;
 use super::vec_mat::VecMat;
+use super::winograd::Winograd;
 use super::Conv;
 use crate::ops::cnn::conv::KernelFormat;
 use crate::ops::cnn::{PaddingSpec, Patch, PatchSpec};
@@ -75,10 +76,11 @@ impl ConvUnary {
         spec.into_patch()
     }
 
+    /// Input channels per group.
     fn input_channels(&self) -> usize {
         match self.kernel_fmt {
             KernelFormat::OIHW => self.kernel.shape()[1],
-            KernelFormat::HWIO => self.kernel.shape()[self.kernel.shape().len() - 2],
+            KernelFormat::HWIO => self.kernel.shape()[self.kernel.shape().len() - 2] / self.group,
         }
     }
 
@@ -87,11 +89,7 @@ impl ConvUnary {
     }
 
     pub fn to_direct(&self, input_full_shape: &[usize]) -> TractResult<super::Direct> {
-        assert!(
-            (0..input_full_shape.len() - 2).all(|ax| self.padding.valid_dim(ax))
-                && self.group == 1
-                && self.bias.is_none()
-        );
+        assert!((0..input_full_shape.len() - 2).all(|ax| self.padding.valid_dim(ax)));
 
         let patch = self.patch(input_full_shape);
         assert!(!patch.padded);
@@ -113,21 +111,99 @@ impl ConvUnary {
                     .map(move |x| x + (ici * channel_stride) as isize)
             })
             .collect();
-        let conv =
-            (tract_linalg::ops().sconv)(self.output_channels(), kernel_offsets, data_offsets);
+        let conv = (tract_linalg::ops().sconv)(
+            self.output_channels() / self.group,
+            kernel_offsets,
+            data_offsets,
+        );
 
         let kernel = self.kernel_as_group_o_ihw()?;
-        let mut packed = unsafe {
-            Tensor::uninitialized_aligned::<f32>(&[conv.packed_a_len()], conv.packed_a_alignment())?
-        };
-        conv.pack_a(
-            packed.as_slice_mut()?.as_mut_ptr(),
-            kernel.as_slice().unwrap().as_ptr(),
-            kernel.strides()[1],
-            kernel.strides()[2],
+        let packed = kernel
+            .outer_iter()
+            .map(|subkernel| {
+                let mut packed = unsafe {
+                    Tensor::uninitialized_aligned::<f32>(
+                        &[conv.packed_a_len()],
+                        conv.packed_a_alignment(),
+                    )?
+                };
+                conv.pack_a(
+                    packed.as_slice_mut()?.as_mut_ptr(),
+                    subkernel.as_ptr(),
+                    subkernel.strides()[0],
+                    subkernel.strides()[1],
+                );
+                Ok(Arc::new(packed))
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        let bias = self.bias_reshaped(&*output_shape.shape)?;
+
+        Ok(super::Direct::new(conv, input_shape, output_shape, self.group, packed, bias))
+    }
+
+    /// Explicit zero padding of the input, followed by a direct convolution
+    /// over the padded input. Returns the padding op, the padded input shape
+    /// and the convolution.
+    pub fn to_padded_direct(
+        &self,
+        input_full_shape: &[usize],
+    ) -> TractResult<(crate::ops::array::Pad, TVec<usize>, super::Direct)> {
+        let input_shape = self.data_format.shape(input_full_shape);
+        let kernel_spatial_shape =
+            &self.kernel.shape()[self.kernel_fmt.h_axis()..][..input_shape.hw_rank()];
+        let computed = self.padding.compute(
+            input_shape.hw_dims(),
+            kernel_spatial_shape,
+            &*self.dilations,
+            &*self.strides,
         );
+        let mut pads = vec![(0, 0); input_full_shape.len()];
+        for (ax, dim) in input_shape.hw_axes().zip(computed.iter()) {
+            pads[ax] = (dim.pad_before, dim.pad_after);
+        }
+        let padded: TVec<usize> =
+            input_full_shape.iter().zip(pads.iter()).map(|(d, (a, b))| d + a + b).collect();
+        let mut conv = self.clone();
+        conv.padding = PaddingSpec::Valid;
+        conv.full_input_shape = padded.iter().map(|d| d.to_dim()).collect();
+        let direct = conv.to_direct(&*padded)?;
+        let pad = crate::ops::array::Pad::new(pads, crate::ops::array::PadMode::Constant(0.0));
+        Ok((pad, padded, direct))
+    }
 
-        Ok(super::Direct::new(conv, input_shape, output_shape, packed))
+    /// Winograd F(2x2,3x3) for 2D 3x3 convolutions with unit strides and
+    /// dilations, or None if the convolution is not eligible.
+    pub fn to_winograd(&self, input_full_shape: &[usize]) -> TractResult<Option<Winograd>> {
+        let input_shape = self.data_format.shape(input_full_shape.into());
+        let kernel_spatial_shape =
+            &self.kernel.shape()[self.kernel_fmt.h_axis()..][..input_shape.hw_rank()];
+        if kernel_spatial_shape != [3, 3]
+            || self.kernel.datum_type() != f32::datum_type()
+            || self.group != 1
+            || self.strides.iter().any(|&s| s != 1)
+            || self.dilations.iter().any(|&d| d != 1)
+        {
+            return Ok(None);
+        }
+        let padding =
+            self.padding.compute(input_shape.hw_dims(), &[3, 3], &self.dilations, &self.strides);
+        if padding.iter().any(|p| p.output < 2) {
+            return Ok(None);
+        }
+        let output_shape = self.data_format.from_n_c_hw(
+            input_shape.n(),
+            self.output_channels(),
+            &*padding.iter().map(|p| p.output).collect::<TVec<_>>(),
+        );
+        let kernel = self.kernel_as_group_o_ihw::<f32>()?;
+        let kernel = kernel.into_shape((self.output_channels(), self.input_channels(), 3, 3))?;
+        Ok(Some(Winograd::new(
+            input_shape,
+            output_shape,
+            (padding[0].pad_before, padding[1].pad_before),
+            kernel.view(),
+            self.bias.clone(),
+        )?))
     }
 
     fn kernel_as_group_o_ihw<T: Datum>(&self) -> TractResult<Array3<T>> {
@@ -143,7 +219,7 @@ impl ConvUnary {
             KernelFormat::HWIO => {
                 let mut shape = kernel.shape().to_vec();
                 shape.insert(hw_rank, self.group);
-                shape[hw_rank] /= self.group;
+                shape[hw_rank + 1] /= self.group;
                 let kernel = kernel.into_shape(shape)?;
                 let mut permutation: Vec<usize> = vec![hw_rank, hw_rank + 2, hw_rank + 1];
                 permutation.extend(0..hw_rank);
@@ -195,7 +271,7 @@ impl ConvUnary {
         let bias = self.bias_reshaped(&*output_shape.shape)?;
 
         let kernel = self.kernel_as_group_o_ihw()?;
-        let mut packed_kernels: Vec<Tensor> = vec![];
+        let mut packed_kernels: Vec<Arc<Tensor>> = vec![];
 
         let (op2, b_pack): (Box<Op>, _) = if m > 1 {
             let mm = T::packed_mat_mul(m, k, n);
@@ -216,7 +292,7 @@ impl ConvUnary {
                     subkernel.strides()[0],
                     subkernel.strides()[1],
                 );
-                packed_kernels.push(packed);
+                packed_kernels.push(Arc::new(packed));
             }
             let conv_gemm = MatMat::new(
                 patch.clone(),
@@ -249,7 +325,7 @@ impl ConvUnary {
                     subkernel.as_ptr(),
                     subkernel.strides()[1],
                 );
-                packed_kernels.push(packed);
+                packed_kernels.push(Arc::new(packed));
             }
             let conv_gemm = VecMat::new(
                 patch.clone(),
@@ -327,7 +403,7 @@ impl ConvUnary {
         }
         let kernel_shape: TVec<usize> =
             copy_rm_nth(self.kernel.shape().clone(), geo_axis + self.kernel_fmt.h_axis());
-        let kernel = unsafe { self.kernel.clone().into_shape(&kernel_shape)? }; 
+        let kernel = unsafe { self.kernel.clone().into_shape(&kernel_shape)? };
         let new_op = ConvUnary {
             data_format: self.data_format,
             kernel_fmt: self.kernel_fmt,
@@ -355,6 +431,18 @@ impl ConvUnary {
             .map(|a| a.to_integer().map(|a| a as usize))
             .collect::<TractResult<TVec<usize>>>()?;
         let output_shape = self.data_format.shape(output_shape);
+        if T::datum_type() == f32::datum_type()
+            && self.input_channels() == 1
+            && input_shape.c() == output_shape.c()
+        {
+            let kernel = self.kernel_as_group_o_ihw::<f32>()?;
+            let kernel = kernel
+                .into_shape((self.output_channels(), patch.standard_layout_data_field.len()))?;
+            let bias = self.bias.as_ref().map(|b| b.to_array_view::<f32>()).transpose()?;
+            let bias = bias.map(|b| b.into_shape(self.output_channels())).transpose()?;
+            let op = PaddedDepthWise::new(patch, input_shape, output_shape, kernel.view(), bias)?;
+            return Ok(Box::new(op));
+        }
         let op = DepthWise::<T>::new(
             patch,
             input_shape,
@@ -384,11 +472,20 @@ impl Op for ConvUnary {
         let n_output_points: TDim = output_dims.iter().map(|d| d.output).product::<TDim>();
         let n_output_channels = self.output_channels().to_dim();
         let kernel_surface = kernel_spatial_shape.into_iter().product::<usize>().to_dim();
-        Ok(tvec!((
-            Cost::FMA(f32::datum_type()),
-            shape.n() * shape.c() * n_output_channels * n_output_points * kernel_surface
-                / self.group
-        )))
+        let mut cost = tvec!(
+            (
+                Cost::FMA(f32::datum_type()),
+                shape.n() * shape.c() * n_output_channels * n_output_points * kernel_surface
+                    / self.group
+            ),
+            Cost::read(inputs),
+            Cost::write(inputs[0].datum_type, shape.n() * n_output_channels * n_output_points)
+        );
+        cost.extend(Cost::params(&self.kernel));
+        if let Some(bias) = &self.bias {
+            cost.extend(Cost::params(bias));
+        }
+        Ok(cost)
     }
 
     fn declutter(
@@ -439,7 +536,7 @@ impl Op for ConvUnary {
             if self.kernel_fmt == KernelFormat::HWIO && self.data_format == DataFormat::NHWC {
                 use crate::ops::math::mat_mul::MatMulUnaryA;
                 let kernel_shape = &self.kernel.shape()[spatial_rank..];
-                let kernel = unsafe { self.kernel.clone().into_shape(&kernel_shape)? }; 
+                let kernel = unsafe { self.kernel.clone().into_shape(&kernel_shape)? };
                 return Ok(Some(TypedModelPatch::single_unary_op(
                     model,
                     node,
@@ -449,19 +546,39 @@ impl Op for ConvUnary {
         } else {
             if let Some(shape) = inputs[0].shape.as_finite() {
                 let dt = inputs[0].datum_type;
-                if (0..spatial_rank).all(|ax| self.padding.valid_dim(ax))
-                    && dt == f32::datum_type()
-                    && self.group == 1
-                    && self.bias.is_none()
-                {
-                    let op = self.to_direct(&*shape)?;
-                    return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
-                } else if self.group != 1 && self.group == self.output_channels() {
+                if dt == f32::datum_type() {
+                    if let Some(op) = self.to_winograd(&shape)? {
+                        return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
+                    }
+                }
+                if self.group != 1 && self.group == self.output_channels() {
                     return Ok(Some(TypedModelPatch::single_unary_op(
                         model,
                         node,
                         dispatch_floatlike!(Self::to_depth_wise(dt)(self, &shape))?,
                     )?));
+                } else if (0..spatial_rank).all(|ax| self.padding.valid_dim(ax))
+                    && dt == f32::datum_type()
+                {
+                    let op = self.to_direct(&*shape)?;
+                    return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
+                } else if self.group != 1 && dt == f32::datum_type() {
+                    // pad explicitly so that grouped convolutions use the direct path
+                    let (pad, padded, op) = self.to_padded_direct(&shape)?;
+                    let mut patch = TypedModelPatch::default();
+                    let _ = patch.tap_model(&model, node.inputs[0])?;
+                    patch.chain(
+                        format!("{}-pad", node.name),
+                        pad,
+                        tvec!(TypedTensorInfo {
+                            shape: ShapeInfo::from(&*padded),
+                            datum_type: dt,
+                            konst: None,
+                        }),
+                    )?;
+                    let conv = patch.chain(&*node.name, op, tvec!(node.outputs[0].fact.clone()))?;
+                    patch.shunt_outside(OutletId::new(node.id, 0), OutletId::new(conv, 0))?;
+                    return Ok(Some(patch));
                 } else {
                     let (op1, shape, op2) =
                         dispatch_floatlike!(Self::to_boxed_im2col_pair(dt)(self, &shape))?;
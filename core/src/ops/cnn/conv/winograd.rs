@@ -0,0 +1,424 @@
+use crate::internal::*;
+use crate::ops::nn::DataShape;
+use ndarray::prelude::*;
+use tract_linalg::MatMul;
+
+/*
+ * Winograd F(2x2,3x3): each 2x2 output tile is computed from a 4x4 input
+ * tile as Y = At [ (G g Gt) . (Bt d B) ] A, where "." is the element-wise
+ * product. Summing over input channels turns the element-wise products into
+ * 16 matrix products, one for each of the 4x4 transformed positions:
+ *
+ *  M[xi] (co x tiles) = U[xi] (co x ci) * V[xi] (ci x tiles)
+ *
+ * U is the transformed kernel, computed and packed once. V is the
+ * transformed input. This needs 16 multiplications per tile instead of 36.
+ */
+
+/// Kernel transform G (4x3).
+const G: [[f32; 3]; 4] = [[1.0, 0.0, 0.0], [0.5, 0.5, 0.5], [0.5, -0.5, 0.5], [0.0, 0.0, 1.0]];
+
+#[derive(CustomDebug, Clone)]
+pub struct Winograd {
+    input_shape: DataShape,
+    output_shape: DataShape,
+    pad_top: usize,
+    pad_left: usize,
+    tiles: (usize, usize),
+    mm: Box<MatMul<f32>>,
+    #[debug(skip)]
+    packed_kernels: Vec<Arc<Tensor>>,
+    #[debug(skip)]
+    bias: Option<Arc<Tensor>>,
+}
+
+impl Winograd {
+    /// `kernel` is [co, ci, 3, 3], `pad` the top and left padding.
+    pub fn new(
+        input_shape: DataShape,
+        output_shape: DataShape,
+        pad: (usize, usize),
+        kernel: ArrayView4<f32>,
+        bias: Option<Tensor>,
+    ) -> TractResult<Winograd> {
+        let (co, ci) = (kernel.shape()[0], kernel.shape()[1]);
+        let hw = output_shape.hw_dims();
+        let tiles = ((hw[0] + 1) / 2, (hw[1] + 1) / 2);
+        let mm = (tract_linalg::ops().smm)(co, ci, tiles.0 * tiles.1);
+        let mut u = Array3::<f32>::zeros((16, co, ci));
+        for o in 0..co {
+            for i in 0..ci {
+                let g = kernel.slice(s![o, i, .., ..]);
+                let mut gg = [[0.0f32; 3]; 4];
+                for a in 0..4 {
+                    for x in 0..3 {
+                        gg[a][x] = (0..3).map(|y| G[a][y] * g[(y, x)]).sum();
+                    }
+                }
+                for a in 0..4 {
+                    for b in 0..4 {
+                        u[(a * 4 + b, o, i)] = (0..3).map(|x| gg[a][x] * G[b][x]).sum();
+                    }
+                }
+            }
+        }
+        let packed_kernels = u
+            .outer_iter()
+            .map(|u| {
+                let mut packed = unsafe {
+                    Tensor::uninitialized_aligned::<f32>(
+                        &[mm.packed_a_len()],
+                        mm.packed_a_alignment(),
+                    )?
+                };
+                mm.pack_a(packed.as_slice_mut()?.as_mut_ptr(), u.as_ptr(), ci as isize, 1);
+                Ok(Arc::new(packed))
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        Ok(Winograd {
+            input_shape,
+            output_shape,
+            pad_top: pad.0,
+            pad_left: pad.1,
+            tiles,
+            mm,
+            packed_kernels,
+            bias: bias.map(Arc::new),
+        })
+    }
+
+    fn n_tiles(&self) -> usize {
+        self.tiles.0 * self.tiles.1
+    }
+
+    /// V = Bt d B for every input channel and tile, as [16, ci, tiles].
+    fn transform_input(&self, input: &ArrayViewD<f32>, n: usize, v: &mut Array3<f32>) {
+        let (ih, iw) = (self.input_shape.hw_dims()[0], self.input_shape.hw_dims()[1]);
+        let n_stride = self.input_shape.n_stride() as isize;
+        let c_stride = self.input_shape.c_stride() as isize;
+        let (h_stride, w_stride) =
+            (self.input_shape.hw_strides()[0] as isize, self.input_shape.hw_strides()[1] as isize);
+        let input = input.as_ptr();
+        for c in 0..self.input_shape.c() {
+            for ty in 0..self.tiles.0 {
+                for tx in 0..self.tiles.1 {
+                    let mut d = [[0.0f32; 4]; 4];
+                    for r in 0..4 {
+                        let y = (ty * 2 + r) as isize - self.pad_top as isize;
+                        if y < 0 || y >= ih as isize {
+                            continue;
+                        }
+                        for s in 0..4 {
+                            let x = (tx * 2 + s) as isize - self.pad_left as isize;
+                            if x < 0 || x >= iw as isize {
+                                continue;
+                            }
+                            d[r][s] = unsafe {
+                                *input.offset(
+                                    n as isize * n_stride
+                                        + c as isize * c_stride
+                                        + y * h_stride
+                                        + x * w_stride,
+                                )
+                            };
+                        }
+                    }
+                    let mut t = [[0.0f32; 4]; 4];
+                    for s in 0..4 {
+                        t[0][s] = d[0][s] - d[2][s];
+                        t[1][s] = d[1][s] + d[2][s];
+                        t[2][s] = d[2][s] - d[1][s];
+                        t[3][s] = d[1][s] - d[3][s];
+                    }
+                    let tile = ty * self.tiles.1 + tx;
+                    for r in 0..4 {
+                        v[(r * 4, c, tile)] = t[r][0] - t[r][2];
+                        v[(r * 4 + 1, c, tile)] = t[r][1] + t[r][2];
+                        v[(r * 4 + 2, c, tile)] = t[r][2] - t[r][1];
+                        v[(r * 4 + 3, c, tile)] = t[r][1] - t[r][3];
+                    }
+                }
+            }
+        }
+    }
+
+    /// Y = At M A for every output channel and tile, into the output.
+    fn transform_output(&self, m: &Array3<f32>, n: usize, output: &mut ArrayD<f32>) {
+        let (oh, ow) = (self.output_shape.hw_dims()[0], self.output_shape.hw_dims()[1]);
+        let n_stride = self.output_shape.n_stride() as isize;
+        let c_stride = self.output_shape.c_stride() as isize;
+        let (h_stride, w_stride) = (
+            self.output_shape.hw_strides()[0] as isize,
+            self.output_shape.hw_strides()[1] as isize,
+        );
+        let bias = self.bias.as_ref().map(|b| b.as_slice::<f32>().unwrap());
+        let output = output.as_mut_ptr();
+        for c in 0..self.output_shape.c() {
+            let bias = bias.map(|b| b[c]).unwrap_or(0.0);
+            for ty in 0..self.tiles.0 {
+                for tx in 0..self.tiles.1 {
+                    let tile = ty * self.tiles.1 + tx;
+                    let mut t = [[0.0f32; 4]; 2];
+                    for s in 0..4 {
+                        let (m0, m1, m2, m3) = (
+                            m[(s, c, tile)],
+                            m[(4 + s, c, tile)],
+                            m[(8 + s, c, tile)],
+                            m[(12 + s, c, tile)],
+                        );
+                        t[0][s] = m0 + m1 + m2;
+                        t[1][s] = m1 - m2 - m3;
+                    }
+                    for r in 0..2 {
+                        let y = ty * 2 + r;
+                        let values = [t[r][0] + t[r][1] + t[r][2], t[r][1] - t[r][2] - t[r][3]];
+                        for s in 0..2 {
+                            let x = tx * 2 + s;
+                            if y < oh && x < ow {
+                                unsafe {
+                                    *output.offset(
+                                        n as isize * n_stride
+                                            + c as isize * c_stride
+                                            + y as isize * h_stride
+                                            + x as isize * w_stride,
+                                    ) = values[s] + bias;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Op for Winograd {
+    fn name(&self) -> Cow<str> {
+        "ConvWinograd".into()
+    }
+
+    fn info(&self) -> TractResult<Option<String>> {
+        Ok(Some(format!("F(2x2,3x3), {} tiles, {:?}", self.n_tiles(), self.mm)))
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let batch = self.input_shape.n();
+        let tiles = batch * self.n_tiles();
+        let (ci, co) = (self.input_shape.c(), self.output_shape.c());
+        let mut cost = tvec!(
+            (Cost::FMA(f32::datum_type()), (16 * tiles * ci * co).to_dim()),
+            // 32 additions per input tile, 24 per output tile
+            (Cost::FLOP(f32::datum_type()), (tiles * (32 * ci + 24 * co)).to_dim()),
+            Cost::read(inputs),
+            Cost::write(
+                f32::datum_type(),
+                self.output_shape.shape.iter().product::<usize>().to_dim()
+            )
+        );
+        for packed in &self.packed_kernels {
+            cost.extend(Cost::params(packed));
+        }
+        if let Some(bias) = &self.bias {
+            cost.extend(Cost::params(bias));
+        }
+        Ok(cost)
+    }
+
+    fn rounding_errors(&self) -> bool {
+        true
+    }
+}
+
+impl StatelessOp for Winograd {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.to_array_view::<f32>()?;
+        let (ci, co, tiles) = (self.input_shape.c(), self.output_shape.c(), self.n_tiles());
+        let mut output = unsafe { ArrayD::<f32>::uninitialized(&*self.output_shape.shape) };
+        let mut v = Array3::<f32>::zeros((16, ci, tiles));
+        let mut m = Array3::<f32>::zeros((16, co, tiles));
+        let b_pack = self.mm.b_pack();
+        let mut packed_v =
+            unsafe { Tensor::uninitialized_aligned::<f32>(&[b_pack.len()], b_pack.alignment())? };
+        for n in 0..self.input_shape.n() {
+            self.transform_input(&input, n, &mut v);
+            for xi in 0..16 {
+                let packed_v = packed_v.as_slice_mut::<f32>()?.as_mut_ptr();
+                b_pack.pack(packed_v, v.slice(s![xi, .., ..]).as_ptr(), tiles as isize, 1);
+                self.mm.mat_mul_prepacked(
+                    self.packed_kernels[xi].as_slice::<f32>()?.as_ptr(),
+                    packed_v,
+                    m.slice_mut(s![xi, .., ..]).as_mut_ptr(),
+                    tiles as isize,
+                    1,
+                );
+            }
+            self.transform_output(&m, n, &mut output);
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Winograd {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        _s: &mut Solver<'r>,
+        _inputs: &'p [TensorProxy],
+        _outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::*;
+    use crate::ops::nn::DataFormat;
+    use proptest::prelude::*;
+    use proptest::*;
+    use std::convert::TryFrom;
+
+    #[derive(Debug)]
+    struct Problem {
+        data_format: DataFormat,
+        padding: PaddingSpec,
+        input: ArrayD<f32>,
+        kernel: Array4<f32>,
+        bias: Option<Array1<f32>>,
+    }
+
+    impl Problem {
+        fn unary(&self) -> ConvUnary {
+            let conv = Conv::new(
+                self.data_format,
+                KernelFormat::OIHW,
+                None,
+                Some(tvec!(3, 3)),
+                self.padding.clone(),
+                None,
+                1,
+            );
+            let input = TypedTensorInfo::try_from(TensorFact::dt_shape(
+                f32::datum_type(),
+                self.input.shape(),
+            ))
+            .unwrap();
+            let kernel = TypedTensorInfo::from(Tensor::from(self.kernel.clone()));
+            let mut unary = conv.to_unary(&[&input, &kernel]).unwrap().unwrap();
+            unary.bias = self.bias.clone().map(|b| b.into());
+            unary
+        }
+
+        fn check(&self) -> Result<(), TestCaseError> {
+            let unary = self.unary();
+            let input = tvec!(Tensor::from(self.input.clone()).into());
+            let expected = unary.eval(input.clone()).unwrap();
+            let winograd = unary.to_winograd(self.input.shape()).unwrap().unwrap();
+            let found = winograd.eval(input).unwrap();
+            prop_assert!(
+                found[0].close_enough(&expected[0], true),
+                "found: {:?} expected: {:?}",
+                found[0],
+                expected[0]
+            );
+            Ok(())
+        }
+    }
+
+    impl Arbitrary for Problem {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Problem>;
+        fn arbitrary_with(_args: ()) -> Self::Strategy {
+            (
+                prop_oneof!(Just(DataFormat::NCHW), Just(DataFormat::NHWC)),
+                prop_oneof!(Just(PaddingSpec::Valid), Just(PaddingSpec::SameUpper)),
+                1usize..3,
+                1usize..5,
+                1usize..5,
+                4usize..9,
+                4usize..9,
+                any::<bool>(),
+            )
+                .prop_flat_map(|(fmt, padding, n, ci, co, h, w, bias)| {
+                    let shape = fmt.from_n_c_hw(n, ci, [h, w].as_ref()).shape;
+                    let input = proptest::collection::vec(-5i8..5, shape.iter().product::<usize>());
+                    let kernel = proptest::collection::vec(-5i8..5, co * ci * 9);
+                    let bias = proptest::collection::vec(-5i8..5, if bias { co } else { 0 });
+                    (Just((fmt, padding, shape, co, ci)), input, kernel, bias)
+                })
+                .prop_map(|((data_format, padding, shape, co, ci), input, kernel, bias)| {
+                    let input = input.into_iter().map(|x| x as f32).collect();
+                    let kernel = kernel.into_iter().map(|x| x as f32).collect();
+                    Problem {
+                        data_format,
+                        padding,
+                        input: ArrayD::from_shape_vec(&*shape, input).unwrap(),
+                        kernel: Array4::from_shape_vec((co, ci, 3, 3), kernel).unwrap(),
+                        bias: if bias.len() > 0 {
+                            Some(bias.into_iter().map(|x| x as f32).collect())
+                        } else {
+                            None
+                        },
+                    }
+                })
+                .boxed()
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn winograd_vs_im2col(pb in any::<Problem>()) {
+            pb.check()?
+        }
+    }
+
+    #[test]
+    fn model_clones_share_packed_kernels_and_bias() {
+        let conv = Conv::new(
+            DataFormat::NCHW,
+            KernelFormat::OIHW,
+            None,
+            None,
+            PaddingSpec::SameUpper,
+            None,
+            1,
+        );
+        let mut model = InferenceModel::default();
+        let input = model
+            .add_source("input", TensorFact::dt_shape(f32::datum_type(), tvec!(1, 2, 5, 5)))
+            .unwrap();
+        let kernel = model.add_const("kernel", Array4::<f32>::zeros((3, 2, 3, 3))).unwrap();
+        let bias = model.add_const("bias", Array1::<f32>::zeros(3)).unwrap();
+        let conv = model.add_node_default("conv", conv).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(conv, 0)).unwrap();
+        model.add_edge(OutletId::new(kernel, 0), InletId::new(conv, 1)).unwrap();
+        model.add_edge(OutletId::new(bias, 0), InletId::new(conv, 2)).unwrap();
+        model.set_output_outlets(&[OutletId::new(conv, 0)]).unwrap();
+        let model = model.into_optimized().unwrap();
+        let other = model.clone();
+        let winograd = |model: &TypedModel| {
+            let op = model.nodes().iter().find_map(|n| n.op_as::<Winograd>()).unwrap();
+            (op.packed_kernels[0].clone(), op.bias.clone().unwrap())
+        };
+        let ((k1, b1), (k2, b2)) = (winograd(&model), winograd(&other));
+        assert!(Arc::ptr_eq(&k1, &k2));
+        assert!(Arc::ptr_eq(&b1, &b2));
+    }
+
+    #[test]
+    fn winograd_same_padding_with_bias() {
+        let pb = Problem {
+            data_format: DataFormat::NHWC,
+            padding: PaddingSpec::SameUpper,
+            input: ArrayD::from_shape_fn(&[1, 5, 4, 2][..], |ix| {
+                (ix[1] * 8 + ix[2] * 2 + ix[3]) as f32
+            }),
+            kernel: Array4::from_shape_fn((3, 2, 3, 3), |(o, i, y, x)| {
+                (o + i * 2 + y * x) as f32 - 3.0
+            }),
+            bias: Some(arr1(&[1.0, -2.0, 0.5])),
+        };
+        pb.check().unwrap();
+    }
+}
@@ -2,20 +2,31 @@ use crate::internal::*;
 use ndarray::prelude::*;
 use num_traits::Float;
 
-use crate::ops::cnn::pools::PoolSpec;
-use crate::ops::cnn::Patch;
-use crate::ops::nn::DataShape;
+use crate::ops::cnn::pools::{PoolScan, PoolSpec};
+use crate::ops::nn::{DataFormat, DataShape};
 
+/// Max pooling, optionally returning the argmax as a second output.
+///
+/// Indices are offsets in the flattened input tensor, including the batch
+/// offset when `index_include_batch` is set (ONNX) and excluding it otherwise
+/// (TensorFlow `MaxPoolWithArgmax` default).
 #[derive(Debug, Clone, new, Default)]
 pub struct MaxPool {
     pool_spec: PoolSpec,
     with_index_outputs: Option<DatumType>,
+    index_include_batch: bool,
 }
 
 impl MaxPool {
     fn to_fixed<T: Datum + Float>(&self, input_shape: &[usize]) -> TractResult<Box<Op>> {
         let (input_shape, patch, output_shape) = self.pool_spec.compute_geo(input_shape);
-        let op = MaxPoolFixed::<T>::new(patch, input_shape, output_shape, self.with_index_outputs);
+        let op = MaxPoolFixed::<T>::new(
+            input_shape,
+            output_shape,
+            PoolScan::new(&patch),
+            self.with_index_outputs,
+            self.index_include_batch,
+        );
         Ok(Box::new(op))
     }
 }
@@ -25,6 +36,14 @@ impl Op for MaxPool {
         "MaxPool".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        if let Some(shape) = inputs[0].shape.as_finite() {
+            let op = dispatch_floatlike!(MaxPool::to_fixed(inputs[0].datum_type)(self, shape))?;
+            return op.cost(inputs);
+        }
+        Ok(tvec!(Cost::read(inputs)))
+    }
+
     fn codegen(
         &self,
         model: &TypedModel,
@@ -70,17 +89,145 @@ impl InferenceRulesOp for MaxPool {
 
 #[derive(Debug, Clone, new)]
 pub struct MaxPoolFixed<T: Datum + Float> {
-    patch: Patch,
     input_shape: DataShape,
     output_shape: DataShape,
+    scan: PoolScan,
     with_index_outputs: Option<DatumType>,
+    index_include_batch: bool,
     _casper: PhantomData<T>,
 }
 
+impl<T: Datum + Float> MaxPoolFixed<T> {
+    unsafe fn eval_t<F: AsRef<[isize]>>(
+        &self,
+        field: F,
+        input: *const T,
+        values: *mut T,
+        indices: Option<*mut i64>,
+    ) {
+        let field = field.as_ref();
+        let (ishape, oshape) = (&self.input_shape, &self.output_shape);
+        for n in 0..ishape.n() {
+            let input = input.offset((ishape.n_stride() * n) as isize);
+            let values = values.offset((oshape.n_stride() * n) as isize);
+            let indices = indices.map(|i| i.offset((oshape.n_stride() * n) as isize));
+            let base = if self.index_include_batch { ishape.n_stride() * n } else { 0 } as i64;
+            match ishape.fmt {
+                DataFormat::NHWC => {
+                    let channels = ishape.c();
+                    for &(o, center) in &self.scan.full {
+                        let field = field.iter().map(|&t| t + center);
+                        max_hwc(channels, field, input, values.offset(o), indices, o, base);
+                    }
+                    for (o, offsets) in &self.scan.partial {
+                        let field = offsets.iter().cloned();
+                        max_hwc(channels, field, input, values.offset(*o), indices, *o, base);
+                    }
+                }
+                DataFormat::NCHW => {
+                    for c in 0..ishape.c() {
+                        let input = input.offset((ishape.c_stride() * c) as isize);
+                        let o_c = (oshape.c_stride() * c) as isize;
+                        let base = base + (ishape.c_stride() * c) as i64;
+                        for &(o, center) in &self.scan.full {
+                            let (max, arg) = max_taps(field, input.offset(center));
+                            *values.offset(o_c + o) = max;
+                            if let Some(indices) = indices {
+                                *indices.offset(o_c + o) = base + (center + arg) as i64;
+                            }
+                        }
+                        for (o, offsets) in &self.scan.partial {
+                            let (max, arg) = max_taps(offsets, input);
+                            *values.offset(o_c + o) = max;
+                            if let Some(indices) = indices {
+                                *indices.offset(o_c + o) = base + arg as i64;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Max over the taps of a single window, and the winning tap offset.
+#[inline(always)]
+unsafe fn max_taps<T: Float>(taps: &[isize], input: *const T) -> (T, isize) {
+    if taps.len() == 0 {
+        return (T::neg_infinity(), 0);
+    }
+    let mut max = *input.offset(taps[0]);
+    let mut arg = taps[0];
+    for &t in &taps[1..] {
+        let v = *input.offset(t);
+        if v > max {
+            max = v;
+            arg = t;
+        }
+    }
+    (max, arg)
+}
+
+/// Max over the taps of `channels` interleaved windows. Indices, when
+/// requested, are `base` plus the winning input offset.
+#[inline(always)]
+unsafe fn max_hwc<T: Float>(
+    channels: usize,
+    taps: impl Iterator<Item = isize> + Clone,
+    input: *const T,
+    values: *mut T,
+    indices: Option<*mut i64>,
+    o: isize,
+    base: i64,
+) {
+    if let Some(indices) = indices {
+        let indices = indices.offset(o);
+        for c in 0..channels as isize {
+            let mut taps = taps.clone();
+            let (mut max, mut arg) = match taps.next() {
+                Some(t) => (*input.offset(t + c), t + c),
+                None => (T::neg_infinity(), c),
+            };
+            for t in taps {
+                let v = *input.offset(t + c);
+                if v > max {
+                    max = v;
+                    arg = t + c;
+                }
+            }
+            *values.offset(c) = max;
+            *indices.offset(c) = base + arg as i64;
+        }
+    } else {
+        for c in 0..channels as isize {
+            *values.offset(c) =
+                taps.clone().fold(T::neg_infinity(), |acc, t| acc.max(*input.offset(t + c)));
+        }
+    }
+}
+
 impl<T: Datum + Float> Op for MaxPoolFixed<T> {
     fn name(&self) -> Cow<str> {
         format!("MaxPool::Fixed<{:?}>", T::datum_type()).into()
     }
+
+    fn info(&self) -> TractResult<Option<String>> {
+        Ok(Some(format!("{:?}", self.scan)))
+    }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let output_len = self.output_shape.shape.iter().product::<usize>();
+        let kernel_len = self.scan.field.len();
+        let mut cost = tvec!(
+            (Cost::Reduce(T::datum_type()), (output_len * kernel_len).to_dim()),
+            Cost::read(inputs),
+            Cost::write(T::datum_type(), output_len.to_dim())
+        );
+        if let Some(idt) = self.with_index_outputs {
+            cost.push(Cost::write(idt, output_len.to_dim()));
+        }
+        Ok(cost)
+    }
 }
 
 impl<T: Datum + Float> StatelessOp for MaxPoolFixed<T> {
@@ -91,34 +238,28 @@ impl<T: Datum + Float> StatelessOp for MaxPoolFixed<T> {
 
         let mut values = unsafe { ArrayD::<T>::uninitialized(&*self.output_shape.shape) };
         let mut indices = if self.with_index_outputs.is_some() {
-            Some(unsafe { ArrayD::<i32>::uninitialized(&*self.output_shape.shape) })
+            Some(unsafe { ArrayD::<i64>::uninitialized(&*self.output_shape.shape) })
         } else {
             None
         };
+        let values_ptr = values.as_mut_ptr();
+        let indices_ptr = indices.as_mut().map(|i| i.as_mut_ptr());
         unsafe {
-            self.patch.visit_output(|visitor| {
-                for n in 0..self.input_shape.n() {
-                    let input_offset = self.input_shape.n_stride() * n;
-                    let output_offset = self.output_shape.n_stride() * n;
-                    for c in 0..self.input_shape.c() {
-                        let input_offset = input_offset + self.input_shape.c_stride() * c;
-                        let output_offset = output_offset + self.output_shape.c_stride() * c;
-                        let max = visitor
-                            .valid_offsets()
-                            .map(|v| (v, *input_ptr.offset(v + input_offset as isize)))
-                            .fold((0, T::min_value()), |acc, v| if acc.1 < v.1 { v } else { acc });
-                        *values
-                            .as_mut_ptr()
-                            .offset(output_offset as isize + visitor.output_offset) = max.1;
-                        if let Some(ref mut indices) = indices {
-                            *indices
-                                .as_mut_ptr()
-                                .offset(output_offset as isize + visitor.output_offset) =
-                                max.0 as i32 / self.patch.spec.output_inner_stride as i32;
-                        }
-                    }
-                }
-            });
+            match self.scan.field.len() {
+                4 => self.eval_t(
+                    self.scan.fixed_field::<[isize; 4]>(),
+                    input_ptr,
+                    values_ptr,
+                    indices_ptr,
+                ),
+                9 => self.eval_t(
+                    self.scan.fixed_field::<[isize; 9]>(),
+                    input_ptr,
+                    values_ptr,
+                    indices_ptr,
+                ),
+                _ => self.eval_t(&*self.scan.field, input_ptr, values_ptr, indices_ptr),
+            }
         }
         if let Some(dt) = self.with_index_outputs {
             Ok(tvec!(
@@ -1,4 +1,5 @@
 use crate::internal::*;
+use std::fmt;
 
 use crate::ops::cnn::{PaddingSpec, Patch, PatchSpec};
 use crate::ops::nn::{DataFormat, DataShape};
@@ -54,3 +55,215 @@ impl PoolSpec {
         })
     }
 }
+
+/// Output points of a pooling patch, computed once at codegen time.
+///
+/// Points whose window lies entirely in the input share the same field of
+/// offsets around their center; the ones touching the padding carry the
+/// offsets of their valid taps.
+#[derive(Clone)]
+pub struct PoolScan {
+    /// Offsets of the window taps, relative to a point input center.
+    pub field: TVec<isize>,
+    /// (output offset, input center offset) of points with a full window.
+    pub full: Vec<(isize, isize)>,
+    /// (output offset, input offsets of the valid taps) of points touching
+    /// the padding.
+    pub partial: Vec<(isize, TVec<isize>)>,
+}
+
+impl PoolScan {
+    pub fn new(patch: &Patch) -> PoolScan {
+        let field: TVec<isize> = patch.standard_layout_data_field.iter().cloned().collect();
+        let mut full = vec![];
+        let mut partial = vec![];
+        patch.visit_output(|visitor| {
+            if visitor.valid_count() == field.len() {
+                full.push((visitor.output_offset, visitor.input_center_offset));
+            } else {
+                partial.push((visitor.output_offset, visitor.valid_offsets().collect()));
+            }
+        });
+        PoolScan { field, full, partial }
+    }
+
+    /// The window field as a fixed size array, so that tap loops over the
+    /// common 2x2 and 3x3 windows get unrolled.
+    pub fn fixed_field<A: Default + AsMut<[isize]>>(&self) -> A {
+        let mut a = A::default();
+        a.as_mut().copy_from_slice(&self.field);
+        a
+    }
+}
+
+impl fmt::Debug for PoolScan {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "PoolScan taps:{} full:{} partial:{}",
+            self.field.len(),
+            self.full.len(),
+            self.partial.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ops::cnn::{AvgPool, MaxPool};
+    use ndarray::prelude::*;
+    use proptest::prelude::*;
+    use proptest::test_runner::TestCaseError;
+    use proptest::*;
+
+    #[derive(Debug)]
+    struct Problem {
+        data_format: DataFormat,
+        kernel: (usize, usize),
+        stride: usize,
+        pad_before: (usize, usize),
+        pad_after: (usize, usize),
+        include_batch: bool,
+        input: ArrayD<f32>,
+    }
+
+    impl Problem {
+        fn pool_spec(&self) -> PoolSpec {
+            PoolSpec::new(
+                self.data_format,
+                tvec!(self.kernel.0, self.kernel.1),
+                PaddingSpec::Explicit(
+                    tvec!(self.pad_before.0, self.pad_before.1),
+                    tvec!(self.pad_after.0, self.pad_after.1),
+                ),
+                Some(tvec!(self.stride, self.stride)),
+            )
+        }
+
+        /// (max, argmax, average with padding, average without padding)
+        fn reference(&self) -> (ArrayD<f32>, ArrayD<i64>, ArrayD<f32>, ArrayD<f32>) {
+            let ishape = self.data_format.shape(TVec::from(self.input.shape()));
+            let (h, w) = (ishape.hw_dims()[0], ishape.hw_dims()[1]);
+            let oh = (h + self.pad_before.0 + self.pad_after.0 - self.kernel.0) / self.stride + 1;
+            let ow = (w + self.pad_before.1 + self.pad_after.1 - self.kernel.1) / self.stride + 1;
+            let oshape = self.data_format.from_n_c_hw(ishape.n(), ishape.c(), &[oh, ow]);
+            let input = self.input.as_slice().unwrap();
+            let offset = |n: usize, c: usize, y: usize, x: usize| match self.data_format {
+                DataFormat::NCHW => ((n * ishape.c() + c) * h + y) * w + x,
+                DataFormat::NHWC => ((n * h + y) * w + x) * ishape.c() + c,
+            };
+            let coords = |ix: &IxDyn| match self.data_format {
+                DataFormat::NCHW => (ix[0], ix[1], ix[2], ix[3]),
+                DataFormat::NHWC => (ix[0], ix[3], ix[1], ix[2]),
+            };
+            let taps = |ix: IxDyn| {
+                let (n, c, oy, ox) = coords(&ix);
+                let mut taps = vec![];
+                for ky in 0..self.kernel.0 {
+                    for kx in 0..self.kernel.1 {
+                        let y = (oy * self.stride + ky) as isize - self.pad_before.0 as isize;
+                        let x = (ox * self.stride + kx) as isize - self.pad_before.1 as isize;
+                        if y >= 0 && x >= 0 && (y as usize) < h && (x as usize) < w {
+                            taps.push(offset(n, c, y as usize, x as usize));
+                        }
+                    }
+                }
+                taps
+            };
+            let argmax = ArrayD::from_shape_fn(&*oshape.shape, |ix| {
+                let n = coords(&ix).0;
+                let taps = taps(ix);
+                let mut best = taps[0];
+                for &t in &taps {
+                    if input[t] > input[best] {
+                        best = t;
+                    }
+                }
+                if self.include_batch {
+                    best as i64
+                } else {
+                    (best - n * ishape.n_stride()) as i64
+                }
+            });
+            let max = ArrayD::from_shape_fn(&*oshape.shape, |ix| {
+                taps(ix).into_iter().map(|t| input[t]).fold(std::f32::MIN, f32::max)
+            });
+            let sum = ArrayD::from_shape_fn(&*oshape.shape, |ix| {
+                taps(ix).into_iter().map(|t| input[t]).sum::<f32>()
+            });
+            let count = ArrayD::from_shape_fn(&*oshape.shape, |ix| taps(ix).len() as f32);
+            let avg_with_pad = &sum / (self.kernel.0 * self.kernel.1) as f32;
+            let avg_without_pad = &sum / &count;
+            (max, argmax, avg_with_pad, avg_without_pad)
+        }
+
+        fn check(&self) -> Result<(), TestCaseError> {
+            let (max, argmax, avg_with_pad, avg_without_pad) = self.reference();
+            let input = tvec!(Tensor::from(self.input.clone()).into());
+            let op = MaxPool::new(self.pool_spec(), Some(DatumType::I64), self.include_batch);
+            let found = op.eval(input.clone()).unwrap();
+            prop_assert_eq!(found[0].to_array_view::<f32>().unwrap(), max.view());
+            prop_assert_eq!(found[1].to_array_view::<i64>().unwrap(), argmax.view());
+            for &(include_pad, ref expected) in &[(true, avg_with_pad), (false, avg_without_pad)] {
+                let op = AvgPool::new(self.pool_spec(), include_pad);
+                let found = op.eval(input.clone()).unwrap();
+                prop_assert!(found[0].close_enough(&expected.clone().into(), true));
+            }
+            Ok(())
+        }
+    }
+
+    fn strat() -> BoxedStrategy<Problem> {
+        (
+            prop_oneof!(Just(DataFormat::NCHW), Just(DataFormat::NHWC)),
+            1usize..3,
+            1usize..12,
+            (1usize..4, 1usize..4),
+            1usize..3,
+            any::<bool>(),
+        )
+            .prop_flat_map(|(fmt, n, c, (kh, kw), stride, include_batch)| {
+                (
+                    Just((fmt, n, c, (kh, kw), stride, include_batch)),
+                    (0..kh, 0..kw),
+                    (0..kh, 0..kw),
+                    kh..kh + 6,
+                    kw..kw + 6,
+                )
+            })
+            .prop_flat_map(|(params, pad_before, pad_after, h, w)| {
+                let (fmt, n, c, ..) = params;
+                let shape = fmt.from_n_c_hw(n, c, &[h, w]).shape;
+                let len = shape.iter().product::<usize>();
+                (
+                    Just((params, pad_before, pad_after, shape)),
+                    proptest::collection::vec((-100i32..100).prop_map(|x| x as f32), len),
+                )
+            })
+            .prop_map(
+                |(
+                    ((fmt, _, _, kernel, stride, include_batch), pad_before, pad_after, shape),
+                    input,
+                )| {
+                    Problem {
+                        data_format: fmt,
+                        kernel,
+                        stride,
+                        pad_before,
+                        pad_after,
+                        include_batch,
+                        input: ArrayD::from_shape_vec(&*shape, input).unwrap(),
+                    }
+                },
+            )
+            .boxed()
+    }
+
+    proptest! {
+        #[test]
+        fn pools_vs_reference(pb in strat()) {
+            pb.check()?
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use crate::internal::*;
+
+type BodyPlan = SimplePlan<TensorFact, Arc<InferenceModel>>;
+type BodyState = SimpleState<TensorFact, Arc<InferenceModel>, BodyPlan>;
+
+/// A while loop over an inner body model.
+///
+/// The body inputs are the loop-carried states followed by the loop
+/// invariants. The body outputs are the condition (a boolean scalar) followed
+/// by the next value of each state. The op takes the same inputs as the body
+/// (initial states and invariants) and outputs the final value of the states.
+///
+/// The condition is evaluated before each iteration: the body may run zero
+/// times, in which case the initial states are returned untouched.
+///
+/// Stateful ops of the body share the SessionState of the outer model, so
+/// resources (variables, tensor arrays...) can be used across the loop
+/// boundary.
+#[derive(Debug, Clone, new)]
+pub struct Loop {
+    pub body: Arc<InferenceModel>,
+    pub state_count: usize,
+}
+
+impl Loop {
+    /// A plan over the condition and the next states, and its order split in
+    /// two: the nodes the condition depends on, then the other ones.
+    fn plan(&self) -> TractResult<(BodyPlan, Vec<usize>, Vec<usize>)> {
+        let outputs = self.body.output_outlets()?;
+        if outputs.len() != self.state_count + 1 {
+            bail!(
+                "Loop body must output a condition and {} states, found {} outputs",
+                self.state_count,
+                outputs.len()
+            );
+        }
+        let plan = SimplePlan::new_for_outputs(self.body.clone(), &outputs)?;
+        let cond_order = SimplePlan::new_for_output(self.body.clone(), outputs[0])?.order;
+        let next_order = plan.order.iter().cloned().filter(|n| !cond_order.contains(n)).collect();
+        Ok((plan, cond_order, next_order))
+    }
+}
+
+impl Op for Loop {
+    fn name(&self) -> Cow<str> {
+        "Loop".into()
+    }
+
+    fn info(&self) -> TractResult<Option<String>> {
+        Ok(Some(format!(
+            "{} states, {} invariants, body has {} nodes",
+            self.state_count,
+            self.body.input_outlets()?.len() - self.state_count,
+            self.body.nodes().len()
+        )))
+    }
+
+    /// Cost of a single iteration of the body: the trip count is only known
+    /// at runtime.
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut body = (*self.body).clone();
+        for (ix, input) in inputs.iter().enumerate() {
+            let mut fact = input.to_tensor_fact();
+            if ix < self.state_count {
+                fact.value = GenericFact::Any;
+            }
+            body.set_input_fact(ix, fact)?;
+        }
+        let body = body.into_typed()?;
+        let mut cost = tvec!();
+        for node in body.eval_order()? {
+            cost.extend(body.node(node).op().cost(&*body.node_input_facts(node)?)?);
+        }
+        Ok(cost)
+    }
+}
+
+impl StatefullOp for Loop {
+    fn state(&self, session: &mut SessionState) -> TractResult<Option<Box<OpState>>> {
+        let (plan, cond_order, next_order) = self.plan()?;
+        let mut body = SimpleState::new(plan)?;
+        // body ops may have registered resources while creating their states
+        session.tensors.extend(body.session_state.tensors.drain());
+        Ok(Some(Box::new(LoopState { body, cond_order, next_order })))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LoopState {
+    body: BodyState,
+    cond_order: Vec<usize>,
+    next_order: Vec<usize>,
+}
+
+impl LoopState {
+    /// Each iteration evaluates the nodes of the body at most once: first the
+    /// ones the condition depends on, then, if it holds, the other ones.
+    fn iterate(&mut self, op: &Loop, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let sources: Vec<usize> = op.body.input_outlets()?.iter().map(|o| o.node).collect();
+        if sources.len() != inputs.len() {
+            bail!("Loop body expects {} inputs, got {}", sources.len(), inputs.len());
+        }
+        let outputs = op.body.output_outlets()?;
+        let mut values = inputs;
+        loop {
+            self.body.reset_wires()?;
+            for (&node, value) in sources.iter().zip(values.iter()) {
+                self.body.values[node] = Some(tvec!(value.clone()));
+            }
+            self.compute(&sources, true)?;
+            let cond = self.body.values[outputs[0].node].as_ref().unwrap()[outputs[0].slot].clone();
+            if !*cond.to_scalar::<bool>()? {
+                break;
+            }
+            self.compute(&sources, false)?;
+            for (ix, outlet) in outputs[1..].iter().enumerate() {
+                values[ix] = self.body.values[outlet.node].as_ref().unwrap()[outlet.slot].clone();
+            }
+        }
+        values.truncate(op.state_count);
+        Ok(values)
+    }
+
+    fn compute(&mut self, sources: &[usize], cond: bool) -> TractResult<()> {
+        let order = if cond { &self.cond_order } else { &self.next_order };
+        for &node in order.iter() {
+            if !sources.contains(&node) {
+                self.body.compute_one(node)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl OpState for LoopState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op.downcast_ref::<Loop>().ok_or("Wrong op for loop state")?;
+        std::mem::swap(session, &mut self.body.session_state);
+        let result = self.iterate(op, inputs);
+        std::mem::swap(session, &mut self.body.session_state);
+        result
+    }
+}
+
+impl InferenceOp for Loop {
+    fn infer_facts(
+        &self,
+        inputs: TVec<&TensorFact>,
+        outputs: TVec<&TensorFact>,
+    ) -> TractResult<(TVec<TensorFact>, TVec<TensorFact>)> {
+        if inputs.len() != self.body.input_outlets()?.len() {
+            bail!(
+                "Loop body expects {} inputs, node has {}",
+                self.body.input_outlets()?.len(),
+                inputs.len()
+            );
+        }
+        if outputs.len() != self.state_count {
+            bail!("Loop has {} states, node has {} outputs", self.state_count, outputs.len());
+        }
+        // States change from an iteration to the other, so only their type
+        // and shape are given to the body. Invariants are passed as is.
+        let mut body = (*self.body).clone();
+        for (ix, input) in inputs.iter().enumerate() {
+            let mut fact = (*input).clone();
+            if ix < self.state_count {
+                fact.value = GenericFact::Any;
+            }
+            body.set_input_fact(ix, fact)?;
+        }
+        body.analyse(false)?;
+        let mut infered_outputs = tvec!();
+        for (ix, output) in outputs.iter().enumerate() {
+            // final states are either the initial ones or the last computed
+            // ones: we only know what they have in common.
+            let next = body.output_fact(ix + 1)?;
+            let mut fact = TensorFact::default();
+            if inputs[ix].datum_type == next.datum_type {
+                fact.datum_type = next.datum_type;
+            }
+            if inputs[ix].shape == next.shape {
+                fact.shape = next.shape.clone();
+            }
+            infered_outputs.push(fact.unify(output)?);
+        }
+        Ok((inputs.into_iter().cloned().collect(), infered_outputs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::logic::Lesser;
+    use crate::ops::math::Add;
+
+    // i, acc = 0, 1; while i < n: i, acc = i + 1, acc + acc
+    fn pow2() -> Loop {
+        let mut body = InferenceModel::default();
+        let i = body.add_source("i", TensorFact::dt_shape(DatumType::I32, shapefact!())).unwrap();
+        let acc = body.add_source_default("acc").unwrap();
+        let n = body.add_source_default("n").unwrap();
+        let one = body.add_const("one", rctensor0(1i32)).unwrap();
+        let cond = body.add_node_default("cond", Lesser::default()).unwrap();
+        body.add_edge(OutletId::new(i, 0), InletId::new(cond, 0)).unwrap();
+        body.add_edge(OutletId::new(n, 0), InletId::new(cond, 1)).unwrap();
+        let next_i = body.add_node_default("next_i", Add::default()).unwrap();
+        body.add_edge(OutletId::new(i, 0), InletId::new(next_i, 0)).unwrap();
+        body.add_edge(OutletId::new(one, 0), InletId::new(next_i, 1)).unwrap();
+        let next_acc = body.add_node_default("next_acc", Add::default()).unwrap();
+        body.add_edge(OutletId::new(acc, 0), InletId::new(next_acc, 0)).unwrap();
+        body.add_edge(OutletId::new(acc, 0), InletId::new(next_acc, 1)).unwrap();
+        body.set_output_outlets(&[
+            OutletId::new(cond, 0),
+            OutletId::new(next_i, 0),
+            OutletId::new(next_acc, 0),
+        ])
+        .unwrap();
+        Loop::new(Arc::new(body), 2)
+    }
+
+    fn run(op: Loop, inputs: TVec<Tensor>) -> TVec<Arc<Tensor>> {
+        let mut model = InferenceModel::default();
+        let mut ids = tvec!();
+        for (ix, input) in inputs.iter().enumerate() {
+            let fact = TensorFact::dt_shape(input.datum_type(), input.shape());
+            ids.push(model.add_source(format!("input_{}", ix), fact).unwrap());
+        }
+        let node = model.add_node("loop", op, tvec!(TensorFact::default(); 2)).unwrap();
+        for (ix, id) in ids.iter().enumerate() {
+            model.add_edge(OutletId::new(*id, 0), InletId::new(node, ix)).unwrap();
+        }
+        model.set_output_outlets(&[OutletId::new(node, 0), OutletId::new(node, 1)]).unwrap();
+        let model = model.into_typed().unwrap();
+        SimplePlan::new(&model).unwrap().run(inputs).unwrap()
+    }
+
+    #[test]
+    fn loop_pow2() {
+        let outputs = run(pow2(), tvec!(tensor0(0i32), tensor0(1.5f32), tensor0(3i32)));
+        assert_eq!(outputs, tvec!(rctensor0(3i32), rctensor0(12f32)));
+    }
+
+    #[test]
+    fn loop_no_iteration() {
+        let outputs = run(pow2(), tvec!(tensor0(5i32), tensor0(1.5f32), tensor0(3i32)));
+        assert_eq!(outputs, tvec!(rctensor0(5i32), rctensor0(1.5f32)));
+    }
+
+    #[derive(Debug, Clone, new)]
+    struct Counter(Arc<std::sync::atomic::AtomicUsize>);
+
+    impl Op for Counter {
+        fn name(&self) -> Cow<str> {
+            "Counter".into()
+        }
+    }
+
+    impl StatelessOp for Counter {
+        fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(inputs)
+        }
+    }
+
+    impl InferenceRulesOp for Counter {
+        fn rules<'r, 'p: 'r, 's: 'r>(
+            &'s self,
+            s: &mut Solver<'r>,
+            inputs: &'p [TensorProxy],
+            outputs: &'p [TensorProxy],
+        ) -> InferenceResult {
+            s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+            s.equals(&inputs[0].shape, &outputs[0].shape)
+        }
+    }
+
+    #[test]
+    fn loop_evaluates_shared_nodes_once() {
+        // i = 0; while i < 3: i = i + 1, with i counted on both paths
+        let count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut body = InferenceModel::default();
+        let i = body.add_source("i", TensorFact::dt_shape(DatumType::I32, shapefact!())).unwrap();
+        let counted = body.add_node_default("count", Counter::new(count.clone())).unwrap();
+        body.add_edge(OutletId::new(i, 0), InletId::new(counted, 0)).unwrap();
+        let n = body.add_const("n", rctensor0(3i32)).unwrap();
+        let one = body.add_const("one", rctensor0(1i32)).unwrap();
+        let cond = body.add_node_default("cond", Lesser::default()).unwrap();
+        body.add_edge(OutletId::new(counted, 0), InletId::new(cond, 0)).unwrap();
+        body.add_edge(OutletId::new(n, 0), InletId::new(cond, 1)).unwrap();
+        let next_i = body.add_node_default("next_i", Add::default()).unwrap();
+        body.add_edge(OutletId::new(counted, 0), InletId::new(next_i, 0)).unwrap();
+        body.add_edge(OutletId::new(one, 0), InletId::new(next_i, 1)).unwrap();
+        body.set_output_outlets(&[OutletId::new(cond, 0), OutletId::new(next_i, 0)]).unwrap();
+        let op = Loop::new(Arc::new(body), 1);
+        let mut model = InferenceModel::default();
+        let input =
+            model.add_source("input", TensorFact::dt_shape(DatumType::I32, shapefact!())).unwrap();
+        let node = model.add_node_default("loop", op).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(node, 0)).unwrap();
+        model.set_output_outlets(&[OutletId::new(node, 0)]).unwrap();
+        let model = model.into_typed().unwrap();
+        let outputs = SimplePlan::new(&model).unwrap().run(tvec!(tensor0(0i32))).unwrap();
+        assert_eq!(outputs, tvec!(rctensor0(3i32)));
+        // three iterations, and the final condition check
+        assert_eq!(count.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn loop_cost_is_body_cost() {
+        let i = TypedTensorInfo { datum_type: DatumType::I32, shape: [].into(), konst: None };
+        let acc = TypedTensorInfo { datum_type: DatumType::F32, shape: [2, 3].into(), konst: None };
+        let n = TypedTensorInfo::from(rctensor0(3i32));
+        let cost = pow2().cost(&[&i, &acc, &n]).unwrap();
+        let sum = |c| cost.iter().filter(|x| x.0 == c).map(|x| x.1.clone()).sum::<TDim>();
+        assert_eq!(sum(Cost::FLOP(DatumType::F32)), 6.to_dim());
+        assert_eq!(sum(Cost::Params(DatumType::I32)), 1.to_dim());
+    }
+
+    #[test]
+    fn loop_infers_states_facts() {
+        let op = pow2();
+        let i = TensorFact::dt_shape(DatumType::I32, shapefact!());
+        let acc = TensorFact::dt_shape(DatumType::F32, shapefact!(2, 3));
+        let n = TensorFact::from(rctensor0(3i32));
+        let any = TensorFact::default();
+        let (_, outputs) = op.infer_facts(tvec!(&i, &acc, &n), tvec!(&any, &any)).unwrap();
+        assert_eq!(outputs[0], i);
+        assert_eq!(outputs[1], acc);
+    }
+}
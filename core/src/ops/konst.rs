@@ -9,12 +9,20 @@ impl Const {
     pub fn for_tensor(tensor: Tensor) -> Const {
         Const { value: tensor.into() }
     }
+
+    pub fn value(&self) -> &Arc<Tensor> {
+        &self.value
+    }
 }
 
 impl Op for Const {
     fn name(&self) -> Cow<str> {
         "Const".into()
     }
+
+    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(tvec!((Cost::Params(self.value.datum_type()), self.value.len().to_dim())))
+    }
 }
 
 impl StatelessOp for Const {
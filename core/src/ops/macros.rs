@@ -1,12 +1,17 @@
 #[macro_export]
 macro_rules! element_map {
-    ($Name:ident, [$($type:ty),*], $expr:expr) => {
-        element_map!($Name, match $($type => { $expr } ),*);
+    ($Name:ident, [$($type:ty),*], $expr:expr
+     $(; slice: $($stype:ty => $slice:expr),*)? $(; cost: $cost:expr)?) => {
+        element_map!($Name, match $($type => { $expr } ),*
+                     $(; slice: $($stype => $slice),*)? $(; cost: $cost)?);
     };
-    ($Name:ident, match $($type:ty => { $expr:expr }),*) => {
-        element_map!($Name, match $($type => $type { $expr }),*);
+    ($Name:ident, match $($type:ty => { $expr:expr }),*
+     $(; slice: $($stype:ty => $slice:expr),*)? $(; cost: $cost:expr)?) => {
+        element_map!($Name, match $($type => $type { $expr }),*
+                     $(; slice: $($stype => $slice),*)? $(; cost: $cost)?);
     };
-    ($Name:ident, match $($type:ty => $to:ty { $expr:expr }),*) => {
+    ($Name:ident, match $($type:ty => $to:ty { $expr:expr }),*
+     $(; slice: $($stype:ty => $slice:expr),*)? $(; cost: $cost:expr)?) => {
         #[allow(unused_imports)]
         use $crate::internal::*;
 
@@ -17,6 +22,12 @@ macro_rules! element_map {
             fn eval(&self, mut inputs: TVec<Arc<Tensor>>,) -> TractResult<TVec<Arc<Tensor>>> {
                 let a = args_1!(inputs);
                 let dt = a.datum_type();
+                // types with an in-place kernel working on the whole buffer
+                $($(if dt == <$stype>::datum_type() {
+                    let mut a = a.into_tensor();
+                    ($slice)(a.as_slice_mut::<$stype>()?);
+                    return Ok(tvec!(a.into_arc_tensor()));
+                })*)?
                 $(if dt == <$type>::datum_type() {
                     let a = a.into_tensor().into_array::<$type>()?;
                     return Ok(tvec!(a.mapv($expr).into_arc_tensor()));
@@ -43,6 +54,16 @@ macro_rules! element_map {
                 Ok(tvec!(OutletId::new(id, 0)))
             }
 
+            fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+                let dt = inputs[0].datum_type;
+                #[allow(unused_mut)]
+                let mut to = dt;
+                $(if dt == <$type>::datum_type() {
+                    to = <$to>::datum_type();
+                })*
+                let per_element = $crate::element_cost!(dt $(, $cost)?);
+                Ok(Cost::element_wise(inputs, per_element, inputs[0].shape.size(), to))
+            }
         }
 
         impl InferenceRulesOp for $Name {
@@ -69,7 +90,7 @@ macro_rules! element_map {
 
 #[macro_export]
 macro_rules! element_map_with_params {
-    ($Name:ident, [$($type:ty),*], {$($pname:ident : $pty:ty),*}, $expr:item) => {
+    ($Name:ident, [$($type:ty),*], {$($pname:ident : $pty:ty),*}, $expr:item $(; cost: $cost:expr)?) => {
         #[allow(unused_imports)]
         use $crate::internal::*;
 
@@ -96,6 +117,12 @@ macro_rules! element_map_with_params {
             fn name(&self) -> Cow<str> {
                 stringify!($Name).into()
             }
+
+            fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+                let dt = inputs[0].datum_type;
+                let per_element = $crate::element_cost!(dt $(, $cost)?);
+                Ok(Cost::element_wise(inputs, per_element, inputs[0].shape.size(), dt))
+            }
         }
 
         impl InferenceRulesOp for $Name {
@@ -120,13 +147,13 @@ macro_rules! element_map_with_params {
 
 #[macro_export]
 macro_rules! element_bin {
-    ($name:ident, [$($type:ty),*] => $to:ty { $expr:expr }) => {
-        element_bin!($name, match $($type => $to { $expr } ),*);
+    ($name:ident, [$($type:ty),*] => $to:ty { $expr:expr } $(; cost: $cost:expr)?) => {
+        element_bin!($name, match $($type => $to { $expr } ),* $(; cost: $cost)?);
     };
-    ($name:ident, [$($type:ty),*] { $expr:expr }) => {
-        element_bin!($name, match $($type => $type { $expr } ),*);
+    ($name:ident, [$($type:ty),*] { $expr:expr } $(; cost: $cost:expr)?) => {
+        element_bin!($name, match $($type => $type { $expr } ),* $(; cost: $cost)?);
     };
-    ($name:ident, match $($type:ty => $to:ty { $expr:expr }),*) => {
+    ($name:ident, match $($type:ty => $to:ty { $expr:expr }),* $(; cost: $cost:expr)?) => {
         #[allow(non_snake_case)]
         pub mod $name {
             #[allow(unused_imports)]
@@ -158,6 +185,24 @@ macro_rules! element_bin {
                 bail!("{} not covering {:?}", stringify!($name), dt)
             }
 
+            fn cost(inputs: &[&TypedTensorInfo], b: &TypedTensorInfo) -> TractResult<TVec<(Cost, TDim)>> {
+                let a_shape = inputs[0].shape.to_tvec();
+                let b_shape = b.shape.to_tvec();
+                let shape = $crate::broadcast::multi_broadcast(&[a_shape, b_shape])
+                    .ok_or_else(|| format!("Incompatible shapes {:?} and {:?}",
+                                           inputs[0].shape, b.shape))?;
+                let dt = inputs[0].datum_type.common_super_type(b.datum_type)
+                    .ok_or_else(|| format!("Incompatible types {:?} and {:?}",
+                                           inputs[0].datum_type, b.datum_type))?;
+                #[allow(unused_mut)]
+                let mut to = dt;
+                $(if dt == <$type>::datum_type() {
+                    to = <$to>::datum_type();
+                })*
+                let per_element = $crate::element_cost!(dt $(, $cost)?);
+                Ok(Cost::element_wise(inputs, per_element, shape.iter().cloned().product(), to))
+            }
+
             #[derive(Debug, Clone, Default, new)]
             pub struct Bin(TypeFact);
 
@@ -174,6 +219,10 @@ macro_rules! element_bin {
                     concat!(stringify!($name), "::Binary").into()
                 }
 
+                fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+                    cost(inputs, inputs[1])
+                }
+
                 fn declutter(&self, model: &$crate::model::TypedModel, node: &$crate::model::TypedNode)
                  -> TractResult<Option<TypedModelPatch>> {
                      let inputs = model.node_input_facts(node.id)?;
@@ -281,6 +330,12 @@ macro_rules! element_bin {
                     concat!(stringify!($name), "::UnaryA").into()
                 }
 
+                fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+                    let mut cost = cost(inputs, &self.b.clone().into())?;
+                    cost.extend(Cost::params(&self.b));
+                    Ok(cost)
+                }
+
                 fn pulsify(
                     &self,
                     _source: &NormalizedModel,
@@ -331,13 +386,13 @@ macro_rules! element_bin {
 }
 
 macro_rules! element_nary {
-    ($Name:ident, [$($type:ty),*] => $to:ty { $expr:expr }) => {
-        element_nary!($Name, match $($type => $to { $expr } ),*);
+    ($Name:ident, [$($type:ty),*] => $to:ty { $expr:expr } $(; cost: $cost:expr)?) => {
+        element_nary!($Name, match $($type => $to { $expr } ),* $(; cost: $cost)?);
     };
-    ($Name:ident, [$($type:ty),*] { $expr:expr }) => {
-        element_nary!($Name, match $($type => $type { $expr } ),*);
+    ($Name:ident, [$($type:ty),*] { $expr:expr } $(; cost: $cost:expr)?) => {
+        element_nary!($Name, match $($type => $type { $expr } ),* $(; cost: $cost)?);
     };
-    ($Name:ident, match $($type:ty => $to:ty { $expr:expr }),*) => {
+    ($Name:ident, match $($type:ty => $to:ty { $expr:expr }),* $(; cost: $cost:expr)?) => {
         #[allow(unused_imports)]
         use $crate::internal::*;
 
@@ -371,6 +426,26 @@ macro_rules! element_nary {
                 Ok(tvec!(OutletId::new(id, 0)))
             }
 
+            /// Costs are counted per folded input: n inputs make n-1 steps.
+            fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+                let dt = DatumType::super_type_for(inputs.iter().map(|i| i.datum_type))
+                    .ok_or("Could not find a supertype")?;
+                let shapes: TVec<TVec<TDim>> = inputs.iter().map(|i| i.shape.to_tvec()).collect();
+                let shape: TVec<TDim> = $crate::broadcast::multi_broadcast(&shapes)
+                    .ok_or("Could not find a shape")?;
+                #[allow(unused_mut)]
+                let mut to = dt;
+                $(if dt == <$type>::datum_type() {
+                    to = <$to>::datum_type();
+                })*
+                let steps = inputs.len().saturating_sub(1);
+                let per_element = $crate::element_cost!(dt $(, $cost)?)
+                    .into_iter()
+                    .map(|(c, n)| (c, n * steps))
+                    .collect();
+                Ok(Cost::element_wise(inputs, per_element, shape.iter().cloned().product(), to))
+            }
+
         }
 
         impl StatelessOp for $Name {
@@ -439,6 +514,18 @@ macro_rules! element_nary {
     }
 }
 
+/// Per element cost of element-wise ops: one FLOP unless specified by a
+/// `cost: |dt| ...` clause in the op declaration.
+#[macro_export]
+macro_rules! element_cost {
+    ($dt:expr) => {
+        tvec!(($crate::ops::Cost::FLOP($dt), 1))
+    };
+    ($dt:expr, $cost:expr) => {
+        ($cost)($dt)
+    };
+}
+
 #[macro_export]
 macro_rules! args_1 {
     ($inputs:expr) => {{
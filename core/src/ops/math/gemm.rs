@@ -4,6 +4,19 @@ use ndarray::prelude::*;
 use num_traits::AsPrimitive;
 use num_traits::Float;
 
+/// Products and output of `a.b`, with `a` and `b` transposed as requested.
+fn mat_mul_cost(
+    dt: DatumType,
+    a: &[TDim],
+    b: &[TDim],
+    trans_a: bool,
+    trans_b: bool,
+) -> TVec<(Cost, TDim)> {
+    let (m, k) = if trans_a { (a[1], a[0]) } else { (a[0], a[1]) };
+    let n = if trans_b { b[0] } else { b[1] };
+    tvec!((Cost::FMA(dt), m * k * n), Cost::write(dt, m * n))
+}
+
 #[derive(Debug, Clone, new)]
 pub struct Gemm {
     alpha: f32,
@@ -63,6 +76,18 @@ impl Op for Gemm {
         "Gemm".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = mat_mul_cost(
+            inputs[0].datum_type,
+            &inputs[0].shape.to_tvec(),
+            &inputs[1].shape.to_tvec(),
+            self.trans_a,
+            self.trans_b,
+        );
+        cost.push(Cost::read(inputs));
+        Ok(cost)
+    }
+
     fn declutter(
         &self,
         model: &TypedModel,
@@ -225,6 +250,23 @@ impl Op for GemmUnaryA {
         "GemmUnaryA".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let b_shape: TVec<TDim> = self.b.shape().iter().map(|d| d.to_dim()).collect();
+        let mut cost = mat_mul_cost(
+            inputs[0].datum_type,
+            &inputs[0].shape.to_tvec(),
+            &b_shape,
+            self.trans_a,
+            self.trans_b,
+        );
+        cost.push(Cost::read(inputs));
+        cost.extend(Cost::params(&self.b));
+        if self.beta != 0.0 {
+            cost.extend(Cost::params(&self.c));
+        }
+        Ok(cost)
+    }
+
     fn pulsify(
         &self,
         _source: &NormalizedModel,
@@ -314,6 +356,23 @@ impl Op for GemmUnaryB {
     fn name(&self) -> Cow<str> {
         "GemmUnaryB".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let a_shape: TVec<TDim> = self.a.shape().iter().map(|d| d.to_dim()).collect();
+        let mut cost = mat_mul_cost(
+            inputs[0].datum_type,
+            &a_shape,
+            &inputs[0].shape.to_tvec(),
+            self.trans_a,
+            self.trans_b,
+        );
+        cost.push(Cost::read(inputs));
+        cost.extend(Cost::params(&self.a));
+        if self.beta != 0.0 {
+            cost.extend(Cost::params(&self.c));
+        }
+        Ok(cost)
+    }
 }
 
 impl StatelessOp for GemmUnaryB {
@@ -173,7 +173,11 @@ impl Op for MatMul {
         let m = bc_a_shape[bc_a_shape.len() - 2];
         let k = bc_a_shape[bc_a_shape.len() - 1];
         let n = bc_b_shape[bc_b_shape.len() - 1];
-        Ok(tvec!((Cost::FMA(dt), (mul * m * k * n))))
+        Ok(tvec!(
+            (Cost::FMA(dt), (mul * m * k * n)),
+            Cost::read(inputs),
+            Cost::write(dt, bc_c_shape.iter().cloned().product::<TDim>())
+        ))
     }
 }
 
@@ -204,6 +208,28 @@ impl InferenceRulesOp for MatMul {
     }
 }
 
+/// Cost of a product against the constant `a` or `b`.
+fn unary_cost(
+    a_shape: TVec<TDim>,
+    b_shape: TVec<TDim>,
+    dt: DatumType,
+    inputs: &[&TypedTensorInfo],
+    konst: &Tensor,
+) -> TractResult<TVec<(Cost, TDim)>> {
+    let (bc_a_shape, bc_b_shape, bc_c_shape) = infer_shapes(a_shape, b_shape)?;
+    let mul = bc_c_shape.iter().rev().skip(2).cloned().product::<TDim>();
+    let m = bc_a_shape[bc_a_shape.len() - 2];
+    let k = bc_a_shape[bc_a_shape.len() - 1];
+    let n = bc_b_shape[bc_b_shape.len() - 1];
+    let mut cost = tvec!(
+        (Cost::FMA(dt), (mul * m * k * n)),
+        Cost::read(inputs),
+        Cost::write(dt, bc_c_shape.iter().cloned().product::<TDim>())
+    );
+    cost.extend(Cost::params(konst));
+    Ok(cost)
+}
+
 #[derive(Debug, Clone, new)]
 pub struct MatMulUnaryA {
     b: Tensor,
@@ -233,6 +259,11 @@ impl Op for MatMulUnaryA {
         "MatMulUnaryA".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let b_shape = self.b.shape().iter().map(|d| d.to_dim()).collect();
+        unary_cost(inputs[0].shape.to_tvec(), b_shape, inputs[0].datum_type, inputs, &self.b)
+    }
+
     fn pulsify(
         &self,
         _source: &NormalizedModel,
@@ -302,7 +333,7 @@ impl InferenceRulesOp for MatMulUnaryA {
 #[derive(Debug, Clone)]
 pub struct MatMulUnaryImplASimpleB<T: Copy + Datum + Add + Mul + Zero + FloatLike> {
     geo: Geo<T>,
-    packed_b: Tensor,
+    packed_b: Arc<Tensor>,
     a_shape: TVec<usize>,
     c_shape: TVec<usize>,
 }
@@ -320,7 +351,12 @@ impl<T: Copy + Datum + Add + Mul + Zero + FloatLike> MatMulUnaryImplASimpleB<T>
         let mut packed_b =
             unsafe { Tensor::uninitialized_aligned::<T>(&[b_pack.len()], b_pack.alignment())? };
         b_pack.pack(packed_b.as_ptr_mut()?, b.as_ptr(), b.strides()[0], b.strides()[1]);
-        Ok(MatMulUnaryImplASimpleB { geo, packed_b, c_shape, a_shape: a_shape.into() })
+        Ok(MatMulUnaryImplASimpleB {
+            geo,
+            packed_b: Arc::new(packed_b),
+            c_shape,
+            a_shape: a_shape.into(),
+        })
     }
 }
 
@@ -333,11 +369,17 @@ impl<T: Copy + Datum + Add + Mul + Zero + FloatLike> Op for MatMulUnaryImplASimp
         Ok(Some(format!("{:?}", self.geo.mm)))
     }
 
-    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
-        Ok(tvec!((
-            Cost::FMA(T::datum_type()),
-            (self.geo.mm.m() * self.geo.mm.n() * self.geo.mm.k()).to_dim()
-        )))
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = tvec!(
+            (
+                Cost::FMA(T::datum_type()),
+                (self.geo.mm.m() * self.geo.mm.n() * self.geo.mm.k()).to_dim()
+            ),
+            Cost::read(inputs),
+            Cost::write(T::datum_type(), self.c_shape.iter().product::<usize>().to_dim())
+        );
+        cost.extend(Cost::params(&self.packed_b));
+        Ok(cost)
     }
 }
 
@@ -390,7 +432,7 @@ impl<T: Copy + Datum + Add + Mul + Zero + FloatLike> InferenceRulesOp
 #[derive(Debug, Clone)]
 pub struct MatMulUnaryImplA<T: Copy + Datum + Add + Mul + Zero + FloatLike> {
     geo: Geo<T>,
-    packed_bs: Tensor,
+    packed_bs: Arc<Tensor>,
 }
 
 impl<T: Copy + Datum + Add + Mul + Zero + FloatLike> MatMulUnaryImplA<T> {
@@ -418,7 +460,7 @@ impl<T: Copy + Datum + Add + Mul + Zero + FloatLike> MatMulUnaryImplA<T> {
                 );
             }
         }
-        Ok(MatMulUnaryImplA { geo, packed_bs })
+        Ok(MatMulUnaryImplA { geo, packed_bs: Arc::new(packed_bs) })
     }
 }
 
@@ -431,12 +473,18 @@ impl<T: Copy + Datum + Add + Mul + Zero + FloatLike> Op for MatMulUnaryImplA<T>
         Ok(Some(format!("{:?}", self.geo.mm)))
     }
 
-    fn cost(&self, _inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
         let mul = self.geo.c_shape_prefix.iter().product::<usize>();
-        Ok(tvec!((
-            Cost::FMA(T::datum_type()),
-            (self.geo.mm.m() * self.geo.mm.n() * self.geo.mm.k() * mul).to_dim()
-        )))
+        let mut cost = tvec!(
+            (
+                Cost::FMA(T::datum_type()),
+                (self.geo.mm.m() * self.geo.mm.n() * self.geo.mm.k() * mul).to_dim()
+            ),
+            Cost::read(inputs),
+            Cost::write(T::datum_type(), self.geo.c_shape.iter().product::<usize>().to_dim())
+        );
+        cost.extend(Cost::params(&self.packed_bs));
+        Ok(cost)
     }
 }
 
@@ -510,6 +558,11 @@ impl Op for MatMulUnaryB {
     fn name(&self) -> Cow<str> {
         "MatMulUnaryB".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let a_shape = self.a.shape().iter().map(|d| d.to_dim()).collect();
+        unary_cost(a_shape, inputs[0].shape.to_tvec(), inputs[0].datum_type, inputs, &self.a)
+    }
 }
 
 impl StatelessOp for MatMulUnaryB {
@@ -9,11 +9,13 @@ use num_traits::Float;
 use num_traits::Zero;
 
 element_map!(Abs, [f16, f32, i32], |x| x.abs());
-element_map!(Exp, [f16, f32, f64], |x| x.exp());
-element_map!(Ln, [f16, f32, f64], |x| x.ln());
-element_map!(Sqrt, [f16, f32, f64], |x| x.sqrt());
-element_map!(Recip, [f16, f32], |x| x.recip());
-element_map!(Rsqrt, [f16, f32], |x| x.sqrt().recip());
+element_map!(Exp, [f16, f32, f64], |x| x.exp();
+    slice: f32 => |xs| (tract_linalg::ops().sexp)().run(xs);
+    cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Ln, [f16, f32, f64], |x| x.ln(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Sqrt, [f16, f32, f64], |x| x.sqrt(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Recip, [f16, f32], |x| x.recip(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Rsqrt, [f16, f32], |x| x.sqrt().recip(); cost: |dt| tvec!((Cost::Special(dt), 2)));
 
 element_map!(Ceil, [f16, f32, f64], |x| x.ceil());
 element_map!(Floor, [f16, f32, f64], |x| x.floor());
@@ -26,30 +28,33 @@ element_map_with_params!(Clip, [f16, f32, f64], { min: f32, max: f32 },
     }
 );
 
-element_map!(Cos, [f16, f32, f64], |x| x.cos());
-element_map!(Sin, [f16, f32, f64], |x| x.sin());
-element_map!(Tan, [f16, f32, f64], |x| x.tan());
-element_map!(Acos, [f16, f32, f64], |x| x.acos());
-element_map!(Asin, [f16, f32, f64], |x| x.asin());
-element_map!(Atan, [f16, f32, f64], |x| x.atan());
+element_map!(Cos, [f16, f32, f64], |x| x.cos(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Sin, [f16, f32, f64], |x| x.sin(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Tan, [f16, f32, f64], |x| x.tan(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Acos, [f16, f32, f64], |x| x.acos(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Asin, [f16, f32, f64], |x| x.asin(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Atan, [f16, f32, f64], |x| x.atan(); cost: |dt| tvec!((Cost::Special(dt), 1)));
 
-element_map!(Cosh, [f16, f32, f64], |x| x.cosh());
-element_map!(Sinh, [f16, f32, f64], |x| x.sinh());
-element_map!(Tanh, [f16, f32, f64], |x| x.tanh());
-element_map!(Acosh, [f16, f32, f64], |x| x.acosh());
-element_map!(Asinh, [f16, f32, f64], |x| x.asinh());
-element_map!(Atanh, [f16, f32, f64], |x| x.atanh());
+element_map!(Cosh, [f16, f32, f64], |x| x.cosh(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Sinh, [f16, f32, f64], |x| x.sinh(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Tanh, [f16, f32, f64], |x| x.tanh(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Acosh, [f16, f32, f64], |x| x.acosh(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Asinh, [f16, f32, f64], |x| x.asinh(); cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_map!(Atanh, [f16, f32, f64], |x| x.atanh(); cost: |dt| tvec!((Cost::Special(dt), 1)));
 
 element_map!(Neg, [i8, i16, i32, i64, f16, f32, f64, TDim], |x| -x);
 element_bin!(Add, [u8, u16, i8, i16, i32, i64, f16, f32, f64, TDim] { |a, b| a + b });
 element_bin!(Sub, [u8, u16, i8, i16, i32, i64, f16, f32, f64, TDim] { |a, b| a - b });
 element_bin!(Mul, [u8, u16, i8, i16, i32, i64, f16, f32, f64, TDim] { |a, b| a * b });
-element_bin!(Div, [u8, u16, i8, i16, i32, i64, f16, f32, f64, TDim] { |a, b| a / b });
-element_bin!(Rem, [u8, u16, i8, i16, i32, i64, f16, f32, f64, TDim] { |a, b| a % b });
+element_bin!(Div, [u8, u16, i8, i16, i32, i64, f16, f32, f64, TDim] { |a, b| a / b };
+    cost: |dt| tvec!((Cost::Special(dt), 1)));
+element_bin!(Rem, [u8, u16, i8, i16, i32, i64, f16, f32, f64, TDim] { |a, b| a % b };
+    cost: |dt| tvec!((Cost::Special(dt), 1)));
 element_bin!(Pow, match
      f16 => f16 { |a:f16, b| a.powf(b) },
      f32 => f32 { |a:f32, b| a.powf(b) },
-     f64 => f64 { |a:f64, b| a.powf(b) }
+     f64 => f64 { |a:f64, b| a.powf(b) };
+     cost: |dt| tvec!((Cost::Special(dt), 1))
 );
 
 element_map!(Sign, match
@@ -105,4 +110,35 @@ mod tests {
         let b = arr2(&[[1., 0.], [0., 0.]]);
         assert_eq!(a.dot(&b), arr2(&[[1., 0.], [3., 0.]]));
     }
+
+    #[test]
+    fn cost_of_broadcasting_add() {
+        use crate::internal::*;
+        let a = TypedTensorInfo::from(Tensor::from(arr2(&[[1f32, 2., 3.], [4., 5., 6.]])));
+        let b = TypedTensorInfo::from(rctensor1(&[1f32, 2., 3.]));
+        let cost = super::Add::default().cost(&[&a, &b]).unwrap();
+        assert_eq!(
+            cost,
+            tvec!(
+                (Cost::FLOP(DatumType::F32), 6.to_dim()),
+                (Cost::MemoryRead, 36.to_dim()),
+                (Cost::MemoryWrite, 24.to_dim())
+            )
+        );
+    }
+
+    #[test]
+    fn cost_of_exp() {
+        use crate::internal::*;
+        let a = TypedTensorInfo::from(rctensor1(&[1f32, 2., 3.]));
+        let cost = super::Exp::default().cost(&[&a]).unwrap();
+        assert_eq!(
+            cost,
+            tvec!(
+                (Cost::Special(DatumType::F32), 3.to_dim()),
+                (Cost::MemoryRead, 12.to_dim()),
+                (Cost::MemoryWrite, 12.to_dim())
+            )
+        );
+    }
 }
@@ -11,6 +11,7 @@ pub mod macros;
 pub mod array;
 pub mod cast;
 pub mod cnn;
+pub mod control_flow;
 pub mod identity;
 #[cfg(features = "image_ops")]
 pub mod image;
@@ -37,9 +38,69 @@ pub fn check_output_arity(outputs: &[TensorProxy], expected: usize) -> TractResu
     }
 }
 
+/// Resources needed to run an op, as reported by `Op::cost`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Cost {
+    /// Fused multiply-adds.
     FMA(DatumType),
+    /// Other arithmetic operations (additions, comparisons, min, max...).
+    FLOP(DatumType),
+    /// Divisions, square roots and transcendental functions.
+    Special(DatumType),
+    /// Elements folded by a reduction.
+    Reduce(DatumType),
+    /// Parameters embedded in the op, in elements.
+    Params(DatumType),
+    /// Bytes read from the inputs and parameters.
+    MemoryRead,
+    /// Bytes written to the outputs.
+    MemoryWrite,
+}
+
+impl Cost {
+    /// Reading all of `inputs`.
+    pub fn read(inputs: &[&TypedTensorInfo]) -> (Cost, TDim) {
+        (Cost::MemoryRead, inputs.iter().map(|i| i.shape.size() * i.datum_type.size_of()).sum())
+    }
+
+    /// Writing `len` elements of `dt`.
+    pub fn write(dt: DatumType, len: TDim) -> (Cost, TDim) {
+        (Cost::MemoryWrite, len * dt.size_of())
+    }
+
+    /// Holding `tensor` as a parameter, and reading it.
+    pub fn params(tensor: &Tensor) -> TVec<(Cost, TDim)> {
+        Cost::params_len(tensor.datum_type(), tensor.len())
+    }
+
+    /// Holding `len` elements of `dt` as parameters, and reading them.
+    pub fn params_len(dt: DatumType, len: usize) -> TVec<(Cost, TDim)> {
+        tvec!((Cost::Params(dt), len.to_dim()), (Cost::MemoryRead, (len * dt.size_of()).to_dim()))
+    }
+
+    /// Applying `per_element` costs to `len` elements computed from `inputs`,
+    /// and writing them as `dt`.
+    pub fn element_wise(
+        inputs: &[&TypedTensorInfo],
+        per_element: TVec<(Cost, usize)>,
+        len: TDim,
+        dt: DatumType,
+    ) -> TVec<(Cost, TDim)> {
+        let mut cost: TVec<(Cost, TDim)> =
+            per_element.into_iter().map(|(c, n)| (c, len * n)).collect();
+        cost.push(Cost::read(inputs));
+        cost.push(Cost::write(dt, len));
+        cost
+    }
+
+    /// Reading `inputs` and writing them back, for ops moving data
+    /// around without changing it.
+    pub fn copy(inputs: &[&TypedTensorInfo]) -> TVec<(Cost, TDim)> {
+        tvec!(
+            Cost::read(inputs),
+            (Cost::MemoryWrite, Cost::read(inputs).1)
+        )
+    }
 }
 
 use crate::internal::*;
@@ -30,6 +30,15 @@ impl Op for ArgMaxMin {
     fn name(&self) -> Cow<str> {
         "ArgMaxMin".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let len = inputs[0].shape.size();
+        Ok(tvec!(
+            (Cost::Reduce(inputs[0].datum_type), len),
+            Cost::read(inputs),
+            Cost::write(i64::datum_type(), len / inputs[0].shape.dim(self.axis))
+        ))
+    }
 }
 
 impl StatelessOp for ArgMaxMin {
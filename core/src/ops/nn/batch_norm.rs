@@ -35,6 +35,19 @@ impl Op for BatchNorm {
         "BatchNorm".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        let mut cost = Cost::element_wise(
+            inputs,
+            tvec!((Cost::FMA(dt), 1)),
+            inputs[0].shape.size(),
+            dt,
+        );
+        // slope and intercept, computed once per channel
+        cost.push((Cost::Special(dt), inputs[1].shape.size() * 2));
+        Ok(cost)
+    }
+
     fn declutter(
         &self,
         model: &TypedModel,
@@ -173,6 +186,18 @@ where
         format!("FixedBatchNorm<{:?}>", T::datum_type()).into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let mut cost = Cost::element_wise(
+            inputs,
+            tvec!((Cost::FMA(T::datum_type()), 1)),
+            inputs[0].shape.size(),
+            T::datum_type(),
+        );
+        cost.extend(Cost::params(&self.slope.clone().into_tensor()));
+        cost.extend(Cost::params(&self.intercept.clone().into_tensor()));
+        Ok(cost)
+    }
+
     fn pulsify(
         &self,
         _source: &NormalizedModel,
@@ -33,6 +33,11 @@ impl Op for GlobalAvgPool {
     fn name(&self) -> Cow<str> {
         "GlobalAvgPool".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        Ok(cost(inputs, tvec!(), tvec!((Cost::Special(dt), 1))))
+    }
 }
 
 impl StatelessOp for GlobalAvgPool {
@@ -90,6 +95,16 @@ impl Op for GlobalLpPool {
     fn name(&self) -> Cow<str> {
         "GlobalLpPool".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        let per_input = if self.p <= 2 {
+            tvec!((Cost::FLOP(dt), 1))
+        } else {
+            tvec!((Cost::FLOP(dt), 1), (Cost::Special(dt), 1))
+        };
+        Ok(cost(inputs, per_input, tvec!((Cost::Special(dt), 2))))
+    }
 }
 
 impl StatelessOp for GlobalLpPool {
@@ -141,6 +156,10 @@ impl Op for GlobalMaxPool {
     fn name(&self) -> Cow<str> {
         "GlobalMaxPool".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(cost(inputs, tvec!(), tvec!()))
+    }
 }
 
 impl StatelessOp for GlobalMaxPool {
@@ -161,6 +180,24 @@ impl InferenceRulesOp for GlobalMaxPool {
     }
 }
 
+/// Reduction of all spatial axes, with extra costs for each input and each
+/// output element.
+fn cost(
+    inputs: &[&TypedTensorInfo],
+    per_input: TVec<(Cost, usize)>,
+    per_output: TVec<(Cost, usize)>,
+) -> TVec<(Cost, TDim)> {
+    let dt = inputs[0].datum_type;
+    let len = inputs[0].shape.size();
+    let out_len = inputs[0].shape.dim(0) * inputs[0].shape.dim(1);
+    let mut cost = tvec!((Cost::Reduce(dt), len));
+    cost.extend(per_input.into_iter().map(|(c, n)| (c, len * n)));
+    cost.extend(per_output.into_iter().map(|(c, n)| (c, out_len * n)));
+    cost.push(Cost::read(inputs));
+    cost.push(Cost::write(dt, out_len));
+    cost
+}
+
 fn rules<'r, 'p: 'r, 's: 'r>(
     s: &mut Solver<'r>,
     inputs: &'p [TensorProxy],
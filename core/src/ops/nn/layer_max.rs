@@ -38,6 +38,11 @@ impl Op for LayerHardmax {
     fn name(&self) -> Cow<str> {
         "LayerHardmax".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        Ok(Cost::element_wise(inputs, tvec!((Cost::Reduce(dt), 1)), inputs[0].shape.size(), dt))
+    }
 }
 
 impl StatelessOp for LayerHardmax {
@@ -93,6 +98,11 @@ impl Op for LayerLogSoftmax {
     fn name(&self) -> Cow<str> {
         "LayerLogSoftmax".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        Ok(Cost::element_wise(inputs, per_element(dt, 3), inputs[0].shape.size(), dt))
+    }
 }
 
 impl StatelessOp for LayerLogSoftmax {
@@ -142,17 +152,37 @@ impl LayerSoftmax {
         });
         Ok(tvec!(array.into_shape(shape)?.into_arc_tensor()))
     }
+
+    fn eval_f32(&self, input: Arc<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
+        let mut tensor = input.into_tensor();
+        let axis = if self.axis < 0 { tensor.shape().len() as isize + self.axis } else { self.axis }
+            as usize;
+        let layer_len: usize = tensor.shape()[axis..].iter().product();
+        if layer_len > 0 {
+            let softmax = (tract_linalg::ops().ssoftmax)();
+            tensor.as_slice_mut::<f32>()?.chunks_mut(layer_len).for_each(|l| softmax.run(l));
+        }
+        Ok(tvec!(tensor.into_arc_tensor()))
+    }
 }
 
 impl Op for LayerSoftmax {
     fn name(&self) -> Cow<str> {
         "LayerSoftmax".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        Ok(Cost::element_wise(inputs, per_element(dt, 2), inputs[0].shape.size(), dt))
+    }
 }
 
 impl StatelessOp for LayerSoftmax {
     fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let input = args_1!(inputs);
+        if input.datum_type() == f32::datum_type() {
+            return self.eval_f32(input);
+        }
         dispatch_floatlike!(Self::eval_t(input.datum_type())(self, input))
     }
 }
@@ -168,6 +198,12 @@ impl InferenceRulesOp for LayerSoftmax {
     }
 }
 
+/// Max and sum reductions, a subtraction, and `special` transcendental
+/// functions or divisions for each element.
+fn per_element(dt: DatumType, special: usize) -> TVec<(Cost, usize)> {
+    tvec!((Cost::Reduce(dt), 2), (Cost::FLOP(dt), 1), (Cost::Special(dt), special))
+}
+
 fn rules<'r, 'p: 'r, 's: 'r>(
     s: &mut Solver<'r>,
     inputs: &'p [TensorProxy],
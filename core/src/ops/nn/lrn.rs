@@ -39,6 +39,12 @@ impl Op for Lrn {
     fn name(&self) -> Cow<str> {
         "Lrn".into()
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        let per_element = tvec!((Cost::FMA(dt), self.size + 1), (Cost::Special(dt), 2));
+        Ok(Cost::element_wise(inputs, per_element, inputs[0].shape.size(), dt))
+    }
 }
 
 impl StatelessOp for Lrn {
@@ -21,8 +21,10 @@ pub use self::tanh::Tanh;
 use num_traits::AsPrimitive;
 
 element_map!(Relu, [f32, i32], |x| if x < 0 as _ { 0 as _ } else { x });
-element_map!(Softplus, [f32], |x| (x.exp() + 1.0).ln());
-element_map!(Softsign, [f32], |x| x / (x.abs() + 1.0));
+element_map!(Softplus, [f32], |x| (x.exp() + 1.0).ln();
+    cost: |dt| tvec!((Cost::FLOP(dt), 1), (Cost::Special(dt), 2)));
+element_map!(Softsign, [f32], |x| x / (x.abs() + 1.0);
+    cost: |dt| tvec!((Cost::FLOP(dt), 2), (Cost::Special(dt), 1)));
 
 element_map_with_params!(
     Elu,
@@ -38,7 +40,8 @@ element_map_with_params!(
         } else {
             x
         }
-    }
+    };
+    cost: |dt| tvec!((Cost::FMA(dt), 1), (Cost::Special(dt), 1))
 );
 
 element_map_with_params!(Hardsigmoid, [f32, f64], {alpha: f32, beta: f32},
@@ -46,7 +49,8 @@ element_map_with_params!(Hardsigmoid, [f32, f64], {alpha: f32, beta: f32},
     where T: Datum+::num_traits::Float, f32: ::num_traits::AsPrimitive<T>
     {
         (hs.alpha.as_() * x + hs.beta.as_()).min(1.0.as_()).max(0.0.as_())
-    }
+    };
+    cost: |dt| tvec!((Cost::FMA(dt), 1), (Cost::FLOP(dt), 2))
 );
 
 element_map_with_params!(
@@ -63,7 +67,8 @@ element_map_with_params!(
         } else {
             x
         }
-    }
+    };
+    cost: |dt| tvec!((Cost::FLOP(dt), 2))
 );
 
 element_map_with_params!(ParametricSoftplus, [f32, f64], {alpha: f32, beta: f32},
@@ -71,7 +76,8 @@ element_map_with_params!(ParametricSoftplus, [f32, f64], {alpha: f32, beta: f32}
     where T: Datum+::num_traits::Float, f32: ::num_traits::AsPrimitive<T>
     {
         s.alpha.as_() * ((s.beta.as_() * x).exp() + 1.0.as_()).ln()
-    }
+    };
+    cost: |dt| tvec!((Cost::FLOP(dt), 3), (Cost::Special(dt), 2))
 );
 
 element_map_with_params!(ScaledTanh, [f32, f64], {alpha: f32, beta: f32},
@@ -79,7 +85,8 @@ element_map_with_params!(ScaledTanh, [f32, f64], {alpha: f32, beta: f32},
     where T: Datum+::num_traits::Float, f32: ::num_traits::AsPrimitive<T>
     {
         s.alpha.as_() * (s.beta.as_() * x).tanh()
-    }
+    };
+    cost: |dt| tvec!((Cost::FLOP(dt), 2), (Cost::Special(dt), 1))
 );
 
 element_map_with_params!(Selu, [f32, f64], {alpha: f32, gamma: f32},
@@ -91,7 +98,8 @@ element_map_with_params!(Selu, [f32, f64], {alpha: f32, gamma: f32},
         } else {
             s.gamma.as_() * x
         }
-    }
+    };
+    cost: |dt| tvec!((Cost::FMA(dt), 1), (Cost::FLOP(dt), 1), (Cost::Special(dt), 1))
 );
 
 element_map_with_params!(
@@ -108,5 +116,6 @@ element_map_with_params!(
         } else {
             x
         }
-    }
+    };
+    cost: |dt| tvec!((Cost::FLOP(dt), 1))
 );
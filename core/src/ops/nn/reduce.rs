@@ -30,6 +30,8 @@ macro_rules! reduce_floatlike {
 
 #[derive(Clone, Copy, Debug)]
 pub enum Reducer {
+    All,
+    Any,
     L1,
     L2,
     LogSum,
@@ -46,6 +48,14 @@ impl Reducer {
     fn reduce(&self, reduce: &Reduce, input: Arc<Tensor>) -> TractResult<Arc<Tensor>> {
         let dt = input.datum_type();
         match self {
+            Reducer::All => match dt {
+                DatumType::Bool => self.reduce_t::<bool, _>(reduce, input, all_t),
+                _ => bail!("{:?} is not a boolean, can not reduce with All", dt),
+            },
+            Reducer::Any => match dt {
+                DatumType::Bool => self.reduce_t::<bool, _>(reduce, input, any_t),
+                _ => bail!("{:?} is not a boolean, can not reduce with Any", dt),
+            },
             Reducer::L1 => match dt {
                 DatumType::U8 => self.reduce_t::<u8, _>(reduce, input, l1u_t),
                 DatumType::U16 => self.reduce_t::<u16, _>(reduce, input, l1u_t),
@@ -111,6 +121,14 @@ impl Reducer {
     }
 }
 
+fn all_t<'a>(v: ArrayViewD<'a, bool>) -> bool {
+    v.iter().all(|&b| b)
+}
+
+fn any_t<'a>(v: ArrayViewD<'a, bool>) -> bool {
+    v.iter().any(|&b| b)
+}
+
 fn l1s_t<'a, T>(v: ArrayViewD<'a, T>) -> T
 where
     T: Copy + Datum + num_traits::Signed + num_traits::Zero,
@@ -243,6 +261,29 @@ impl Op for Reduce {
         let id = target.chain_after(input, &*node.name, self.clone(), tvec!(fact))?;
         Ok(tvec!(OutletId::new(id, 0)))
     }
+
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        let dt = inputs[0].datum_type;
+        let shape = inputs[0].shape.to_tvec();
+        let len = inputs[0].shape.size();
+        let out_len: TDim = shape
+            .iter()
+            .enumerate()
+            .filter(|(ix, _)| !self.must_reduce(*ix, shape.len()))
+            .map(|(_, d)| *d)
+            .product();
+        let mut cost = tvec!((Cost::Reduce(dt), len));
+        match self.reducer {
+            Reducer::L1 | Reducer::SumSquare => cost.push((Cost::FLOP(dt), len)),
+            Reducer::L2 => cost.extend(tvec!((Cost::FLOP(dt), len), (Cost::Special(dt), out_len))),
+            Reducer::LogSum | Reducer::Mean => cost.push((Cost::Special(dt), out_len)),
+            Reducer::LogSumExp => cost.push((Cost::Special(dt), len + out_len)),
+            _ => (),
+        }
+        cost.push(Cost::read(inputs));
+        cost.push(Cost::write(dt, out_len));
+        Ok(cost)
+    }
 }
 
 impl StatelessOp for Reduce {
@@ -260,6 +301,7 @@ impl InferenceRulesOp for Reduce {
     ) -> InferenceResult {
         check_input_arity(&inputs, 1)?;
         check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
         if self.keep_dims {
             s.equals(&inputs[0].rank, &outputs[0].rank)?;
         } else if let Some(axes) = self.axes.as_ref() {
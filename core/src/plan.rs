@@ -141,6 +141,33 @@ impl<TI: TensorInfo, M: Borrow<Model<TI>>, P: Borrow<SimplePlan<TI, M>>> SimpleS
         inputs: TVec<Tensor>,
         plan: usize,
     ) -> TractResult<TVec<Arc<Tensor>>> {
+        self.run_plan_with_hook(inputs, plan, |_, _| Ok(()))
+    }
+
+    /// Run the default plan, calling `hook` with each node outputs as soon
+    /// as they are available (model inputs included).
+    pub fn run_with_hook<F>(
+        &mut self,
+        inputs: TVec<Tensor>,
+        hook: F,
+    ) -> TractResult<TVec<Arc<Tensor>>>
+    where
+        F: FnMut(&Node<TI>, &[Arc<Tensor>]) -> TractResult<()>,
+    {
+        self.run_plan_with_hook(inputs, 0, hook)
+    }
+
+    /// Run the plan-th plan, calling `hook` with each node outputs as soon
+    /// as they are available (model inputs included).
+    pub fn run_plan_with_hook<F>(
+        &mut self,
+        inputs: TVec<Tensor>,
+        plan: usize,
+        mut hook: F,
+    ) -> TractResult<TVec<Arc<Tensor>>>
+    where
+        F: FnMut(&Node<TI>, &[Arc<Tensor>]) -> TractResult<()>,
+    {
         let mut result = tvec!();
         {
             self.set_inputs(inputs)?;
@@ -233,6 +260,9 @@ impl<TI: TensorInfo, M: Borrow<Model<TI>>, P: Borrow<SimplePlan<TI, M>>> SimpleS
 
                     values[node.id] = Some(vs);
                 }
+                if let Some(vs) = &values[node.id] {
+                    hook(node, vs).map_err(|e| format!("Hook on {}: {}", node, e))?;
+                }
                 for flush in &plan.flush_lists[step] {
                     trace!("  flushing node {} {}", flush, node);
                     values[*flush] = None;
@@ -369,3 +399,39 @@ impl<TI: TensorInfo, M: Borrow<Model<TI>>, P: Borrow<SimplePlan<TI, M>>> SimpleS
         self.plan().model()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::math::{Add, Mul};
+
+    #[test]
+    fn hook_sees_every_node_in_order() {
+        let mut model = InferenceModel::default();
+        let input = model.add_source_default("input").unwrap();
+        let three = model.add_const("three", rctensor0(3f32)).unwrap();
+        let add = model.add_node_default("add", Add::default()).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(add, 0)).unwrap();
+        model.add_edge(OutletId::new(three, 0), InletId::new(add, 1)).unwrap();
+        let mul = model.add_node_default("mul", Mul::default()).unwrap();
+        model.add_edge(OutletId::new(add, 0), InletId::new(mul, 0)).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(mul, 1)).unwrap();
+        model.set_output_outlets(&[OutletId::new(mul, 0)]).unwrap();
+        let plan = SimplePlan::new(&model).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let mut seen = vec![];
+        let outputs = state
+            .run_with_hook(tvec!(tensor1(&[1f32, 2.0])), |node, values| {
+                seen.push((node.id, values.to_vec()));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(seen.iter().map(|s| s.0).collect::<Vec<_>>(), plan.order);
+        let value_of = |id: usize| &seen.iter().find(|s| s.0 == id).unwrap().1;
+        assert_eq!(value_of(input), &vec![rctensor1(&[1f32, 2.0])]);
+        assert_eq!(value_of(three), &vec![rctensor0(3f32)]);
+        assert_eq!(value_of(add), &vec![rctensor1(&[4f32, 5.0])]);
+        assert_eq!(value_of(mul), &vec![rctensor1(&[4f32, 10.0])]);
+        assert_eq!(outputs, tvec!(rctensor1(&[4f32, 10.0])));
+    }
+}
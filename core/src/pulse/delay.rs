@@ -74,6 +74,10 @@ impl Op for Delay {
         "Delay".into()
     }
 
+    fn cost(&self, inputs: &[&TypedTensorInfo]) -> TractResult<TVec<(Cost, TDim)>> {
+        Ok(Cost::copy(inputs))
+    }
+
     impl_op_same_as!();
 }
 
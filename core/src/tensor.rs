@@ -3,7 +3,9 @@ use crate::internal::*;
 use ndarray::prelude::*;
 use std::alloc;
 use std::fmt;
+use std::fs::File;
 use std::mem::size_of;
+use std::path::Path;
 
 use tract_linalg::align;
 use tract_linalg::f16::f16;
@@ -12,6 +14,8 @@ use tract_linalg::f16::f16;
 use serde::ser::{Serialize, Serializer};
 use std::sync::Arc;
 
+pub use memmap::Mmap;
+
 pub mod litteral;
 
 /// Tensor is a concrete tensor in tract.
@@ -20,7 +24,33 @@ pub struct Tensor {
     dt: DatumType,
     shape: TVec<usize>,
     alignment: usize,
-    data: Vec<u8>,
+    data: Storage,
+}
+
+/// Bytes backing a tensor.
+#[derive(Clone)]
+enum Storage {
+    /// Aligned buffer owned by the tensor.
+    Owned(Vec<u8>),
+    /// Region of a read-only memory-mapped file, shared between clones.
+    Mapped { map: Arc<Mmap>, offset: usize, len: usize },
+}
+
+impl Storage {
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Storage::Owned(v) => v,
+            Storage::Mapped { map, offset, len } => &map[*offset..*offset + *len],
+        }
+    }
+
+    /// Get an owned buffer, copying mapped data.
+    fn into_vec(self, alignment: usize) -> Vec<u8> {
+        match self {
+            Storage::Owned(v) => v,
+            mapped => align::realign_slice(mapped.as_bytes(), alignment),
+        }
+    }
 }
 
 impl Clone for Tensor {
@@ -30,14 +60,23 @@ impl Clone for Tensor {
             "Invalid alignment in tensor ({})",
             self.alignment
         );
-        Tensor {
-            shape: self.shape.clone(),
-            data: align::realign_slice(&self.data, self.alignment),
-            ..*self
-        }
+        let data = match &self.data {
+            Storage::Owned(v) => Storage::Owned(align::realign_slice(v, self.alignment)),
+            mapped => mapped.clone(),
+        };
+        Tensor { shape: self.shape.clone(), data, ..*self }
     }
 }
 
+/// Map a whole file in memory, to build tensors with `Tensor::from_mmap`.
+///
+/// The file must not be modified while it is mapped.
+pub unsafe fn map_file<P: AsRef<Path>>(path: P) -> TractResult<Arc<Mmap>> {
+    let file = File::open(path.as_ref())
+        .map_err(|e| format!("Could not open {:?}: {}", path.as_ref(), e))?;
+    Ok(Arc::new(Mmap::map(&file)?))
+}
+
 impl Default for Tensor {
     fn default() -> Tensor {
         Tensor::from(arr0(0f32))
@@ -61,7 +100,13 @@ impl Tensor {
                 })?);
             Vec::from_raw_parts(aligned_buffer as _, len, len)
         };
-        Ok(Tensor { null: false, dt: T::datum_type(), shape: shape.into(), alignment, data })
+        Ok(Tensor {
+            null: false,
+            dt: T::datum_type(),
+            shape: shape.into(),
+            alignment,
+            data: Storage::Owned(data),
+        })
     }
 
     /// Create an tensor from raw data.
@@ -73,20 +118,76 @@ impl Tensor {
             null: false,
             dt: T::datum_type(),
             shape: shape.into(),
-            data,
+            data: Storage::Owned(data),
             alignment: T::datum_type().alignment(),
         })
     }
 
+    /// Create a tensor backed by a region of a memory-mapped file.
+    ///
+    /// The data is not copied: it is paged in on demand, and shared with the
+    /// clones of the tensor and with other processes mapping the same file.
+    /// The region is copied if it is not aligned for `dt` (check with
+    /// `is_mapped`), and before the tensor is mutated.
+    ///
+    /// The region must hold valid values for `dt`, and the file must not be
+    /// modified while it is mapped.
+    pub unsafe fn from_mmap(
+        dt: DatumType,
+        shape: &[usize],
+        map: Arc<Mmap>,
+        offset: usize,
+    ) -> TractResult<Tensor> {
+        if dt == DatumType::String || dt == DatumType::TDim {
+            bail!("Can not map {:?} tensor data", dt)
+        }
+        let len = shape.iter().product::<usize>() * dt.size_of();
+        if offset.checked_add(len).map(|end| end > map.len()).unwrap_or(true) {
+            bail!(
+                "Mapped region ({} bytes at {}) overflows the file ({} bytes)",
+                len,
+                offset,
+                map.len()
+            )
+        }
+        let alignment = dt.alignment();
+        let mut data = Storage::Mapped { map, offset, len };
+        if data.as_bytes().as_ptr() as usize % alignment != 0 {
+            debug!("Copying misaligned mapped region ({} bytes at {})", len, offset);
+            data = Storage::Owned(data.into_vec(alignment));
+        }
+        Ok(Tensor { null: false, dt, shape: shape.into(), alignment, data })
+    }
+
+    /// Check weather the tensor data lives in a memory-mapped file.
+    pub fn is_mapped(&self) -> bool {
+        match self.data {
+            Storage::Mapped { .. } => true,
+            Storage::Owned(_) => false,
+        }
+    }
+
+    /// Copy mapped data to an owned buffer, so that it can be mutated.
+    fn make_owned(&mut self) {
+        if self.is_mapped() {
+            let data = std::mem::replace(&mut self.data, Storage::Owned(vec![]));
+            self.data = Storage::Owned(data.into_vec(self.alignment));
+        }
+    }
+
     /// Re-align a tensor to a byte alignment.
     pub fn into_aligned(self, alignment: usize) -> TractResult<Tensor> {
-        Ok(Tensor {
-            null: self.null,
-            dt: self.datum_type(),
-            shape: self.shape,
-            data: align::realign_vec(self.data, alignment),
-            alignment,
-        })
+        let data = match self.data {
+            Storage::Owned(v) => Storage::Owned(align::realign_vec(v, alignment)),
+            mapped => {
+                if mapped.as_bytes().as_ptr() as usize % alignment == 0 {
+                    mapped
+                } else {
+                    Storage::Owned(mapped.into_vec(alignment))
+                }
+            }
+        };
+        Ok(Tensor { null: self.null, dt: self.dt, shape: self.shape, data, alignment })
     }
 
     /// Creates a null tensor (this is rare, and should stay that way).
@@ -96,7 +197,13 @@ impl Tensor {
 
     /// Creates a null tensor (this is rare, and should stay that way).
     pub unsafe fn null_dt(dt: DatumType, shape: &[usize]) -> TractResult<Tensor> {
-        Ok(Tensor { null: true, dt, shape: shape.into(), data: vec![], alignment: dt.alignment() })
+        Ok(Tensor {
+            null: true,
+            dt,
+            shape: shape.into(),
+            data: Storage::Owned(vec![]),
+            alignment: dt.alignment(),
+        })
     }
 
     /// Check weather self is a null tensor.
@@ -109,6 +216,11 @@ impl Tensor {
         &self.shape
     }
 
+    /// Get the number of elements of the tensor.
+    pub fn len(&self) -> usize {
+        self.shape.iter().product()
+    }
+
     /// Reshape the tensor to `shape`.
     pub unsafe fn into_shape(self, shape: &[usize]) -> TractResult<Tensor> {
         Ok(Tensor { shape: shape.into(), ..self })
@@ -183,7 +295,8 @@ impl Tensor {
         if self.is_null() {
             bail!("Null tensor")
         }
-        let casted = unsafe { vec_to_datum::<D>(self.data) };
+        let alignment = D::datum_type().alignment();
+        let casted = unsafe { vec_to_datum::<D>(self.data.into_vec(alignment)) };
         unsafe { Ok(ArrayD::from_shape_vec_unchecked(&*self.shape, casted)) }
     }
 
@@ -199,9 +312,9 @@ impl Tensor {
         if self.is_null() {
             bail!("Null tensor")
         }
-        if self.data.len() != 0 {
+        if self.data.as_bytes().len() != 0 {
             unsafe {
-                return Ok(ArrayViewD::from_shape_ptr(&*self.shape, self.data.as_ptr() as _));
+                return Ok(ArrayViewD::from_shape_ptr(&*self.shape, self.as_ptr::<D>()?));
             }
         } else {
             return Ok(ArrayViewD::from_shape(&*self.shape, &[])?);
@@ -221,8 +334,8 @@ impl Tensor {
             bail!("Null tensor")
         }
         let shape = self.shape.clone();
-        if self.data.len() != 0 {
-            unsafe { Ok(ArrayViewMutD::from_shape_ptr(&*shape, self.data.as_mut_ptr() as _)) }
+        if self.data.as_bytes().len() != 0 {
+            unsafe { Ok(ArrayViewMutD::from_shape_ptr(&*shape, self.as_ptr_mut::<D>()?)) }
         } else {
             return Ok(ArrayViewMutD::from_shape(&*self.shape, &mut [])?);
         }
@@ -240,11 +353,15 @@ impl Tensor {
         if self.is_null() {
             bail!("Null tensor")
         }
-        Ok(self.data.as_ptr() as *const D)
+        Ok(self.data.as_bytes().as_ptr() as *const D)
     }
 
     /// Access the data as a mutable pointer.
+    ///
+    /// Mapped data is copied first.
     pub fn as_ptr_mut<D: Datum>(&mut self) -> TractResult<*mut D> {
+        self.as_ptr::<D>()?;
+        self.make_owned();
         self.as_ptr::<D>().map(|p| p as *mut D)
     }
 
@@ -254,7 +371,7 @@ impl Tensor {
         unsafe {
             Ok(std::slice::from_raw_parts::<D>(
                 self.as_ptr()?,
-                self.data.len() / datum_size,
+                self.data.as_bytes().len() / datum_size,
             ))
         }
     }
@@ -262,11 +379,9 @@ impl Tensor {
     /// Access the data as a mutable slice.
     pub fn as_slice_mut<D: Datum>(&mut self) -> TractResult<&mut [D]> {
         let datum_size = ::std::mem::size_of::<D>();
+        let ptr = self.as_ptr_mut()?;
         unsafe {
-            Ok(std::slice::from_raw_parts_mut::<D>(
-                self.as_ptr_mut()?,
-                self.data.len() / datum_size,
-            ))
+            Ok(std::slice::from_raw_parts_mut::<D>(ptr, self.data.as_bytes().len() / datum_size))
         }
     }
 
@@ -282,7 +397,7 @@ impl Tensor {
         if self.is_null() {
             bail!("Null tensor")
         }
-        unsafe { Ok(&*(self.data.as_ptr() as *const D)) }
+        unsafe { Ok(&*(self.data.as_bytes().as_ptr() as *const D)) }
     }
 
     /// Convert data to a new DatumType.
@@ -299,7 +414,7 @@ impl Tensor {
             null: self.null,
             dt: Target::datum_type(),
             shape: self.shape.clone(),
-            data: vec_to_u8(data),
+            data: Storage::Owned(vec_to_u8(data)),
             alignment: Target::datum_type().alignment(),
         })
     }
@@ -450,7 +565,7 @@ impl<D: ::ndarray::Dimension, T: Datum> From<Array<T, D>> for Tensor {
             null: false,
             dt: T::datum_type(),
             shape,
-            data: raw_data,
+            data: Storage::Owned(raw_data),
             alignment: T::datum_type().alignment(),
         }
     }
@@ -501,3 +616,53 @@ impl IntoArcTensor for Arc<Tensor> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn mapped(name: &str, values: &[f32]) -> Arc<Mmap> {
+        let path = std::env::temp_dir().join(format!("tract-{}-{}", std::process::id(), name));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&[0u8; 4]).unwrap();
+        for v in values {
+            file.write_all(&v.to_bits().to_le_bytes()).unwrap();
+        }
+        drop(file);
+        let map = unsafe { map_file(&path).unwrap() };
+        std::fs::remove_file(&path).unwrap();
+        map
+    }
+
+    #[test]
+    fn mapped_tensor_is_copied_on_write() {
+        let map = mapped("cow", &[1.0, 2.0, 3.0, 4.0]);
+        let mut t = unsafe { Tensor::from_mmap(DatumType::F32, &[2, 2], map.clone(), 4).unwrap() };
+        assert!(t.is_mapped());
+        assert_eq!(t, Tensor::from(arr2(&[[1.0f32, 2.0], [3.0, 4.0]])));
+        let clone = t.clone();
+        assert!(clone.is_mapped());
+        t.as_slice_mut::<f32>().unwrap()[0] = 5.0;
+        assert!(!t.is_mapped());
+        assert_eq!(t.as_slice::<f32>().unwrap(), &[5.0, 2.0, 3.0, 4.0]);
+        assert_eq!(clone.as_slice::<f32>().unwrap(), &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(&map[4..8], &1.0f32.to_bits().to_le_bytes());
+    }
+
+    #[test]
+    fn misaligned_mapped_tensor_is_copied() {
+        let map = mapped("misaligned", &[1.0, 2.0]);
+        let t = unsafe { Tensor::from_mmap(DatumType::I16, &[3], map.clone(), 3).unwrap() };
+        assert!(!t.is_mapped());
+        assert_eq!(t.as_ptr::<i16>().unwrap() as usize % 2, 0);
+        let i16s = map[3..9].chunks(2).map(|c| i16::from_le_bytes([c[0], c[1]])).collect();
+        assert_eq!(t.into_array::<i16>().unwrap(), ArrayD::from_shape_vec(vec![3], i16s).unwrap());
+    }
+
+    #[test]
+    fn mapped_region_must_fit() {
+        let map = mapped("overflow", &[1.0, 2.0]);
+        assert!(unsafe { Tensor::from_mmap(DatumType::F32, &[3], map, 4) }.is_err());
+    }
+}
@@ -0,0 +1,443 @@
+//! # Tract C API
+//!
+//! Load a TensorFlow or ONNX model, optimize it, and run it from C or any
+//! language with a C FFI.
+//!
+//! Every function returns a `TRACT_RESULT`. When it is `TRACT_RESULT_KO`,
+//! `tract_get_last_error` gives the reason, on the same thread.
+//!
+//! Objects created by the library are released by the matching `_destroy`
+//! function, which also resets the caller's pointer to null.
+//!
+//! The `tract.h` header is generated by cbindgen, from the `ffi` directory:
+//!
+//! ```sh
+//! cbindgen -c cbindgen.toml -o tract.h
+//! ```
+//!
+//! CI regenerates it and fails if it differs from the committed one.
+
+#[macro_use]
+extern crate error_chain;
+#[macro_use]
+extern crate tract_core;
+extern crate tract_onnx;
+extern crate tract_tensorflow;
+
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use tract_core::internal::*;
+
+/// Outcome of a call to the library.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TRACT_RESULT {
+    TRACT_RESULT_OK = 0,
+    TRACT_RESULT_KO = 1,
+}
+
+/// Element type of a tensor.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TractDatumType {
+    TRACT_DATUM_TYPE_BOOL = 1,
+    TRACT_DATUM_TYPE_U8,
+    TRACT_DATUM_TYPE_U16,
+    TRACT_DATUM_TYPE_I8,
+    TRACT_DATUM_TYPE_I16,
+    TRACT_DATUM_TYPE_I32,
+    TRACT_DATUM_TYPE_I64,
+    TRACT_DATUM_TYPE_F16,
+    TRACT_DATUM_TYPE_F32,
+    TRACT_DATUM_TYPE_F64,
+}
+
+impl From<TractDatumType> for DatumType {
+    fn from(dt: TractDatumType) -> DatumType {
+        use TractDatumType::*;
+        match dt {
+            TRACT_DATUM_TYPE_BOOL => DatumType::Bool,
+            TRACT_DATUM_TYPE_U8 => DatumType::U8,
+            TRACT_DATUM_TYPE_U16 => DatumType::U16,
+            TRACT_DATUM_TYPE_I8 => DatumType::I8,
+            TRACT_DATUM_TYPE_I16 => DatumType::I16,
+            TRACT_DATUM_TYPE_I32 => DatumType::I32,
+            TRACT_DATUM_TYPE_I64 => DatumType::I64,
+            TRACT_DATUM_TYPE_F16 => DatumType::F16,
+            TRACT_DATUM_TYPE_F32 => DatumType::F32,
+            TRACT_DATUM_TYPE_F64 => DatumType::F64,
+        }
+    }
+}
+
+impl TryFrom<u32> for TractDatumType {
+    type Error = TractError;
+    fn try_from(dt: u32) -> TractResult<TractDatumType> {
+        use TractDatumType::*;
+        Ok(match dt {
+            1 => TRACT_DATUM_TYPE_BOOL,
+            2 => TRACT_DATUM_TYPE_U8,
+            3 => TRACT_DATUM_TYPE_U16,
+            4 => TRACT_DATUM_TYPE_I8,
+            5 => TRACT_DATUM_TYPE_I16,
+            6 => TRACT_DATUM_TYPE_I32,
+            7 => TRACT_DATUM_TYPE_I64,
+            8 => TRACT_DATUM_TYPE_F16,
+            9 => TRACT_DATUM_TYPE_F32,
+            10 => TRACT_DATUM_TYPE_F64,
+            _ => bail!("Invalid datum type {}", dt),
+        })
+    }
+}
+
+impl TryFrom<DatumType> for TractDatumType {
+    type Error = TractError;
+    fn try_from(dt: DatumType) -> TractResult<TractDatumType> {
+        use TractDatumType::*;
+        Ok(match dt {
+            DatumType::Bool => TRACT_DATUM_TYPE_BOOL,
+            DatumType::U8 => TRACT_DATUM_TYPE_U8,
+            DatumType::U16 => TRACT_DATUM_TYPE_U16,
+            DatumType::I8 => TRACT_DATUM_TYPE_I8,
+            DatumType::I16 => TRACT_DATUM_TYPE_I16,
+            DatumType::I32 => TRACT_DATUM_TYPE_I32,
+            DatumType::I64 => TRACT_DATUM_TYPE_I64,
+            DatumType::F16 => TRACT_DATUM_TYPE_F16,
+            DatumType::F32 => TRACT_DATUM_TYPE_F32,
+            DatumType::F64 => TRACT_DATUM_TYPE_F64,
+            _ => bail!("{:?} tensors are not supported by the C API", dt),
+        })
+    }
+}
+
+/// A model as loaded from TensorFlow or ONNX, before optimization.
+pub struct TractInferenceModel(InferenceModel);
+
+/// An optimized model.
+pub struct TractModel(Arc<TypedModel>);
+
+/// An execution plan for an optimized model.
+pub struct TractPlan(SimplePlan<TypedTensorInfo, Arc<TypedModel>>);
+
+/// A tensor, passed as input or obtained as output of a plan.
+pub struct TractTensor(Arc<Tensor>);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+fn wrap<F: FnOnce() -> TractResult<()>>(func: F) -> TRACT_RESULT {
+    let message = match catch_unwind(AssertUnwindSafe(func)) {
+        Ok(Ok(())) => return TRACT_RESULT::TRACT_RESULT_OK,
+        Ok(Err(e)) => e.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(": "),
+        Err(_) => "tract panicked".to_string(),
+    };
+    let message = CString::new(message.replace('\0', " ")).unwrap();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+    TRACT_RESULT::TRACT_RESULT_KO
+}
+
+macro_rules! check_not_null {
+    ($($ptr:ident),*) => {
+        $(
+            if $ptr.is_null() {
+                bail!("Unexpected null pointer {}", stringify!($ptr));
+            }
+         )*
+    }
+}
+
+unsafe fn buffer_of<'a, T>(ptr: *const T, len: usize) -> TractResult<&'a [T]> {
+    if len == 0 {
+        Ok(&[])
+    } else {
+        check_not_null!(ptr);
+        Ok(std::slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Get the message of the last error that occured on this thread.
+///
+/// The string is owned by the library, and stays valid until the next call
+/// failing on this thread. Returns null if no call failed yet.
+#[no_mangle]
+pub extern "C" fn tract_get_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map(|s| s.as_ptr()).unwrap_or(std::ptr::null()))
+}
+
+unsafe fn c_str<'a>(s: *const c_char) -> TractResult<&'a str> {
+    check_not_null!(s);
+    Ok(CStr::from_ptr(s).to_str()?)
+}
+
+unsafe fn create<T>(ptr: *mut *mut T, value: impl FnOnce() -> TractResult<T>) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(ptr);
+        *ptr = std::ptr::null_mut();
+        *ptr = Box::into_raw(Box::new(value()?));
+        Ok(())
+    })
+}
+
+unsafe fn destroy<T>(ptr: *mut *mut T) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(ptr);
+        if !(*ptr).is_null() {
+            drop(Box::from_raw(*ptr));
+            *ptr = std::ptr::null_mut();
+        }
+        Ok(())
+    })
+}
+
+/// Load an ONNX model from a file.
+///
+/// External data files are resolved relative to the model directory.
+#[no_mangle]
+pub unsafe extern "C" fn tract_onnx_model_for_path(
+    path: *const c_char,
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    create(model, || Ok(TractInferenceModel(tract_onnx::onnx().model_for_path(c_str(path)?)?)))
+}
+
+/// Load an ONNX model from a buffer.
+#[no_mangle]
+pub unsafe extern "C" fn tract_onnx_model_for_buffer(
+    data: *const u8,
+    len: usize,
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    create(model, || {
+        let mut buffer = buffer_of(data, len)?;
+        Ok(TractInferenceModel(tract_onnx::onnx().model_for_read(&mut buffer)?))
+    })
+}
+
+/// Load a TensorFlow model from a file.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensorflow_model_for_path(
+    path: *const c_char,
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    create(model, || {
+        Ok(TractInferenceModel(tract_tensorflow::tensorflow().model_for_path(c_str(path)?)?))
+    })
+}
+
+/// Load a TensorFlow model from a buffer.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensorflow_model_for_buffer(
+    data: *const u8,
+    len: usize,
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    create(model, || {
+        let mut buffer = buffer_of(data, len)?;
+        Ok(TractInferenceModel(tract_tensorflow::tensorflow().model_for_read(&mut buffer)?))
+    })
+}
+
+/// Set the element type and shape of the `input`-th model input.
+///
+/// `datum_type` is a `TractDatumType` value.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_set_input_fact(
+    model: *mut TractInferenceModel,
+    input: usize,
+    datum_type: u32,
+    rank: usize,
+    shape: *const usize,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model);
+        let dt = TractDatumType::try_from(datum_type)?.into();
+        let fact = TensorFact::dt_shape(dt, buffer_of(shape, rank)?);
+        (*model).0.set_input_fact(input, fact)
+    })
+}
+
+/// Optimize a model.
+///
+/// The inference model is consumed: `*model` is reset to null, even if the
+/// optimization fails.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_into_optimized(
+    model: *mut *mut TractInferenceModel,
+    optimized: *mut *mut TractModel,
+) -> TRACT_RESULT {
+    create(optimized, || {
+        check_not_null!(model);
+        if (*model).is_null() {
+            bail!("Unexpected null pointer *model");
+        }
+        let inference = Box::from_raw(*model);
+        *model = std::ptr::null_mut();
+        Ok(TractModel(Arc::new(inference.0.into_optimized()?)))
+    })
+}
+
+/// Destroy an inference model.
+#[no_mangle]
+pub unsafe extern "C" fn tract_inference_model_destroy(
+    model: *mut *mut TractInferenceModel,
+) -> TRACT_RESULT {
+    destroy(model)
+}
+
+/// Get the number of inputs of an optimized model.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_input_count(
+    model: *const TractModel,
+    count: *mut usize,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model, count);
+        *count = (*model).0.input_outlets()?.len();
+        Ok(())
+    })
+}
+
+/// Get the number of outputs of an optimized model.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_output_count(
+    model: *const TractModel,
+    count: *mut usize,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(model, count);
+        *count = (*model).0.output_outlets()?.len();
+        Ok(())
+    })
+}
+
+/// Build an execution plan for an optimized model.
+///
+/// The plan shares the model, which can be destroyed independently.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_plan(
+    model: *const TractModel,
+    plan: *mut *mut TractPlan,
+) -> TRACT_RESULT {
+    create(plan, || {
+        check_not_null!(model);
+        Ok(TractPlan(SimplePlan::new((*model).0.clone())?))
+    })
+}
+
+/// Destroy an optimized model.
+#[no_mangle]
+pub unsafe extern "C" fn tract_model_destroy(model: *mut *mut TractModel) -> TRACT_RESULT {
+    destroy(model)
+}
+
+/// Run a plan.
+///
+/// `inputs` holds one tensor for each model input. They are copied, and stay
+/// owned by the caller. `outputs` must have room for one tensor per model
+/// output: they are allocated by the library during the call, and are to be
+/// released with `tract_tensor_destroy`.
+#[no_mangle]
+pub unsafe extern "C" fn tract_plan_run(
+    plan: *const TractPlan,
+    inputs: *const *const TractTensor,
+    outputs: *mut *mut TractTensor,
+) -> TRACT_RESULT {
+    wrap(|| {
+        check_not_null!(plan, outputs);
+        let plan = &(*plan).0;
+        let input_count = plan.model().input_outlets()?.len();
+        let inputs = buffer_of(inputs, input_count)?
+            .iter()
+            .enumerate()
+            .map(|(ix, &t)| {
+                if t.is_null() {
+                    bail!("Unexpected null pointer for input {}", ix)
+                }
+                Ok(Tensor::clone(&(*t).0))
+            })
+            .collect::<TractResult<_>>()?;
+        for (ix, output) in plan.run(inputs)?.into_iter().enumerate() {
+            *outputs.add(ix) = Box::into_raw(Box::new(TractTensor(output)));
+        }
+        Ok(())
+    })
+}
+
+/// Destroy a plan.
+#[no_mangle]
+pub unsafe extern "C" fn tract_plan_destroy(plan: *mut *mut TractPlan) -> TRACT_RESULT {
+    destroy(plan)
+}
+
+/// Create a tensor, copying its content from a caller buffer.
+///
+/// `datum_type` is a `TractDatumType` value. `data` holds the product of the
+/// `shape` dimensions elements of `datum_type`, in row-major order. Booleans
+/// are bytes set to 0 or 1, other byte values are rejected.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensor_create(
+    datum_type: u32,
+    rank: usize,
+    shape: *const usize,
+    data: *const c_void,
+    tensor: *mut *mut TractTensor,
+) -> TRACT_RESULT {
+    create(tensor, || {
+        let dt = DatumType::from(TractDatumType::try_from(datum_type)?);
+        let shape = buffer_of(shape, rank)?;
+        let len = shape.iter().product::<usize>() * dt.size_of();
+        let data = buffer_of(data as *const u8, len)?;
+        if dt == DatumType::Bool && data.iter().any(|&b| b > 1) {
+            bail!("Invalid boolean value, expected 0 or 1")
+        }
+        Ok(TractTensor(Arc::new(dispatch_datum!(Tensor::from_raw(dt)(shape, data))?)))
+    })
+}
+
+/// Access the content of a tensor.
+///
+/// The shape and data are owned by the tensor, and stay valid until it is
+/// destroyed. Any of the output pointers may be null.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensor_inspect(
+    tensor: *const TractTensor,
+    datum_type: *mut TractDatumType,
+    rank: *mut usize,
+    shape: *mut *const usize,
+    data: *mut *const c_void,
+) -> TRACT_RESULT {
+    fn data_ptr<D: Datum>(t: &Tensor) -> TractResult<*const c_void> {
+        Ok(t.as_ptr::<D>()? as *const c_void)
+    }
+    wrap(|| {
+        check_not_null!(tensor);
+        let t = &(*tensor).0;
+        let dt = TractDatumType::try_from(t.datum_type())?;
+        if !datum_type.is_null() {
+            *datum_type = dt;
+        }
+        if !rank.is_null() {
+            *rank = t.shape().len();
+        }
+        if !shape.is_null() {
+            *shape = t.shape().as_ptr();
+        }
+        if !data.is_null() {
+            *data = dispatch_datum!(data_ptr(t.datum_type())(t))?;
+        }
+        Ok(())
+    })
+}
+
+/// Destroy a tensor.
+#[no_mangle]
+pub unsafe extern "C" fn tract_tensor_destroy(tensor: *mut *mut TractTensor) -> TRACT_RESULT {
+    destroy(tensor)
+}
@@ -0,0 +1,32 @@
+//! Build the C test program against the library, and run it.
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn plus3() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // the test runs from target/<profile>/deps, next to the library, which is
+    // rebuilt with the test as the crate is also an rlib
+    let exe = std::env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap();
+    let program = lib_dir.join("tract-ffi-plus3");
+    let status = Command::new(std::env::var("CC").unwrap_or("cc".to_string()))
+        .arg(crate_dir.join("tests/plus3.c"))
+        .arg("-I")
+        .arg(&crate_dir)
+        .arg("-L")
+        .arg(lib_dir)
+        .arg("-ltract_ffi")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let status = Command::new(&program)
+        .arg(crate_dir.join("../tensorflow/tests/models/plus3.pb"))
+        .env("LD_LIBRARY_PATH", lib_dir)
+        .env("DYLD_LIBRARY_PATH", lib_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,77 @@
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "tract.h"
+
+#define check(call)                                                            \
+    do {                                                                       \
+        if ((call) != TRACT_RESULT_OK) {                                       \
+            fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__,                 \
+                    tract_get_last_error());                                   \
+            return 1;                                                          \
+        }                                                                      \
+    } while (0)
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s plus3.pb\n", argv[0]);
+        return 2;
+    }
+
+    TractInferenceModel *model = NULL;
+    assert(tract_get_last_error() == NULL);
+    assert(tract_tensorflow_model_for_path("does-not-exist.pb", &model) == TRACT_RESULT_KO);
+    assert(model == NULL);
+    assert(strstr(tract_get_last_error(), "does-not-exist.pb") != NULL);
+
+    check(tract_tensorflow_model_for_path(argv[1], &model));
+    size_t shape[] = {3};
+    check(tract_inference_model_set_input_fact(model, 0, TRACT_DATUM_TYPE_F32, 1, shape));
+
+    TractModel *optimized = NULL;
+    check(tract_inference_model_into_optimized(&model, &optimized));
+    assert(model == NULL);
+    size_t inputs = 0, outputs = 0;
+    check(tract_model_input_count(optimized, &inputs));
+    check(tract_model_output_count(optimized, &outputs));
+    assert(inputs == 1 && outputs == 1);
+
+    TractPlan *plan = NULL;
+    check(tract_model_plan(optimized, &plan));
+    check(tract_model_destroy(&optimized));
+    assert(optimized == NULL);
+
+    float data[] = {1.0, 2.5, 5.0};
+    TractTensor *input = NULL;
+    assert(tract_tensor_create(42, 1, shape, data, &input) == TRACT_RESULT_KO);
+    assert(input == NULL);
+    assert(strstr(tract_get_last_error(), "Invalid datum type 42") != NULL);
+    unsigned char flags[] = {0, 1, 2};
+    assert(tract_tensor_create(TRACT_DATUM_TYPE_BOOL, 1, shape, flags,
+                               &input) == TRACT_RESULT_KO);
+    assert(input == NULL);
+    assert(strstr(tract_get_last_error(), "Invalid boolean value") != NULL);
+    flags[2] = 1;
+    check(tract_tensor_create(TRACT_DATUM_TYPE_BOOL, 1, shape, flags, &input));
+    check(tract_tensor_destroy(&input));
+    check(tract_tensor_create(TRACT_DATUM_TYPE_F32, 1, shape, data, &input));
+    TractTensor *output = NULL;
+    check(tract_plan_run(plan, (const TractTensor *const *)&input, &output));
+
+    TractDatumType datum_type;
+    size_t rank;
+    const size_t *output_shape;
+    const float *result;
+    check(tract_tensor_inspect(output, &datum_type, &rank, &output_shape,
+                               (const void **)&result));
+    assert(datum_type == TRACT_DATUM_TYPE_F32);
+    assert(rank == 1 && output_shape[0] == 3);
+    assert(result[0] == 4.0 && result[1] == 5.5 && result[2] == 8.0);
+
+    check(tract_tensor_destroy(&output));
+    check(tract_tensor_destroy(&input));
+    check(tract_plan_destroy(&plan));
+    assert(plan == NULL);
+    return 0;
+}
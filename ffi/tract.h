@@ -0,0 +1,193 @@
+#ifndef TRACT_H
+#define TRACT_H
+
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of a call to the library.
+ */
+typedef enum TRACT_RESULT {
+  TRACT_RESULT_OK = 0,
+  TRACT_RESULT_KO = 1,
+} TRACT_RESULT;
+
+/**
+ * Element type of a tensor.
+ */
+typedef enum TractDatumType {
+  TRACT_DATUM_TYPE_BOOL = 1,
+  TRACT_DATUM_TYPE_U8,
+  TRACT_DATUM_TYPE_U16,
+  TRACT_DATUM_TYPE_I8,
+  TRACT_DATUM_TYPE_I16,
+  TRACT_DATUM_TYPE_I32,
+  TRACT_DATUM_TYPE_I64,
+  TRACT_DATUM_TYPE_F16,
+  TRACT_DATUM_TYPE_F32,
+  TRACT_DATUM_TYPE_F64,
+} TractDatumType;
+
+/**
+ * A model as loaded from TensorFlow or ONNX, before optimization.
+ */
+typedef struct TractInferenceModel TractInferenceModel;
+
+/**
+ * An optimized model.
+ */
+typedef struct TractModel TractModel;
+
+/**
+ * An execution plan for an optimized model.
+ */
+typedef struct TractPlan TractPlan;
+
+/**
+ * A tensor, passed as input or obtained as output of a plan.
+ */
+typedef struct TractTensor TractTensor;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Get the message of the last error that occured on this thread.
+ *
+ * The string is owned by the library, and stays valid until the next call
+ * failing on this thread. Returns null if no call failed yet.
+ */
+const char *tract_get_last_error(void);
+
+/**
+ * Load an ONNX model from a file.
+ *
+ * External data files are resolved relative to the model directory.
+ */
+enum TRACT_RESULT tract_onnx_model_for_path(const char *path, struct TractInferenceModel **model);
+
+/**
+ * Load an ONNX model from a buffer.
+ */
+enum TRACT_RESULT tract_onnx_model_for_buffer(const uint8_t *data,
+                                              size_t len,
+                                              struct TractInferenceModel **model);
+
+/**
+ * Load a TensorFlow model from a file.
+ */
+enum TRACT_RESULT tract_tensorflow_model_for_path(const char *path,
+                                                  struct TractInferenceModel **model);
+
+/**
+ * Load a TensorFlow model from a buffer.
+ */
+enum TRACT_RESULT tract_tensorflow_model_for_buffer(const uint8_t *data,
+                                                    size_t len,
+                                                    struct TractInferenceModel **model);
+
+/**
+ * Set the element type and shape of the `input`-th model input.
+ *
+ * `datum_type` is a `TractDatumType` value.
+ */
+enum TRACT_RESULT tract_inference_model_set_input_fact(struct TractInferenceModel *model,
+                                                       size_t input,
+                                                       uint32_t datum_type,
+                                                       size_t rank,
+                                                       const size_t *shape);
+
+/**
+ * Optimize a model.
+ *
+ * The inference model is consumed: `*model` is reset to null, even if the
+ * optimization fails.
+ */
+enum TRACT_RESULT tract_inference_model_into_optimized(struct TractInferenceModel **model,
+                                                       struct TractModel **optimized);
+
+/**
+ * Destroy an inference model.
+ */
+enum TRACT_RESULT tract_inference_model_destroy(struct TractInferenceModel **model);
+
+/**
+ * Get the number of inputs of an optimized model.
+ */
+enum TRACT_RESULT tract_model_input_count(const struct TractModel *model, size_t *count);
+
+/**
+ * Get the number of outputs of an optimized model.
+ */
+enum TRACT_RESULT tract_model_output_count(const struct TractModel *model, size_t *count);
+
+/**
+ * Build an execution plan for an optimized model.
+ *
+ * The plan shares the model, which can be destroyed independently.
+ */
+enum TRACT_RESULT tract_model_plan(const struct TractModel *model, struct TractPlan **plan);
+
+/**
+ * Destroy an optimized model.
+ */
+enum TRACT_RESULT tract_model_destroy(struct TractModel **model);
+
+/**
+ * Run a plan.
+ *
+ * `inputs` holds one tensor for each model input. They are copied, and stay
+ * owned by the caller. `outputs` must have room for one tensor per model
+ * output: they are allocated by the library during the call, and are to be
+ * released with `tract_tensor_destroy`.
+ */
+enum TRACT_RESULT tract_plan_run(const struct TractPlan *plan,
+                                 const struct TractTensor *const *inputs,
+                                 struct TractTensor **outputs);
+
+/**
+ * Destroy a plan.
+ */
+enum TRACT_RESULT tract_plan_destroy(struct TractPlan **plan);
+
+/**
+ * Create a tensor, copying its content from a caller buffer.
+ *
+ * `datum_type` is a `TractDatumType` value. `data` holds the product of the
+ * `shape` dimensions elements of `datum_type`, in row-major order. Booleans
+ * are bytes set to 0 or 1, other byte values are rejected.
+ */
+enum TRACT_RESULT tract_tensor_create(uint32_t datum_type,
+                                      size_t rank,
+                                      const size_t *shape,
+                                      const void *data,
+                                      struct TractTensor **tensor);
+
+/**
+ * Access the content of a tensor.
+ *
+ * The shape and data are owned by the tensor, and stay valid until it is
+ * destroyed. Any of the output pointers may be null.
+ */
+enum TRACT_RESULT tract_tensor_inspect(const struct TractTensor *tensor,
+                                       enum TractDatumType *datum_type,
+                                       size_t *rank,
+                                       const size_t **shape,
+                                       const void **data);
+
+/**
+ * Destroy a tensor.
+ */
+enum TRACT_RESULT tract_tensor_destroy(struct TractTensor **tensor);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TRACT_H */
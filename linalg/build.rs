@@ -24,6 +24,11 @@ fn main() {
         cc::Build::new()
             .file("arm64/arm64simd/arm64simd_conv_s8x8.S")
             .file("arm64/arm64simd/arm64simd_mm_s8x8.S")
+            .file("arm64/arm64simd/arm64simd_sigmoid_f32_4n.S")
+            .file("arm64/arm64simd/arm64simd_tanh_f32_4n.S")
+            .file("arm64/arm64simd/arm64simd_exp_f32_4n.S")
+            .file("arm64/arm64simd/arm64simd_dw_hwc_s4.S")
+            .file("arm64/arm64simd/arm64simd_dw_chw_s4.S")
             .static_flag(true)
             .compile("arm64");
     }
@@ -1,7 +1,10 @@
 mod arm64simd;
 
+use crate::frame::DepthWiseImpl;
+use crate::frame::ElementWiseImpl;
 use crate::frame::PackedConv;
 use crate::frame::PackedMatMul;
+use crate::frame::SoftMaxImpl;
 use crate::Ops;
 
 pub fn plug(ops: &mut Ops) {
@@ -10,4 +13,13 @@ pub fn plug(ops: &mut Ops) {
         Box::new(|m, k, n| Box::new(PackedMatMul::<arm64simd::SMatMul8x8, f32>::new(m, k, n)));
     log::info!("arm64simd activated for sconv");
     ops.sconv = Box::new(|m, k, n| Box::new(PackedConv::<arm64simd::SConv8x8, f32>::new(m, k, n)));
+    log::info!("arm64simd activated for depthwise conv");
+    ops.sdepth_wise = Box::new(|offsets, stride| {
+        Box::new(DepthWiseImpl::<arm64simd::SDepthWise4, f32>::new(offsets, stride))
+    });
+    log::info!("arm64simd activated for sigmoid, tanh, exp and softmax");
+    ops.ssigmoid = Box::new(|| Box::new(ElementWiseImpl::<arm64simd::SSigmoid4, f32>::new()));
+    ops.stanh = Box::new(|| Box::new(ElementWiseImpl::<arm64simd::STanh4, f32>::new()));
+    ops.sexp = Box::new(|| Box::new(ElementWiseImpl::<arm64simd::SExp4, f32>::new()));
+    ops.ssoftmax = Box::new(|| Box::new(SoftMaxImpl::<arm64simd::SExp4>::new()));
 }
@@ -22,6 +22,30 @@ extern "C" {
         rsc: size_t,
         csc: size_t,
     );
+
+    fn arm64simd_sigmoid_f32_4n(vec: *mut f32, len: size_t);
+    fn arm64simd_tanh_f32_4n(vec: *mut f32, len: size_t);
+    fn arm64simd_exp_f32_4n(vec: *mut f32, len: size_t);
+
+    fn arm64simd_dw_hwc_s4(
+        taps: size_t,
+        offsets: *const ssize_t,
+        kernel: *const f32,
+        k_stride: size_t,
+        bias: *const f32,
+        input: *const f32,
+        output: *mut f32,
+    );
+
+    fn arm64simd_dw_chw_s4(
+        taps: size_t,
+        offsets: *const ssize_t,
+        kernel: *const f32,
+        input: *const f32,
+        stride: size_t,
+        output: *mut f32,
+        bias: f32,
+    );
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -88,10 +112,76 @@ impl frame::conv::ConvKer<f32> for SConv8x8 {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct SDepthWise4;
+
+impl frame::depth_wise::DepthWiseKer<f32> for SDepthWise4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "arm64simd"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(never)]
+    fn hwc(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const f32,
+        k_stride: usize,
+        bias: *const f32,
+        input: *const f32,
+        output: *mut f32,
+    ) {
+        unsafe { arm64simd_dw_hwc_s4(taps, offsets, kernel, k_stride, bias, input, output) }
+    }
+    #[inline(never)]
+    fn chw(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const f32,
+        bias: f32,
+        input: *const f32,
+        stride: usize,
+        output: *mut f32,
+    ) {
+        unsafe { arm64simd_dw_chw_s4(taps, offsets, kernel, input, stride, output, bias) }
+    }
+}
+
+macro_rules! element_wise_ker {
+    ($ker:ident, $func:ident) => {
+        #[derive(Copy, Clone, Debug)]
+        pub struct $ker;
+
+        impl frame::element_wise::ElementWiseKer<f32> for $ker {
+            #[inline(always)]
+            fn name() -> &'static str {
+                "arm64simd"
+            }
+            #[inline(always)]
+            fn nr() -> usize {
+                4
+            }
+            #[inline(never)]
+            fn run(vec: &mut [f32]) {
+                unsafe { $func(vec.as_mut_ptr(), vec.len()) }
+            }
+        }
+    };
+}
+
+element_wise_ker!(SSigmoid4, arm64simd_sigmoid_f32_4n);
+element_wise_ker!(STanh4, arm64simd_tanh_f32_4n);
+element_wise_ker!(SExp4, arm64simd_exp_f32_4n);
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::frame::conv::test::*;
+    use crate::frame::depth_wise::test::*;
+    use crate::frame::element_wise::test::*;
     use crate::frame::matmul::test::*;
     use crate::frame::PackedConv;
     use crate::frame::PackedMatMul;
@@ -120,4 +210,53 @@ mod test {
             prop_assert_eq!(found, expected)
         }
     }
+
+    proptest! {
+        #[test]
+        fn sigmoid(ref xs in strat_element_wise(-25.0, 25.0)) {
+            test_element_wise::<SSigmoid4>(xs, |x| 1.0 / (1.0 + (-x).exp()), 1e-6)?
+        }
+
+        #[test]
+        fn tanh(ref xs in strat_element_wise(-15.0, 15.0)) {
+            test_element_wise::<STanh4>(xs, |x| x.tanh(), 1e-6)?
+        }
+
+        #[test]
+        fn exp(ref xs in strat_element_wise(-80.0, 80.0)) {
+            test_element_wise::<SExp4>(xs, |x| x.exp(), 1e-6)?
+        }
+
+        #[test]
+        fn sigmoid_special(ref xs in strat_element_wise_special(-25.0, 25.0)) {
+            test_element_wise::<SSigmoid4>(xs, |x| 1.0 / (1.0 + (-x).exp()), 1e-6)?
+        }
+
+        #[test]
+        fn tanh_special(ref xs in strat_element_wise_special(-15.0, 15.0)) {
+            test_element_wise::<STanh4>(xs, |x| x.tanh(), 1e-6)?
+        }
+
+        #[test]
+        fn exp_special(ref xs in strat_element_wise_special(-80.0, 80.0)) {
+            test_element_wise::<SExp4>(xs, |x| x.exp(), 1e-6)?
+        }
+
+        #[test]
+        fn softmax(ref xs in strat_element_wise(-50.0, 50.0)) {
+            test_softmax::<SExp4>(xs)?
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn depth_wise_chw(pb in strat_depth_wise()) {
+            test_depth_wise_chw::<SDepthWise4>(&pb)?
+        }
+
+        #[test]
+        fn depth_wise_hwc(pb in strat_depth_wise()) {
+            test_depth_wise_hwc::<SDepthWise4>(&pb)?
+        }
+    }
 }
@@ -1,4 +1,6 @@
 pub mod conv;
+pub mod depth_wise;
+pub mod element_wise;
 pub mod matmul;
 pub mod pack_b;
 pub mod vecmatmul;
@@ -7,6 +9,8 @@ pub use pack_b::PackB;
 
 pub use self::conv::Conv;
 pub use self::conv::PackedConv;
+pub use self::depth_wise::{DepthWise, DepthWiseImpl};
+pub use self::element_wise::{ElementWise, ElementWiseImpl, SoftMaxImpl};
 pub use self::matmul::MatMul;
 pub use self::matmul::PackedMatMul;
 pub use self::vecmatmul::PackedVecMatMul;
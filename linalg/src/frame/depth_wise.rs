@@ -0,0 +1,326 @@
+use num_traits::Zero;
+use std::fmt::Debug;
+use std::ops::{Add, Mul};
+
+use std::marker::PhantomData;
+
+/// Depthwise convolution over an explicitly padded input: every output value
+/// is a dot product of one channel of the kernel with `offsets.len()` taps
+/// of the matching input channel.
+pub trait DepthWise<T: Copy + Add + Mul + Zero + Debug>:
+    Send + Sync + Debug + objekt::Clone
+{
+    /// Channel-last layout: computes one output point for `channels`
+    /// contiguous channels.
+    ///
+    /// `output[c] = bias[c] + sum_t kernel[t * channels + c] * input[offsets[t] + c]`
+    fn hwc(
+        &self,
+        channels: usize,
+        kernel: *const T,
+        bias: *const T,
+        input: *const T,
+        output: *mut T,
+    );
+
+    /// Channel-first layout: computes `len` consecutive outputs of a single
+    /// channel along the innermost axis.
+    ///
+    /// `output[x] = bias + sum_t kernel[t] * input[offsets[t] + x * stride]`
+    fn chw_row(&self, len: usize, kernel: *const T, bias: T, input: *const T, output: *mut T);
+
+    fn offsets(&self) -> &[isize];
+    fn stride(&self) -> usize;
+}
+
+clone_trait_object!(<T> DepthWise<T> where T: Copy + Add + Mul + Zero);
+
+pub trait DepthWiseKer<T: Copy + Add + Mul + Zero>: Copy + Clone + Debug + Send + Sync {
+    #[inline(always)]
+    fn name() -> &'static str;
+    #[inline(always)]
+    fn nr() -> usize;
+    /// `nr()` channels of a channel-last output point. The kernel taps are
+    /// `k_stride` apart.
+    #[inline(always)]
+    fn hwc(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const T,
+        k_stride: usize,
+        bias: *const T,
+        input: *const T,
+        output: *mut T,
+    );
+    /// `nr()` consecutive outputs of a channel-first row, reading the input
+    /// `stride` items apart.
+    #[inline(always)]
+    fn chw(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const T,
+        bias: T,
+        input: *const T,
+        stride: usize,
+        output: *mut T,
+    );
+}
+
+#[derive(Clone)]
+pub struct DepthWiseImpl<K, T>
+where
+    K: DepthWiseKer<T> + Debug,
+    T: Copy + Add + Mul + Zero + Debug + Send + Sync,
+{
+    pub offsets: Vec<isize>,
+    pub stride: usize,
+    _kernel: PhantomData<(K, T)>,
+}
+
+impl<K, T> std::fmt::Debug for DepthWiseImpl<K, T>
+where
+    K: DepthWiseKer<T>,
+    T: Copy + Add + Mul + Zero + Debug + Send + Sync,
+{
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            fmt,
+            "DepthWise taps:{} stride:{} ({} {})",
+            self.offsets.len(),
+            self.stride,
+            K::name(),
+            K::nr()
+        )
+    }
+}
+
+impl<K, T> DepthWiseImpl<K, T>
+where
+    K: DepthWiseKer<T>,
+    T: Copy + Add + Mul + Zero + Debug + Send + Sync,
+{
+    pub fn new(offsets: Vec<isize>, stride: usize) -> DepthWiseImpl<K, T> {
+        assert!(offsets.len() > 0);
+        DepthWiseImpl { offsets, stride, _kernel: PhantomData }
+    }
+}
+
+impl<K, T> DepthWise<T> for DepthWiseImpl<K, T>
+where
+    K: DepthWiseKer<T>,
+    T: Copy + Add<Output = T> + Mul<Output = T> + Zero + Debug + Send + Sync + PartialEq,
+{
+    fn hwc(
+        &self,
+        channels: usize,
+        kernel: *const T,
+        bias: *const T,
+        input: *const T,
+        output: *mut T,
+    ) {
+        let nr = K::nr();
+        let taps = self.offsets.len();
+        unsafe {
+            for c in (0..channels / nr).map(|i| (i * nr) as isize) {
+                K::hwc(
+                    taps,
+                    self.offsets.as_ptr(),
+                    kernel.offset(c),
+                    channels,
+                    bias.offset(c),
+                    input.offset(c),
+                    output.offset(c),
+                );
+            }
+            for c in (channels / nr * nr)..channels {
+                let mut sum = *bias.offset(c as isize);
+                for (t, &o) in self.offsets.iter().enumerate() {
+                    sum = sum
+                        + *kernel.offset((t * channels + c) as isize)
+                            * *input.offset(o + c as isize);
+                }
+                *output.offset(c as isize) = sum;
+            }
+        }
+    }
+
+    fn chw_row(&self, len: usize, kernel: *const T, bias: T, input: *const T, output: *mut T) {
+        let nr = K::nr();
+        let taps = self.offsets.len();
+        unsafe {
+            for x in (0..len / nr).map(|i| i * nr) {
+                K::chw(
+                    taps,
+                    self.offsets.as_ptr(),
+                    kernel,
+                    bias,
+                    input.offset((x * self.stride) as isize),
+                    self.stride,
+                    output.offset(x as isize),
+                );
+            }
+            for x in (len / nr * nr)..len {
+                let mut sum = bias;
+                for (t, &o) in self.offsets.iter().enumerate() {
+                    sum = sum
+                        + *kernel.offset(t as isize)
+                            * *input.offset(o + (x * self.stride) as isize);
+                }
+                *output.offset(x as isize) = sum;
+            }
+        }
+    }
+
+    fn offsets(&self) -> &[isize] {
+        &self.offsets
+    }
+
+    fn stride(&self) -> usize {
+        self.stride
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::*;
+
+    /// A 2D depthwise problem on an already padded input.
+    #[derive(Clone, Debug)]
+    pub struct DepthWiseProblem {
+        pub channels: usize,
+        pub h: usize,
+        pub w: usize,
+        pub kh: usize,
+        pub kw: usize,
+        pub stride: usize,
+        pub input: Vec<f32>,
+        pub kernel: Vec<f32>,
+        pub bias: Vec<f32>,
+    }
+
+    impl DepthWiseProblem {
+        fn oh(&self) -> usize {
+            (self.h - self.kh) / self.stride + 1
+        }
+
+        fn ow(&self) -> usize {
+            (self.w - self.kw) / self.stride + 1
+        }
+
+        /// Reference in channel-first layout, kernel as [c, kh, kw].
+        fn expected_chw(&self) -> Vec<f32> {
+            let (oh, ow) = (self.oh(), self.ow());
+            let mut output = vec![0.0; self.channels * oh * ow];
+            for c in 0..self.channels {
+                for y in 0..oh {
+                    for x in 0..ow {
+                        let mut sum = self.bias[c];
+                        for ky in 0..self.kh {
+                            for kx in 0..self.kw {
+                                sum += self.kernel[(c * self.kh + ky) * self.kw + kx]
+                                    * self.input[(c * self.h + y * self.stride + ky) * self.w
+                                        + x * self.stride
+                                        + kx];
+                            }
+                        }
+                        output[(c * oh + y) * ow + x] = sum;
+                    }
+                }
+            }
+            output
+        }
+    }
+
+    pub fn strat_depth_wise() -> BoxedStrategy<DepthWiseProblem> {
+        (1usize..20, 1usize..4, 1usize..4, 1usize..3)
+            .prop_flat_map(|(channels, kh, kw, stride)| {
+                (Just((channels, kh, kw, stride)), kh..kh + 12, kw..kw + 20)
+            })
+            .prop_flat_map(|((channels, kh, kw, stride), h, w)| {
+                (
+                    Just((channels, kh, kw, stride, h, w)),
+                    proptest::collection::vec((-10..10).prop_map(|a| a as f32), channels * h * w),
+                    proptest::collection::vec((-10..10).prop_map(|a| a as f32), channels * kh * kw),
+                    proptest::collection::vec((-10..10).prop_map(|a| a as f32), channels),
+                )
+            })
+            .prop_map(|((channels, kh, kw, stride, h, w), input, kernel, bias)| DepthWiseProblem {
+                channels,
+                h,
+                w,
+                kh,
+                kw,
+                stride,
+                input,
+                kernel,
+                bias,
+            })
+            .boxed()
+    }
+
+    pub fn test_depth_wise_chw<K: DepthWiseKer<f32>>(
+        pb: &DepthWiseProblem,
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        let offsets = (0..pb.kh)
+            .flat_map(|ky| (0..pb.kw).map(move |kx| (ky, kx)))
+            .map(|(ky, kx)| (ky * pb.w + kx) as isize)
+            .collect();
+        let dw = DepthWiseImpl::<K, f32>::new(offsets, pb.stride);
+        let (oh, ow) = (pb.oh(), pb.ow());
+        let mut found = vec![9999.0f32; pb.channels * oh * ow];
+        for c in 0..pb.channels {
+            for y in 0..oh {
+                dw.chw_row(
+                    ow,
+                    pb.kernel[c * pb.kh * pb.kw..].as_ptr(),
+                    pb.bias[c],
+                    pb.input[(c * pb.h + y * pb.stride) * pb.w..].as_ptr(),
+                    found[(c * oh + y) * ow..].as_mut_ptr(),
+                );
+            }
+        }
+        prop_assert_eq!(found, pb.expected_chw());
+        Ok(())
+    }
+
+    pub fn test_depth_wise_hwc<K: DepthWiseKer<f32>>(
+        pb: &DepthWiseProblem,
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        let c = pb.channels;
+        let offsets = (0..pb.kh)
+            .flat_map(|ky| (0..pb.kw).map(move |kx| (ky, kx)))
+            .map(|(ky, kx)| ((ky * pb.w + kx) * c) as isize)
+            .collect();
+        let dw = DepthWiseImpl::<K, f32>::new(offsets, pb.stride);
+        let input_hwc: Vec<f32> = (0..pb.h * pb.w)
+            .flat_map(|yx| (0..c).map(move |ch| (yx, ch)))
+            .map(|(yx, ch)| pb.input[ch * pb.h * pb.w + yx])
+            .collect();
+        let kernel_hwc: Vec<f32> = (0..pb.kh * pb.kw)
+            .flat_map(|t| (0..c).map(move |ch| (t, ch)))
+            .map(|(t, ch)| pb.kernel[ch * pb.kh * pb.kw + t])
+            .collect();
+        let (oh, ow) = (pb.oh(), pb.ow());
+        let mut found = vec![9999.0f32; c * oh * ow];
+        for y in 0..oh {
+            for x in 0..ow {
+                dw.hwc(
+                    c,
+                    kernel_hwc.as_ptr(),
+                    pb.bias.as_ptr(),
+                    input_hwc[(y * pb.stride * pb.w + x * pb.stride) * c..].as_ptr(),
+                    found[(y * ow + x) * c..].as_mut_ptr(),
+                );
+            }
+        }
+        let expected = pb.expected_chw();
+        let expected: Vec<f32> = (0..oh * ow)
+            .flat_map(|yx| (0..c).map(move |ch| (yx, ch)))
+            .map(|(yx, ch)| expected[ch * oh * ow + yx])
+            .collect();
+        prop_assert_eq!(found, expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait ElementWise<T>: Send + Sync + Debug + objekt::Clone
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+{
+    fn run(&self, vec: &mut [T]);
+}
+
+clone_trait_object!(<T> ElementWise<T> where T: Copy);
+
+/// Largest `nr()` of an ElementWiseKer, bounding the stack buffer for tails.
+pub const MAX_NR: usize = 16;
+
+pub trait ElementWiseKer<T>: Send + Sync + Debug + Clone + Copy + 'static
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+{
+    #[inline(always)]
+    fn name() -> &'static str;
+    /// Number of values processed at once, up to MAX_NR.
+    #[inline(always)]
+    fn nr() -> usize;
+    /// Works in place on `vec`, whose length is a multiple of `nr()`.
+    #[inline(always)]
+    fn run(vec: &mut [T]);
+}
+
+#[derive(Debug, Clone)]
+pub struct ElementWiseImpl<K, T>
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+    K: ElementWiseKer<T> + Clone,
+{
+    phantom: PhantomData<(K, T)>,
+}
+
+impl<K, T> ElementWiseImpl<K, T>
+where
+    T: Copy + Debug + PartialEq + Send + Sync,
+    K: ElementWiseKer<T> + Clone,
+{
+    pub fn new() -> ElementWiseImpl<K, T> {
+        ElementWiseImpl { phantom: PhantomData }
+    }
+}
+
+impl<K, T> ElementWise<T> for ElementWiseImpl<K, T>
+where
+    T: Copy + Debug + PartialEq + Send + Sync + Default,
+    K: ElementWiseKer<T> + Clone,
+{
+    fn run(&self, vec: &mut [T]) {
+        let nr = K::nr();
+        let prefix = vec.len() / nr * nr;
+        if prefix > 0 {
+            K::run(&mut vec[..prefix]);
+        }
+        if prefix < vec.len() {
+            assert!(nr <= MAX_NR);
+            let tail = &mut vec[prefix..];
+            let mut padded = [T::default(); MAX_NR];
+            padded[..tail.len()].copy_from_slice(tail);
+            K::run(&mut padded[..nr]);
+            tail.copy_from_slice(&padded[..tail.len()]);
+        }
+    }
+}
+
+/// Softmax of a slice, using an exponential kernel.
+#[derive(Debug, Clone)]
+pub struct SoftMaxImpl<K>
+where
+    K: ElementWiseKer<f32> + Clone,
+{
+    phantom: PhantomData<K>,
+}
+
+impl<K> SoftMaxImpl<K>
+where
+    K: ElementWiseKer<f32> + Clone,
+{
+    pub fn new() -> SoftMaxImpl<K> {
+        SoftMaxImpl { phantom: PhantomData }
+    }
+}
+
+impl<K> ElementWise<f32> for SoftMaxImpl<K>
+where
+    K: ElementWiseKer<f32> + Clone,
+{
+    fn run(&self, vec: &mut [f32]) {
+        // subtracting the max keeps exp in range
+        let max = vec.iter().cloned().fold(std::f32::NEG_INFINITY, f32::max);
+        vec.iter_mut().for_each(|x| *x -= max);
+        ElementWiseImpl::<K, f32>::new().run(vec);
+        let recip = vec.iter().sum::<f32>().recip();
+        vec.iter_mut().for_each(|x| *x *= recip);
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::*;
+
+    pub fn strat_element_wise(low: f32, high: f32) -> BoxedStrategy<Vec<f32>> {
+        proptest::collection::vec(low..high, 0usize..100).boxed()
+    }
+
+    /// Values in [low, high) mixed with NaN and infinities.
+    pub fn strat_element_wise_special(low: f32, high: f32) -> BoxedStrategy<Vec<f32>> {
+        let value = prop_oneof![
+            low..high,
+            Just(std::f32::NAN),
+            Just(std::f32::INFINITY),
+            Just(std::f32::NEG_INFINITY)
+        ];
+        proptest::collection::vec(value, 0usize..100).boxed()
+    }
+
+    pub fn test_element_wise<K: ElementWiseKer<f32>>(
+        values: &[f32],
+        reference: impl Fn(f64) -> f64,
+        tolerance: f32,
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        let mut found = values.to_vec();
+        ElementWiseImpl::<K, f32>::new().run(&mut found);
+        for (x, y) in values.iter().zip(found.iter()) {
+            let expected = reference(*x as f64) as f32;
+            prop_assert!(
+                if expected.is_nan() {
+                    y.is_nan()
+                } else if expected.is_infinite() {
+                    *y == expected
+                } else {
+                    (y - expected).abs() <= tolerance * expected.abs().max(1.0)
+                },
+                "{}({}): found {}, expected {}",
+                K::name(),
+                x,
+                y,
+                expected
+            );
+        }
+        Ok(())
+    }
+
+    pub fn test_softmax<K: ElementWiseKer<f32>>(
+        values: &[f32],
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        let mut found = values.to_vec();
+        SoftMaxImpl::<K>::new().run(&mut found);
+        let max = values.iter().cloned().fold(std::f64::NEG_INFINITY, |a, b| a.max(b as f64));
+        let sum: f64 = values.iter().map(|&x| (x as f64 - max).exp()).sum();
+        for (x, y) in values.iter().zip(found.iter()) {
+            let expected = ((*x as f64 - max).exp() / sum) as f32;
+            prop_assert!((y - expected).abs() <= 1e-5, "found {}, expected {}", y, expected);
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,16 @@
 pub mod conv;
+pub mod depth_wise;
+pub mod exp;
 pub mod matmul;
+pub mod sigmoid;
+pub mod tanh;
 pub mod vecmatmul;
 
 pub use self::conv::SConv4x4;
+pub use self::depth_wise::SDepthWise4;
+pub use self::exp::SExp4;
 pub use self::matmul::DMatMul4x2;
 pub use self::matmul::SMatMul4x4;
+pub use self::sigmoid::SSigmoid4;
+pub use self::tanh::STanh4;
 pub use self::vecmatmul::SVecMatMul8;
@@ -0,0 +1,85 @@
+use crate::frame;
+
+#[derive(Copy, Clone, Debug)]
+pub struct SDepthWise4;
+
+impl frame::depth_wise::DepthWiseKer<f32> for SDepthWise4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn hwc(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const f32,
+        k_stride: usize,
+        bias: *const f32,
+        input: *const f32,
+        output: *mut f32,
+    ) {
+        unsafe {
+            let mut sum = [0.0f32; 4];
+            for c in 0..4 {
+                sum[c] = *bias.offset(c as isize);
+            }
+            for t in 0..taps {
+                let k = kernel.offset((t * k_stride) as isize);
+                let i = input.offset(*offsets.offset(t as isize));
+                for c in 0..4 {
+                    sum[c] += *k.offset(c as isize) * *i.offset(c as isize);
+                }
+            }
+            for c in 0..4 {
+                *output.offset(c as isize) = sum[c];
+            }
+        }
+    }
+    #[inline(always)]
+    fn chw(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const f32,
+        bias: f32,
+        input: *const f32,
+        stride: usize,
+        output: *mut f32,
+    ) {
+        unsafe {
+            let mut sum = [bias; 4];
+            for t in 0..taps {
+                let k = *kernel.offset(t as isize);
+                let i = input.offset(*offsets.offset(t as isize));
+                for x in 0..4 {
+                    sum[x] += k * *i.offset((x * stride) as isize);
+                }
+            }
+            for x in 0..4 {
+                *output.offset(x as isize) = sum[x];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::depth_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn depth_wise_chw(pb in strat_depth_wise()) {
+            test_depth_wise_chw::<SDepthWise4>(&pb)?
+        }
+
+        #[test]
+        fn depth_wise_hwc(pb in strat_depth_wise()) {
+            test_depth_wise_hwc::<SDepthWise4>(&pb)?
+        }
+    }
+}
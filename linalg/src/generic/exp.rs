@@ -0,0 +1,82 @@
+use crate::frame::element_wise::ElementWiseKer;
+
+pub const LOW: f32 = -87.0;
+pub const HIGH: f32 = 88.0;
+pub const LOG2_E: f32 = 1.44269504088896341;
+// ln(2) split in a part exactly representable on few bits, and the rest
+pub const LN_2_HI: f32 = 0.693359375;
+pub const LN_2_LO: f32 = -2.12194440e-4;
+pub const P_5: f32 = 1.9875691500e-4;
+pub const P_4: f32 = 1.3981999507e-3;
+pub const P_3: f32 = 8.3334519073e-3;
+pub const P_2: f32 = 4.1665795894e-2;
+pub const P_1: f32 = 1.6666665459e-1;
+pub const P_0: f32 = 5.0000001201e-1;
+
+/// Exponential, as 2^n * exp(r) with r in [-ln(2)/2, ln(2)/2] and exp(r)
+/// from a polynomial. Vectorized kernels implement the same computation, so
+/// they agree with it.
+///
+/// Flushes to 0 below LOW and overflows to +inf above HIGH. NaN goes through.
+#[inline(always)]
+pub fn sexp(x: f32) -> f32 {
+    if x < LOW {
+        return 0.0;
+    }
+    if x > HIGH {
+        return std::f32::INFINITY;
+    }
+    let n = (x * LOG2_E).round();
+    let r = x - n * LN_2_HI - n * LN_2_LO;
+
+    let y = r * P_5 + P_4;
+    let y = r * y + P_3;
+    let y = r * y + P_2;
+    let y = r * y + P_1;
+    let y = r * y + P_0;
+    let y = y * r * r + r + 1.0;
+
+    y * f32::from_bits(((n as i32 + 127) as u32) << 23)
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SExp4;
+
+impl ElementWiseKer<f32> for SExp4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn run(vec: &mut [f32]) {
+        vec.iter_mut().for_each(|x| *x = sexp(*x))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::element_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn exp(ref xs in strat_element_wise(-80.0, 80.0)) {
+            test_element_wise::<SExp4>(xs, |x| x.exp(), 1e-6)?
+        }
+
+        #[test]
+        fn exp_special(ref xs in strat_element_wise_special(-80.0, 80.0)) {
+            test_element_wise::<SExp4>(xs, |x| x.exp(), 1e-6)?
+        }
+
+        #[test]
+        fn softmax(ref xs in strat_element_wise(-50.0, 50.0)) {
+            test_softmax::<SExp4>(xs)?
+        }
+    }
+}
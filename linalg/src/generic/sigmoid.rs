@@ -0,0 +1,81 @@
+use crate::frame::element_wise::ElementWiseKer;
+
+pub const LOW: f32 = -18.0;
+pub const HIGH: f32 = 18.0;
+pub const ALPHA_9: f32 = 4.37031012579801e-11;
+pub const ALPHA_7: f32 = 1.15627324459942e-07;
+pub const ALPHA_5: f32 = 6.08574864600143e-05;
+pub const ALPHA_3: f32 = 8.51377133304701e-03;
+pub const ALPHA_1: f32 = 2.48287947061529e-01;
+pub const BETA_10: f32 = 6.10247389755681e-13;
+pub const BETA_8: f32 = 5.76102136993427e-09;
+pub const BETA_6: f32 = 6.29106785017040e-06;
+pub const BETA_4: f32 = 1.70198817374094e-03;
+pub const BETA_2: f32 = 1.16817656904453e-01;
+pub const BETA_0: f32 = 9.93151921023180e-01;
+
+/// Rational approximation of the sigmoid. Vectorized kernels implement the
+/// same computation, so they agree with it.
+///
+/// Exactly 0 below LOW and 1 above HIGH. NaN goes through.
+#[inline(always)]
+pub fn ssigmoid(x: f32) -> f32 {
+    if x <= LOW {
+        return 0.0;
+    }
+    if x >= HIGH {
+        return 1.0;
+    }
+    let x2 = x * x;
+
+    let p = x2 * ALPHA_9 + ALPHA_7;
+    let p = x2 * p + ALPHA_5;
+    let p = x2 * p + ALPHA_3;
+    let p = x2 * p + ALPHA_1;
+    let p = p * x;
+
+    let q = x2 * BETA_10 + BETA_8;
+    let q = x2 * q + BETA_6;
+    let q = x2 * q + BETA_4;
+    let q = x2 * q + BETA_2;
+    let q = x2 * q + BETA_0;
+
+    p / q + 0.5
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SSigmoid4;
+
+impl ElementWiseKer<f32> for SSigmoid4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn run(vec: &mut [f32]) {
+        vec.iter_mut().for_each(|x| *x = ssigmoid(*x))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::element_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn sigmoid(ref xs in strat_element_wise(-25.0, 25.0)) {
+            test_element_wise::<SSigmoid4>(xs, |x| 1.0 / (1.0 + (-x).exp()), 1e-6)?
+        }
+
+        #[test]
+        fn sigmoid_special(ref xs in strat_element_wise_special(-25.0, 25.0)) {
+            test_element_wise::<SSigmoid4>(xs, |x| 1.0 / (1.0 + (-x).exp()), 1e-6)?
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::frame::element_wise::ElementWiseKer;
+
+pub const LOW: f32 = -9.0;
+pub const HIGH: f32 = 9.0;
+pub const ALPHA_13: f32 = -2.76076847742355e-16;
+pub const ALPHA_11: f32 = 2.00018790482477e-13;
+pub const ALPHA_9: f32 = -8.60467152213735e-11;
+pub const ALPHA_7: f32 = 5.12229709037114e-08;
+pub const ALPHA_5: f32 = 1.48572235717979e-05;
+pub const ALPHA_3: f32 = 6.37261928875436e-04;
+pub const ALPHA_1: f32 = 4.89352455891786e-03;
+pub const BETA_6: f32 = 1.19825839466702e-06;
+pub const BETA_4: f32 = 1.18534705686654e-04;
+pub const BETA_2: f32 = 2.26843463243900e-03;
+pub const BETA_0: f32 = 4.89352518554385e-03;
+
+/// Rational approximation of tanh. Vectorized kernels implement the same
+/// computation, so they agree with it.
+///
+/// Inputs are clamped to [LOW, HIGH]. NaN goes through.
+#[inline(always)]
+pub fn stanh(x: f32) -> f32 {
+    let x = if x < LOW {
+        LOW
+    } else if x > HIGH {
+        HIGH
+    } else {
+        x
+    };
+    let x2 = x * x;
+
+    let p = x2 * ALPHA_13 + ALPHA_11;
+    let p = x2 * p + ALPHA_9;
+    let p = x2 * p + ALPHA_7;
+    let p = x2 * p + ALPHA_5;
+    let p = x2 * p + ALPHA_3;
+    let p = x2 * p + ALPHA_1;
+    let p = p * x;
+
+    let q = x2 * BETA_6 + BETA_4;
+    let q = x2 * q + BETA_2;
+    let q = x2 * q + BETA_0;
+
+    p / q
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct STanh4;
+
+impl ElementWiseKer<f32> for STanh4 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "generic"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        4
+    }
+    #[inline(always)]
+    fn run(vec: &mut [f32]) {
+        vec.iter_mut().for_each(|x| *x = stanh(*x))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::element_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn tanh(ref xs in strat_element_wise(-15.0, 15.0)) {
+            test_element_wise::<STanh4>(xs, |x| x.tanh(), 1e-6)?
+        }
+
+        #[test]
+        fn tanh_special(ref xs in strat_element_wise_special(-15.0, 15.0)) {
+            test_element_wise::<STanh4>(xs, |x| x.tanh(), 1e-6)?
+        }
+    }
+}
@@ -10,7 +10,7 @@ extern crate proptest;
 pub mod align;
 pub mod f16;
 pub mod frame;
-mod generic;
+pub mod generic;
 
 #[cfg(target_arch = "x86_64")]
 pub mod x86_64_fma;
@@ -28,6 +28,11 @@ pub struct Ops {
     pub smm: Box<Fn(usize, usize, usize) -> Box<MatMul<f32>> + Send + Sync>,
     pub dmm: Box<Fn(usize, usize, usize) -> Box<MatMul<f64>> + Send + Sync>,
     pub sconv: Box<Fn(usize, Vec<isize>, Vec<isize>) -> Box<Conv<f32>> + Send + Sync>,
+    pub sdepth_wise: Box<Fn(Vec<isize>, usize) -> Box<DepthWise<f32>> + Send + Sync>,
+    pub ssigmoid: Box<Fn() -> Box<ElementWise<f32>> + Send + Sync>,
+    pub stanh: Box<Fn() -> Box<ElementWise<f32>> + Send + Sync>,
+    pub sexp: Box<Fn() -> Box<ElementWise<f32>> + Send + Sync>,
+    pub ssoftmax: Box<Fn() -> Box<ElementWise<f32>> + Send + Sync>,
 }
 
 pub fn generic() -> Ops {
@@ -38,6 +43,13 @@ pub fn generic() -> Ops {
         sconv: Box::new(|co, kernel_offsets, data_offsets| {
             Box::new(PackedConv::<generic::SConv4x4, f32>::new(co, kernel_offsets, data_offsets))
         }),
+        sdepth_wise: Box::new(|offsets, stride| {
+            Box::new(DepthWiseImpl::<generic::SDepthWise4, f32>::new(offsets, stride))
+        }),
+        ssigmoid: Box::new(|| Box::new(ElementWiseImpl::<generic::SSigmoid4, f32>::new())),
+        stanh: Box::new(|| Box::new(ElementWiseImpl::<generic::STanh4, f32>::new())),
+        sexp: Box::new(|| Box::new(ElementWiseImpl::<generic::SExp4, f32>::new())),
+        ssoftmax: Box::new(|| Box::new(SoftMaxImpl::<generic::SExp4>::new())),
     }
 }
 
@@ -47,7 +59,7 @@ pub fn best() -> Ops {
     #[cfg(target_arch = "x86_64")]
     {
         if is_x86_feature_detected!("fma") {
-            log::info!("x86_64/fma activated for smm and sconv");
+            log::info!("x86_64/fma activated for smm, sconv, depthwise, sigmoid and tanh");
             ops.smm = Box::new(|m, k, n| {
                 Box::new(PackedMatMul::<x86_64_fma::matmul::KerFma16x6, f32>::new(m, k, n))
             });
@@ -58,6 +70,23 @@ pub fn best() -> Ops {
                     data_offsets,
                 ))
             });
+            ops.sdepth_wise = Box::new(|offsets, stride| {
+                Box::new(DepthWiseImpl::<x86_64_fma::depth_wise::SDepthWiseFma8, f32>::new(
+                    offsets, stride,
+                ))
+            });
+            ops.ssigmoid = Box::new(|| {
+                Box::new(ElementWiseImpl::<x86_64_fma::sigmoid::SSigmoidFma8, f32>::new())
+            });
+            ops.stanh =
+                Box::new(|| Box::new(ElementWiseImpl::<x86_64_fma::tanh::STanhFma8, f32>::new()));
+            if is_x86_feature_detected!("avx2") {
+                log::info!("x86_64/fma activated for exp and softmax");
+                ops.sexp =
+                    Box::new(|| Box::new(ElementWiseImpl::<x86_64_fma::exp::SExpFma8, f32>::new()));
+                ops.ssoftmax =
+                    Box::new(|| Box::new(SoftMaxImpl::<x86_64_fma::exp::SExpFma8>::new()));
+            }
         }
     }
     #[cfg(any(target_arch = "arm", target_arch = "armv7"))]
@@ -1,2 +1,6 @@
 pub mod conv;
+pub mod depth_wise;
+pub mod exp;
 pub mod matmul;
+pub mod sigmoid;
+pub mod tanh;
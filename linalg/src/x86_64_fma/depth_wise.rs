@@ -0,0 +1,123 @@
+use crate::frame;
+
+#[derive(Copy, Clone, Debug)]
+pub struct SDepthWiseFma8;
+
+#[target_feature(enable = "fma")]
+unsafe fn hwc(
+    taps: usize,
+    offsets: *const isize,
+    kernel: *const f32,
+    k_stride: usize,
+    bias: *const f32,
+    input: *const f32,
+    output: *mut f32,
+) {
+    use std::arch::x86_64::*;
+    let mut sum = _mm256_loadu_ps(bias);
+    for t in 0..taps {
+        let k = _mm256_loadu_ps(kernel.offset((t * k_stride) as isize));
+        let i = _mm256_loadu_ps(input.offset(*offsets.offset(t as isize)));
+        sum = _mm256_fmadd_ps(k, i, sum);
+    }
+    _mm256_storeu_ps(output, sum);
+}
+
+#[target_feature(enable = "fma")]
+unsafe fn chw(
+    taps: usize,
+    offsets: *const isize,
+    kernel: *const f32,
+    bias: f32,
+    input: *const f32,
+    stride: usize,
+    output: *mut f32,
+) {
+    use std::arch::x86_64::*;
+    let mut sum = _mm256_set1_ps(bias);
+    if stride == 1 {
+        for t in 0..taps {
+            let k = _mm256_set1_ps(*kernel.offset(t as isize));
+            let i = _mm256_loadu_ps(input.offset(*offsets.offset(t as isize)));
+            sum = _mm256_fmadd_ps(k, i, sum);
+        }
+    } else {
+        let s = stride as isize;
+        for t in 0..taps {
+            let k = _mm256_set1_ps(*kernel.offset(t as isize));
+            let p = input.offset(*offsets.offset(t as isize));
+            let i = _mm256_set_ps(
+                *p.offset(7 * s),
+                *p.offset(6 * s),
+                *p.offset(5 * s),
+                *p.offset(4 * s),
+                *p.offset(3 * s),
+                *p.offset(2 * s),
+                *p.offset(s),
+                *p,
+            );
+            sum = _mm256_fmadd_ps(k, i, sum);
+        }
+    }
+    _mm256_storeu_ps(output, sum);
+}
+
+impl frame::depth_wise::DepthWiseKer<f32> for SDepthWiseFma8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn hwc(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const f32,
+        k_stride: usize,
+        bias: *const f32,
+        input: *const f32,
+        output: *mut f32,
+    ) {
+        unsafe { hwc(taps, offsets, kernel, k_stride, bias, input, output) }
+    }
+    #[inline(always)]
+    fn chw(
+        taps: usize,
+        offsets: *const isize,
+        kernel: *const f32,
+        bias: f32,
+        input: *const f32,
+        stride: usize,
+        output: *mut f32,
+    ) {
+        unsafe { chw(taps, offsets, kernel, bias, input, stride, output) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::depth_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn depth_wise_chw(pb in strat_depth_wise()) {
+            if !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_depth_wise_chw::<SDepthWiseFma8>(&pb)?
+        }
+
+        #[test]
+        fn depth_wise_hwc(pb in strat_depth_wise()) {
+            if !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_depth_wise_hwc::<SDepthWiseFma8>(&pb)?
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::frame::element_wise::ElementWiseKer;
+use crate::generic::exp::*;
+
+#[derive(Copy, Clone, Debug)]
+pub struct SExpFma8;
+
+#[target_feature(enable = "avx2,fma")]
+unsafe fn sexp(vec: &mut [f32]) {
+    use std::arch::x86_64::*;
+    let low = _mm256_set1_ps(LOW);
+    let high = _mm256_set1_ps(HIGH);
+    let one = _mm256_set1_ps(1.0);
+    let inf = _mm256_set1_ps(std::f32::INFINITY);
+    for chunk in vec.chunks_exact_mut(8) {
+        let x = _mm256_loadu_ps(chunk.as_ptr());
+        let below = _mm256_cmp_ps(x, low, _CMP_LT_OQ);
+        let above = _mm256_cmp_ps(x, high, _CMP_GT_OQ);
+        // min and max return their second operand on NaN: x goes through
+        let x = _mm256_min_ps(high, _mm256_max_ps(low, x));
+        let n = _mm256_round_ps(
+            _mm256_mul_ps(x, _mm256_set1_ps(LOG2_E)),
+            _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC,
+        );
+        let r = _mm256_fnmadd_ps(n, _mm256_set1_ps(LN_2_HI), x);
+        let r = _mm256_fnmadd_ps(n, _mm256_set1_ps(LN_2_LO), r);
+
+        let y = _mm256_fmadd_ps(r, _mm256_set1_ps(P_5), _mm256_set1_ps(P_4));
+        let y = _mm256_fmadd_ps(r, y, _mm256_set1_ps(P_3));
+        let y = _mm256_fmadd_ps(r, y, _mm256_set1_ps(P_2));
+        let y = _mm256_fmadd_ps(r, y, _mm256_set1_ps(P_1));
+        let y = _mm256_fmadd_ps(r, y, _mm256_set1_ps(P_0));
+        let y = _mm256_add_ps(_mm256_fmadd_ps(y, _mm256_mul_ps(r, r), r), one);
+
+        let pow2n =
+            _mm256_slli_epi32(_mm256_add_epi32(_mm256_cvtps_epi32(n), _mm256_set1_epi32(127)), 23);
+        let y = _mm256_mul_ps(y, _mm256_castsi256_ps(pow2n));
+        let y = _mm256_blendv_ps(_mm256_andnot_ps(below, y), inf, above);
+        _mm256_storeu_ps(chunk.as_mut_ptr(), y);
+    }
+}
+
+impl ElementWiseKer<f32> for SExpFma8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn run(vec: &mut [f32]) {
+        unsafe { sexp(vec) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::element_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn exp(ref xs in strat_element_wise(-80.0, 80.0)) {
+            if !is_x86_feature_detected!("avx2") || !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_element_wise::<SExpFma8>(xs, |x| x.exp(), 1e-6)?
+        }
+
+        #[test]
+        fn exp_special(ref xs in strat_element_wise_special(-80.0, 80.0)) {
+            if !is_x86_feature_detected!("avx2") || !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_element_wise::<SExpFma8>(xs, |x| x.exp(), 1e-6)?
+        }
+
+        #[test]
+        fn softmax(ref xs in strat_element_wise(-50.0, 50.0)) {
+            if !is_x86_feature_detected!("avx2") || !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_softmax::<SExpFma8>(xs)?
+        }
+    }
+}
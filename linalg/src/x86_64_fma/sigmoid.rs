@@ -0,0 +1,78 @@
+use crate::frame::element_wise::ElementWiseKer;
+use crate::generic::sigmoid::*;
+
+#[derive(Copy, Clone, Debug)]
+pub struct SSigmoidFma8;
+
+#[target_feature(enable = "fma")]
+unsafe fn ssigmoid(vec: &mut [f32]) {
+    use std::arch::x86_64::*;
+    let low = _mm256_set1_ps(LOW);
+    let high = _mm256_set1_ps(HIGH);
+    let half = _mm256_set1_ps(0.5);
+    let one = _mm256_set1_ps(1.0);
+    for chunk in vec.chunks_exact_mut(8) {
+        let x = _mm256_loadu_ps(chunk.as_ptr());
+        let below = _mm256_cmp_ps(x, low, _CMP_LE_OQ);
+        let above = _mm256_cmp_ps(x, high, _CMP_GE_OQ);
+        // min and max return their second operand on NaN: x goes through
+        let x = _mm256_min_ps(high, _mm256_max_ps(low, x));
+        let x2 = _mm256_mul_ps(x, x);
+
+        let p = _mm256_fmadd_ps(x2, _mm256_set1_ps(ALPHA_9), _mm256_set1_ps(ALPHA_7));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_5));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_3));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_1));
+        let p = _mm256_mul_ps(p, x);
+
+        let q = _mm256_fmadd_ps(x2, _mm256_set1_ps(BETA_10), _mm256_set1_ps(BETA_8));
+        let q = _mm256_fmadd_ps(x2, q, _mm256_set1_ps(BETA_6));
+        let q = _mm256_fmadd_ps(x2, q, _mm256_set1_ps(BETA_4));
+        let q = _mm256_fmadd_ps(x2, q, _mm256_set1_ps(BETA_2));
+        let q = _mm256_fmadd_ps(x2, q, _mm256_set1_ps(BETA_0));
+
+        let y = _mm256_add_ps(_mm256_div_ps(p, q), half);
+        let y = _mm256_blendv_ps(_mm256_andnot_ps(below, y), one, above);
+        _mm256_storeu_ps(chunk.as_mut_ptr(), y);
+    }
+}
+
+impl ElementWiseKer<f32> for SSigmoidFma8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn run(vec: &mut [f32]) {
+        unsafe { ssigmoid(vec) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::element_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn sigmoid(ref xs in strat_element_wise(-25.0, 25.0)) {
+            if !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_element_wise::<SSigmoidFma8>(xs, |x| 1.0 / (1.0 + (-x).exp()), 1e-6)?
+        }
+
+        #[test]
+        fn sigmoid_special(ref xs in strat_element_wise_special(-25.0, 25.0)) {
+            if !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_element_wise::<SSigmoidFma8>(xs, |x| 1.0 / (1.0 + (-x).exp()), 1e-6)?
+        }
+    }
+}
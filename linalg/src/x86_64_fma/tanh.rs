@@ -0,0 +1,72 @@
+use crate::frame::element_wise::ElementWiseKer;
+use crate::generic::tanh::*;
+
+#[derive(Copy, Clone, Debug)]
+pub struct STanhFma8;
+
+#[target_feature(enable = "fma")]
+unsafe fn stanh(vec: &mut [f32]) {
+    use std::arch::x86_64::*;
+    let low = _mm256_set1_ps(LOW);
+    let high = _mm256_set1_ps(HIGH);
+    for chunk in vec.chunks_exact_mut(8) {
+        let x = _mm256_loadu_ps(chunk.as_ptr());
+        // min and max return their second operand on NaN: x goes through
+        let x = _mm256_min_ps(high, _mm256_max_ps(low, x));
+        let x2 = _mm256_mul_ps(x, x);
+
+        let p = _mm256_fmadd_ps(x2, _mm256_set1_ps(ALPHA_13), _mm256_set1_ps(ALPHA_11));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_9));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_7));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_5));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_3));
+        let p = _mm256_fmadd_ps(x2, p, _mm256_set1_ps(ALPHA_1));
+        let p = _mm256_mul_ps(p, x);
+
+        let q = _mm256_fmadd_ps(x2, _mm256_set1_ps(BETA_6), _mm256_set1_ps(BETA_4));
+        let q = _mm256_fmadd_ps(x2, q, _mm256_set1_ps(BETA_2));
+        let q = _mm256_fmadd_ps(x2, q, _mm256_set1_ps(BETA_0));
+
+        _mm256_storeu_ps(chunk.as_mut_ptr(), _mm256_div_ps(p, q));
+    }
+}
+
+impl ElementWiseKer<f32> for STanhFma8 {
+    #[inline(always)]
+    fn name() -> &'static str {
+        "fma"
+    }
+    #[inline(always)]
+    fn nr() -> usize {
+        8
+    }
+    #[inline(always)]
+    fn run(vec: &mut [f32]) {
+        unsafe { stanh(vec) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::frame::element_wise::test::*;
+    use proptest::*;
+
+    proptest! {
+        #[test]
+        fn tanh(ref xs in strat_element_wise(-15.0, 15.0)) {
+            if !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_element_wise::<STanhFma8>(xs, |x| x.tanh(), 1e-6)?
+        }
+
+        #[test]
+        fn tanh_special(ref xs in strat_element_wise_special(-15.0, 15.0)) {
+            if !is_x86_feature_detected!("fma") {
+                return Ok(())
+            }
+            test_element_wise::<STanhFma8>(xs, |x| x.tanh(), 1e-6)?
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use std::convert::TryInto;
 
 use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 use tract_core::internal::*;
+use tract_core::tensor::Mmap;
 
 use crate::pb;
 
@@ -12,23 +15,53 @@ pub struct Onnx {
     pub op_register: OnnxOpRegister,
 }
 
-impl Framework<pb::NodeProto, pb::ModelProto> for Onnx {
-    fn op_builder_for_name(&self, name: &str) -> Option<&OpBuilder<pb::NodeProto>> {
-        self.op_register.get(name)
+impl Onnx {
+    /// Read a model, resolving the external data files relative to `dir`.
+    pub fn model_for_read_with_base_dir(
+        &self,
+        r: &mut std::io::Read,
+        dir: impl AsRef<Path>,
+    ) -> TractResult<InferenceModel> {
+        let proto_model = self.proto_model_for_read(r)?;
+        self.model_for_proto_model_with_base_dir(&proto_model, dir)
     }
 
-    fn proto_model_for_read(&self, r: &mut std::io::Read) -> TractResult<pb::ModelProto> {
-        Ok(::protobuf::parse_from_reader(r).map_err(|e| format!("{:?}", e))?)
+    /// Translate a proto model into a model, resolving the external data
+    /// files relative to `dir`.
+    pub fn model_for_proto_model_with_base_dir(
+        &self,
+        proto: &pb::ModelProto,
+        dir: impl AsRef<Path>,
+    ) -> TractResult<InferenceModel> {
+        self.model_for_proto_model_and_tensors(proto, HashMap::new(), Some(dir.as_ref()))
     }
 
-    fn model_for_proto_model(&self, proto: &pb::ModelProto) -> TractResult<InferenceModel> {
+    fn model_for_proto_model_and_tensors(
+        &self,
+        proto: &pb::ModelProto,
+        mut initializers: HashMap<String, Tensor>,
+        dir: Option<&Path>,
+    ) -> TractResult<InferenceModel> {
         let mut model = Model::default();
         let graph = proto.get_graph();
-        let mut initializers: HashMap<&str, Tensor> = graph
-            .get_initializer()
-            .iter()
-            .map(|init| Ok((init.get_name(), init.try_into()?)))
-            .collect::<TractResult<_>>()?;
+        let mut maps = HashMap::<PathBuf, Arc<Mmap>>::new();
+        for init in graph.get_initializer() {
+            if initializers.contains_key(init.get_name()) {
+                continue;
+            }
+            let tensor = if init.is_external() {
+                let dir = dir.ok_or_else(|| {
+                    format!(
+                        "Initializer {} has external data, but no base directory was given",
+                        init.get_name()
+                    )
+                })?;
+                crate::tensor::load_external(init, dir, &mut maps)?
+            } else {
+                init.try_into()?
+            };
+            initializers.insert(init.get_name().to_string(), tensor);
+        }
         let mut outlets_by_name = HashMap::<String, OutletId>::new();
         for input in graph.get_input().iter() {
             if let Some(init) = initializers.remove(input.get_name()) {
@@ -69,3 +102,312 @@ impl Framework<pb::NodeProto, pb::ModelProto> for Onnx {
         Ok(model)
     }
 }
+
+impl Framework<pb::NodeProto, pb::ModelProto> for Onnx {
+    fn op_builder_for_name(&self, name: &str) -> Option<&OpBuilder<pb::NodeProto>> {
+        self.op_register.get(name)
+    }
+
+    fn proto_model_for_read(&self, r: &mut std::io::Read) -> TractResult<pb::ModelProto> {
+        Ok(::protobuf::parse_from_reader(r).map_err(|e| format!("{:?}", e))?)
+    }
+
+    fn model_for_proto_model(&self, proto: &pb::ModelProto) -> TractResult<InferenceModel> {
+        self.model_for_proto_model_and_tensors(proto, HashMap::new(), None)
+    }
+
+    /// Build a model from a filename.
+    ///
+    /// The file is memory-mapped, and initializers stored as raw data are
+    /// backed by the mapping instead of being copied. External data files are
+    /// resolved relative to the model directory.
+    ///
+    /// The file must not be modified, truncated or replaced in place while the
+    /// model, or any tensor taken from it, is alive: the mapped initializers
+    /// would change under the model, or access to them would crash. Use
+    /// `model_for_read` to load a file that may change.
+    fn model_for_path(&self, p: impl AsRef<Path>) -> TractResult<InferenceModel> {
+        Ok(self.proto_and_model_for_path(p)?.1)
+    }
+}
+
+impl Onnx {
+    /// Build a model from a filename, like `model_for_path`, and also return
+    /// its proto.
+    ///
+    /// The raw data of the initializers is skipped when parsing the proto, so
+    /// the weights are neither copied nor kept in the returned proto. Protobuf
+    /// does not align raw data: an initializer whose region is not aligned
+    /// for its type is copied. The mapped and copied sizes are logged.
+    ///
+    /// As with `model_for_path`, the file must not be modified while the
+    /// model is alive.
+    pub fn proto_and_model_for_path(
+        &self,
+        p: impl AsRef<Path>,
+    ) -> TractResult<(pb::ModelProto, InferenceModel)> {
+        let map = unsafe { tract_core::tensor::map_file(p.as_ref())? };
+        let (stripped, regions) = strip_raw_initializers(&map)?;
+        let mut proto: pb::ModelProto =
+            ::protobuf::parse_from_bytes(&stripped).map_err(|e| format!("{:?}", e))?;
+        let tensors = mapped_initializers(&mut proto, &map, regions)?;
+        let dir = p.as_ref().parent().unwrap_or(Path::new(""));
+        let model = self.model_for_proto_model_and_tensors(&proto, tensors, Some(dir))?;
+        Ok((proto, model))
+    }
+}
+
+/// Build the raw data initializers as tensors backed by the mapped model.
+///
+/// `regions` locates the raw data of each initializer in the mapping. Raw data
+/// that can not be mapped is put back in the proto.
+fn mapped_initializers(
+    proto: &mut pb::ModelProto,
+    map: &Arc<Mmap>,
+    regions: Vec<Option<Range<usize>>>,
+) -> TractResult<HashMap<String, Tensor>> {
+    let mut tensors = HashMap::new();
+    let initializers = proto.mut_graph().mut_initializer();
+    if regions.len() != initializers.len() {
+        bail!("Found {} raw initializers for {} initializers", regions.len(), initializers.len())
+    }
+    let (mut mapped, mut copied) = ((0, 0), (0, 0));
+    for (init, region) in initializers.iter_mut().zip(regions.into_iter()) {
+        let region = match region {
+            Some(region) => region,
+            None => continue,
+        };
+        let dt: DatumType = init.get_data_type().try_into()?;
+        let shape: Vec<usize> = init.get_dims().iter().map(|&d| d as usize).collect();
+        if dt == DatumType::Bool || shape.iter().product::<usize>() * dt.size_of() != region.len() {
+            init.set_raw_data(map[region].to_vec());
+            continue;
+        }
+        let len = region.len();
+        let tensor = unsafe { Tensor::from_mmap(dt, &shape, map.clone(), region.start)? };
+        let count = if tensor.is_mapped() { &mut mapped } else { &mut copied };
+        *count = (count.0 + 1, count.1 + len);
+        tensors.insert(init.get_name().to_string(), tensor);
+    }
+    info!(
+        "Mapped {} initializers ({} bytes), copied {} misaligned ones ({} bytes)",
+        mapped.0, mapped.1, copied.0, copied.1
+    );
+    Ok(tensors)
+}
+
+/// Copy a serialized ModelProto without the raw data of the graph
+/// initializers, and locate that raw data in the original bytes.
+///
+/// Regions are listed in initializer order, like the parsed proto would.
+fn strip_raw_initializers(model: &[u8]) -> TractResult<(Vec<u8>, Vec<Option<Range<usize>>>)> {
+    let mut stripped = vec![];
+    let mut regions = vec![];
+    for field in fields(model, 0..model.len())? {
+        match field.payload {
+            Some(graph) if field.key == 7 << 3 | 2 => {
+                let mut stripped_graph = vec![];
+                for field in fields(model, graph)? {
+                    match field.payload {
+                        Some(init) if field.key == 5 << 3 | 2 => {
+                            let mut stripped_init = vec![];
+                            let mut region = None;
+                            for field in fields(model, init)? {
+                                match field.payload {
+                                    Some(raw) if field.key == 9 << 3 | 2 => region = Some(raw),
+                                    _ => stripped_init.extend(&model[field.span]),
+                                }
+                            }
+                            regions.push(region);
+                            write_length_delimited(&mut stripped_graph, field.key, &stripped_init);
+                        }
+                        _ => stripped_graph.extend(&model[field.span]),
+                    }
+                }
+                write_length_delimited(&mut stripped, field.key, &stripped_graph);
+            }
+            _ => stripped.extend(&model[field.span]),
+        }
+    }
+    Ok((stripped, regions))
+}
+
+/// A field of a serialized protobuf message.
+#[derive(Debug, PartialEq)]
+struct Field {
+    /// Field number and wire type.
+    key: u64,
+    /// Bytes of the whole field, key included.
+    span: Range<usize>,
+    /// Bytes of the value, for length delimited fields.
+    payload: Option<Range<usize>>,
+}
+
+/// Scan the fields of a protobuf message.
+fn fields(bytes: &[u8], message: Range<usize>) -> TractResult<Vec<Field>> {
+    let mut found = vec![];
+    let mut pos = message.start;
+    while pos < message.end {
+        let start = pos;
+        let key = varint(bytes, &mut pos)?;
+        let mut payload = None;
+        match key & 7 {
+            0 => {
+                varint(bytes, &mut pos)?;
+            }
+            1 => pos += 8,
+            2 => {
+                let len = varint(bytes, &mut pos)? as usize;
+                payload = Some(pos..pos.saturating_add(len));
+                pos = pos.saturating_add(len);
+            }
+            5 => pos += 4,
+            wire_type => bail!("Unsupported protobuf wire type {}", wire_type),
+        }
+        found.push(Field { key, span: start..pos, payload });
+    }
+    if pos != message.end {
+        bail!("Truncated protobuf message")
+    }
+    Ok(found)
+}
+
+fn write_length_delimited(buffer: &mut Vec<u8>, key: u64, payload: &[u8]) {
+    write_varint(buffer, key);
+    write_varint(buffer, payload.len() as u64);
+    buffer.extend(payload);
+}
+
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn varint(bytes: &[u8], pos: &mut usize) -> TractResult<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or("Truncated protobuf varint")?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    bail!("Invalid protobuf varint")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::*;
+    use protobuf::Message;
+    use std::convert::TryFrom;
+
+    fn raw_initializer(name: &str, values: &[f32]) -> TensorProto {
+        let mut init = TensorProto::new();
+        init.set_name(name.to_string());
+        init.set_data_type(TensorProto_DataType::FLOAT);
+        init.set_dims(vec![values.len() as i64]);
+        init.set_raw_data(values.iter().flat_map(|v| v.to_bits().to_le_bytes().to_vec()).collect());
+        init
+    }
+
+    /// y = x + w, w being a raw initializer.
+    fn model(values: &[f32]) -> ModelProto {
+        let mut graph = GraphProto::new();
+        graph.mut_initializer().push(raw_initializer("w", values));
+        let mut typed = TensorProto::new();
+        typed.set_name("v".to_string());
+        typed.set_data_type(TensorProto_DataType::FLOAT);
+        typed.set_float_data(vec![1.0]);
+        graph.mut_initializer().push(typed);
+        let fact = TensorFact::dt_shape(DatumType::F32, vec![values.len()]);
+        for name in &["x", "w", "y"] {
+            let mut field_type = TypeProto::new();
+            field_type.set_tensor_type(TypeProto_Tensor::try_from(&fact).unwrap());
+            let mut info = ValueInfoProto::new();
+            info.set_name(name.to_string());
+            info.set_field_type(field_type);
+            if name == &"y" {
+                graph.mut_output().push(info);
+            } else {
+                graph.mut_input().push(info);
+            }
+        }
+        let mut add = NodeProto::new();
+        add.set_op_type("Add".to_string());
+        add.set_input(vec!["x".to_string(), "w".to_string()].into());
+        add.set_output(vec!["y".to_string()].into());
+        graph.mut_node().push(add);
+        let mut opset = OperatorSetIdProto::new();
+        opset.set_version(9);
+        let mut model = ModelProto::new();
+        model.mut_opset_import().push(opset);
+        model.set_graph(graph);
+        model
+    }
+
+    #[test]
+    fn varint_decoding() {
+        let mut pos = 1;
+        assert_eq!(varint(&[0xff, 0xac, 0x02, 0x01], &mut pos).unwrap(), 300);
+        assert_eq!(pos, 3);
+        assert!(varint(&[0xac], &mut 0).is_err());
+        assert!(varint(&[0xff; 11], &mut 0).is_err());
+        for &value in &[0, 1, 127, 128, 300, std::u64::MAX] {
+            let mut buffer = vec![];
+            write_varint(&mut buffer, value);
+            assert_eq!(varint(&buffer, &mut 0).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn message_fields() {
+        // 1: varint 150, 2: fixed64, 3: "ab", 4: fixed32
+        let mut bytes = vec![0x08, 0x96, 0x01, 0x11, 0, 0, 0, 0, 0, 0, 0, 0];
+        write_length_delimited(&mut bytes, 3 << 3 | 2, b"ab");
+        bytes.extend(&[0x25, 0, 0, 0, 0]);
+        let found = fields(&bytes, 0..bytes.len()).unwrap();
+        assert_eq!(
+            found,
+            vec![
+                Field { key: 1 << 3, span: 0..3, payload: None },
+                Field { key: 2 << 3 | 1, span: 3..12, payload: None },
+                Field { key: 3 << 3 | 2, span: 12..16, payload: Some(14..16) },
+                Field { key: 4 << 3 | 5, span: 16..21, payload: None },
+            ]
+        );
+        assert!(fields(&bytes, 0..bytes.len() - 1).is_err());
+        assert!(fields(&bytes[..15], 0..15).is_err());
+        assert!(fields(&[0x0b], 0..1).is_err());
+    }
+
+    #[test]
+    fn strip_raw_initializers_locates_raw_data() {
+        let bytes = model(&[1.0, 2.0]).write_to_bytes().unwrap();
+        let (stripped, regions) = strip_raw_initializers(&bytes).unwrap();
+        assert_eq!(regions.len(), 2);
+        let raw = regions[0].clone().unwrap();
+        assert_eq!(&bytes[raw], &*raw_initializer("w", &[1.0, 2.0]).get_raw_data());
+        assert!(regions[1].is_none());
+        let parsed: ModelProto = ::protobuf::parse_from_bytes(&stripped).unwrap();
+        let mut expected = model(&[1.0, 2.0]);
+        expected.mut_graph().mut_initializer()[0].clear_raw_data();
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn model_for_path_maps_raw_initializers() {
+        let path = std::env::temp_dir().join(format!("tract-{}-mapped.onnx", std::process::id()));
+        std::fs::write(&path, model(&[1.0, 2.0]).write_to_bytes().unwrap()).unwrap();
+        let (proto, model) = crate::onnx().proto_and_model_for_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!proto.get_graph().get_initializer()[0].has_raw_data());
+        let plan = SimplePlan::new(model).unwrap();
+        let y = plan.run(tvec!(tensor1(&[3f32, 4.0]))).unwrap();
+        assert_eq!(*y[0], tensor1(&[4f32, 6.0]));
+    }
+}
@@ -18,8 +18,13 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("EyeLike", eye_like);
     reg.insert("Flatten", flatten);
     reg.insert("Gather", gather);
+    reg.insert("GatherElements", gather_elements);
+    reg.insert("GatherND", gather_nd);
     reg.insert("Pad", pad);
     reg.insert("Reshape", |_| Ok(Box::new(tractops::array::Reshape::default())));
+    reg.insert("Scatter", scatter_elements);
+    reg.insert("ScatterElements", scatter_elements);
+    reg.insert("ScatterND", scatter_nd);
     reg.insert("Shape", |_| Ok(Box::new(tractops::array::Shape::new(DatumType::I64))));
     reg.insert("Size", |_| Ok(Box::new(tractops::array::Size::new(DatumType::I64))));
     reg.insert("Transpose", transpose);
@@ -97,6 +102,17 @@ pub fn gather(node: &NodeProto) -> TractResult<Box<Op>> {
     Ok(Box::new(tractops::array::Gather::new(axis)))
 }
 
+pub fn gather_elements(node: &NodeProto) -> TractResult<Box<Op>> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(0);
+    Ok(Box::new(tractops::array::GatherElements::new(axis)))
+}
+
+pub fn gather_nd(node: &NodeProto) -> TractResult<Box<Op>> {
+    let batch_dims = node.get_attr_opt::<i64>("batch_dims")?.unwrap_or(0);
+    node.expect_attr("batch_dims", batch_dims == 0, "0, batch dimensions are not supported")?;
+    Ok(Box::new(tractops::array::GatherNd::new()))
+}
+
 pub fn pad(node: &NodeProto) -> TractResult<Box<Op>> {
     let value = node.get_attr_opt("value")?;
     let mode = match node.get_attr_opt("mode")? {
@@ -117,6 +133,27 @@ pub fn pad(node: &NodeProto) -> TractResult<Box<Op>> {
     Ok(Box::new(tractops::array::Pad::new(pads, mode)))
 }
 
+fn scatter_reduction(node: &NodeProto) -> TractResult<tractops::array::ScatterReduction> {
+    match node.get_attr_opt("reduction")? {
+        None => Ok(tractops::array::ScatterReduction::None),
+        Some(reduction) => node.check_value(
+            "reduction",
+            tractops::array::ScatterReduction::parse(reduction).map_err(|_| reduction),
+        ),
+    }
+}
+
+pub fn scatter_elements(node: &NodeProto) -> TractResult<Box<Op>> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(0);
+    let reduction = scatter_reduction(node)?;
+    Ok(Box::new(tractops::array::ScatterElements::new(axis, reduction)))
+}
+
+pub fn scatter_nd(node: &NodeProto) -> TractResult<Box<Op>> {
+    let reduction = scatter_reduction(node)?;
+    Ok(Box::new(tractops::array::ScatterNd::new(reduction)))
+}
+
 pub fn slice(node: &NodeProto) -> TractResult<Box<Op>> {
     let axes = node.get_attr_opt_vec("axes")?;
     let begin = node.get_attr_vec("starts")?;
@@ -180,9 +180,13 @@ pub fn max_pool(node: &NodeProto) -> TractResult<Box<Op>> {
     let kernel_shape = node.get_attr_tvec("kernel_shape")?;
     let pad = pad(node)?;
     let strides = strides(node)?;
+    if node.get_attr_opt("storage_order")?.unwrap_or(0) != 0 {
+        bail!("MaxPool with column-major storage_order is not supported");
+    }
     Ok(Box::new(tractops::cnn::MaxPool::new(
         tractops::cnn::PoolSpec::new(DataFormat::NCHW, kernel_shape, pad, strides),
         if node.get_output().len() == 2 { Some(DatumType::I64) } else { None },
+        true,
     )))
 }
 
@@ -394,3 +394,60 @@ impl NodeProto {
         self.get_attr_tvec(name).map(TVec::into_vec)
     }
 }
+
+impl ModelProto {
+    pub fn save_to<P: AsRef<::std::path::Path>>(self, p: P) -> TractResult<()> {
+        use protobuf::Message;
+        use std::io::Write;
+        let bytes = self.write_to_bytes().map_err(|e| format!("{:?}", e))?;
+        ::std::fs::File::create(p)?.write_all(&*bytes)?;
+        Ok(())
+    }
+}
+
+impl TensorProto {
+    /// Check if the data is stored in an external file.
+    ///
+    /// `data_location` (field 14) postdates the generated code, so it is read
+    /// from the unknown fields.
+    pub fn is_external(&self) -> bool {
+        use protobuf::Message;
+        self.get_unknown_fields().get(14).map(|v| v.varint.last() == Some(&1)).unwrap_or(false)
+    }
+
+    /// Get the `external_data` (field 13) key-value pairs.
+    pub fn get_external_data(&self) -> TractResult<Vec<StringStringEntryProto>> {
+        use protobuf::Message;
+        self.get_unknown_fields()
+            .get(13)
+            .map(|v| &*v.length_delimited)
+            .unwrap_or(&[])
+            .iter()
+            .map(|bytes| Ok(::protobuf::parse_from_bytes(bytes).map_err(|e| format!("{:?}", e))?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protobuf::Message;
+
+    #[test]
+    fn external_data() {
+        let mut t = TensorProto::new();
+        assert!(!t.is_external());
+        assert!(t.get_external_data().unwrap().is_empty());
+        let mut entry = StringStringEntryProto::new();
+        entry.set_key("location".to_string());
+        entry.set_value("weights.bin".to_string());
+        t.mut_unknown_fields().add_length_delimited(13, entry.write_to_bytes().unwrap());
+        t.mut_unknown_fields().add_varint(14, 0);
+        assert!(!t.is_external());
+        t.mut_unknown_fields().add_varint(14, 1);
+        assert!(t.is_external());
+        let t: TensorProto = ::protobuf::parse_from_bytes(&t.write_to_bytes().unwrap()).unwrap();
+        assert!(t.is_external());
+        assert_eq!(t.get_external_data().unwrap(), vec![entry]);
+    }
+}
@@ -2,6 +2,9 @@ use crate::pb::*;
 use tract_core::internal::*;
 use tract_core::*;
 use std::convert::{ TryFrom, TryInto };
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use tract_core::tensor::Mmap;
 
 impl TryFrom<TensorProto_DataType> for DatumType {
     type Error = TractError;
@@ -39,6 +42,27 @@ impl TryFrom<TensorProto_DataType> for DatumType {
     */
 }
 
+impl TryFrom<DatumType> for TensorProto_DataType {
+    type Error = TractError;
+    fn try_from(t: DatumType) -> TractResult<TensorProto_DataType> {
+        use self::TensorProto_DataType::*;
+        match t {
+            DatumType::Bool => Ok(BOOL),
+            DatumType::U8 => Ok(UINT8),
+            DatumType::U16 => Ok(UINT16),
+            DatumType::I8 => Ok(INT8),
+            DatumType::I16 => Ok(INT16),
+            DatumType::I32 => Ok(INT32),
+            DatumType::I64 => Ok(INT64),
+            DatumType::F16 => Ok(FLOAT16),
+            DatumType::F32 => Ok(FLOAT),
+            DatumType::F64 => Ok(DOUBLE),
+            DatumType::String => Ok(STRING),
+            _ => bail!("{:?} is not translatable in protobuf", t),
+        }
+    }
+}
+
 impl<'a> TryFrom<&'a TypeProto_Tensor> for TensorFact {
     type Error = TractError;
     fn try_from(t: &'a TypeProto_Tensor) -> TractResult<TensorFact> {
@@ -72,9 +96,36 @@ impl TryFrom<TypeProto_Tensor> for TensorFact {
     }
 }
 
+impl<'a> TryFrom<&'a TensorFact> for TypeProto_Tensor {
+    type Error = TractError;
+    fn try_from(fact: &'a TensorFact) -> TractResult<TypeProto_Tensor> {
+        let mut t = TypeProto_Tensor::new();
+        if let Some(dt) = fact.datum_type.concretize() {
+            t.set_elem_type(dt.try_into()?);
+        }
+        if !fact.shape.is_open() {
+            let mut shape = TensorShapeProto::new();
+            for d in fact.shape.dims() {
+                let mut dim = TensorShapeProto_Dimension::new();
+                match d.concretize().map(|d| d.to_integer()) {
+                    Some(Ok(d)) => dim.set_dim_value(d as i64),
+                    Some(Err(_)) => dim.set_dim_param("S".to_string()),
+                    None => (),
+                }
+                shape.mut_dim().push(dim);
+            }
+            t.set_shape(shape);
+        }
+        Ok(t)
+    }
+}
+
 impl<'a> TryFrom<&'a TensorProto> for Tensor {
     type Error = TractError;
     fn try_from(t: &TensorProto) -> TractResult<Tensor> {
+        if t.is_external() {
+            bail!("Tensor {} data is stored in an external file", t.get_name())
+        }
         let dt = t.get_data_type().try_into()?;
         let shape: Vec<usize> = t.get_dims().iter().map(|&i| i as usize).collect();
         if t.has_raw_data() {
@@ -153,6 +204,44 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
     }
 }
 
+impl<'a> TryFrom<&'a Tensor> for TensorProto {
+    type Error = TractError;
+    fn try_from(from: &Tensor) -> TractResult<TensorProto> {
+        let mut tensor = TensorProto::new();
+        tensor.set_dims(from.shape().iter().map(|&d| d as i64).collect());
+        tensor.set_data_type(from.datum_type().try_into()?);
+        match from.datum_type() {
+            DatumType::Bool => tensor
+                .set_int32_data(from.to_array_view::<bool>()?.iter().map(|&x| x as i32).collect()),
+            DatumType::U8 => tensor
+                .set_int32_data(from.to_array_view::<u8>()?.iter().map(|&x| x as i32).collect()),
+            DatumType::U16 => tensor
+                .set_int32_data(from.to_array_view::<u16>()?.iter().map(|&x| x as i32).collect()),
+            DatumType::I8 => tensor
+                .set_int32_data(from.to_array_view::<i8>()?.iter().map(|&x| x as i32).collect()),
+            DatumType::I16 => tensor
+                .set_int32_data(from.to_array_view::<i16>()?.iter().map(|&x| x as i32).collect()),
+            DatumType::I32 => {
+                tensor.set_int32_data(from.to_array_view::<i32>()?.iter().cloned().collect())
+            }
+            DatumType::I64 => {
+                tensor.set_int64_data(from.to_array_view::<i64>()?.iter().cloned().collect())
+            }
+            DatumType::F32 => {
+                tensor.set_float_data(from.to_array_view::<f32>()?.iter().cloned().collect())
+            }
+            DatumType::F64 => {
+                tensor.set_double_data(from.to_array_view::<f64>()?.iter().cloned().collect())
+            }
+            DatumType::String => tensor.set_string_data(
+                from.to_array_view::<String>()?.iter().map(|s| s.as_bytes().to_vec()).collect(),
+            ),
+            _ => bail!("Can not translate {:?} tensor to protobuf", from.datum_type()),
+        }
+        Ok(tensor)
+    }
+}
+
 impl TryFrom<TensorProto> for Tensor {
     type Error = TractError;
     fn try_from(t: TensorProto) -> TractResult<Tensor> {
@@ -164,3 +253,162 @@ pub fn from_reader<R: ::std::io::Read>(mut r: R) -> TractResult<Tensor> {
     let tensor: TensorProto = ::protobuf::parse_from_reader(&mut r).unwrap();
     tensor.try_into()
 }
+
+/// Load a tensor which data is stored in an external file.
+///
+/// The file location is resolved relative to `dir`. Files are memory-mapped
+/// once and kept in `maps`, so the tensors they contain share the mapping.
+pub fn load_external(
+    t: &TensorProto,
+    dir: &Path,
+    maps: &mut HashMap<PathBuf, Arc<Mmap>>,
+) -> TractResult<Tensor> {
+    let dt: DatumType = t.get_data_type().try_into()?;
+    let shape: Vec<usize> = t.get_dims().iter().map(|&i| i as usize).collect();
+    let mut location = None;
+    let mut offset = 0;
+    let mut length = None;
+    for entry in t.get_external_data()? {
+        match entry.get_key() {
+            "location" => location = Some(entry.get_value().to_string()),
+            "offset" => offset = entry.get_value().parse()?,
+            "length" => length = Some(entry.get_value().parse::<usize>()?),
+            _ => (),
+        }
+    }
+    let location = location
+        .ok_or_else(|| format!("Tensor {}: external data has no location", t.get_name()))?;
+    let relative = Path::new(&location);
+    if relative.is_absolute() || relative.components().any(|c| c == Component::ParentDir) {
+        bail!(
+            "Tensor {}: external data location {:?} is outside the model directory",
+            t.get_name(),
+            location
+        )
+    }
+    let expected = shape.iter().product::<usize>() * dt.size_of();
+    if let Some(length) = length {
+        if length != expected {
+            bail!(
+                "Tensor {}: external data length is {}, expected {}",
+                t.get_name(),
+                length,
+                expected
+            )
+        }
+    }
+    let path = dir.join(relative);
+    let map = match maps.get(&path) {
+        Some(map) => map.clone(),
+        None => {
+            let map = unsafe { tract_core::tensor::map_file(&path)? };
+            maps.insert(path, map.clone());
+            map
+        }
+    };
+    unsafe {
+        match dt {
+            DatumType::Bool => Ok(Tensor::from_mmap(DatumType::U8, &*shape, map, offset)?
+                .into_array::<u8>()?
+                .mapv(|x| x != 0)
+                .into()),
+            DatumType::String => {
+                bail!("Tensor {}: can not load external string data", t.get_name())
+            }
+            _ => Tensor::from_mmap(dt, &*shape, map, offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(t: Tensor) {
+        let proto = TensorProto::try_from(&t).unwrap();
+        assert_eq!(Tensor::try_from(&proto).unwrap(), t);
+    }
+
+    #[test]
+    fn tensor_round_trip() {
+        round_trip(tensor0(3f32));
+        round_trip(tensor2(&[[1f32, 2.0, 3.0], [4.0, 5.0, 6.0]]));
+        round_trip(tensor1(&[1f64, -2.0]));
+        round_trip(tensor1(&[true, false]));
+        round_trip(tensor1(&[1u8, 255]));
+        round_trip(tensor1(&[1i8, -2]));
+        round_trip(tensor1(&[1i32, -2]));
+        round_trip(tensor1(&[1i64, -2]));
+        round_trip(tensor1(&["a".to_string(), "bc".to_string()]));
+    }
+
+    fn external(entries: &[(&str, &str)], dims: &[i64]) -> TensorProto {
+        use protobuf::Message;
+        let mut t = TensorProto::new();
+        t.set_name("w".to_string());
+        t.set_data_type(TensorProto_DataType::FLOAT);
+        t.set_dims(dims.to_vec());
+        for (key, value) in entries {
+            let mut entry = StringStringEntryProto::new();
+            entry.set_key(key.to_string());
+            entry.set_value(value.to_string());
+            t.mut_unknown_fields().add_length_delimited(13, entry.write_to_bytes().unwrap());
+        }
+        t.mut_unknown_fields().add_varint(14, 1);
+        t
+    }
+
+    fn load(entries: &[(&str, &str)], dims: &[i64]) -> TractResult<Tensor> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/models/external");
+        load_external(&external(entries, dims), &dir, &mut HashMap::new())
+    }
+
+    #[test]
+    fn external_offset_and_length() {
+        let t = load(&[("location", "weights.bin"), ("offset", "4"), ("length", "12")], &[3]);
+        assert_eq!(t.unwrap(), tensor1(&[1f32, 2.0, 3.0]));
+        let t = load(&[("location", "weights.bin"), ("offset", "8")], &[2]);
+        assert_eq!(t.unwrap(), tensor1(&[2f32, 3.0]));
+        let t = load(&[("location", "weights.bin")], &[2, 2]);
+        assert_eq!(t.unwrap(), tensor2(&[[0f32, 1.0], [2.0, 3.0]]));
+    }
+
+    #[test]
+    fn external_errors() {
+        assert!(load(&[("location", "weights.bin"), ("length", "8")], &[3]).is_err());
+        assert!(load(&[("location", "weights.bin"), ("offset", "8")], &[3]).is_err());
+        assert!(load(&[("location", "missing.bin")], &[3]).is_err());
+        assert!(load(&[("offset", "4")], &[3]).is_err());
+    }
+
+    #[test]
+    fn external_location_must_stay_in_model_dir() {
+        let escape = load(&[("location", "../external/weights.bin")], &[3]);
+        assert!(escape.unwrap_err().to_string().contains("outside the model directory"));
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/models/external");
+        let absolute = dir.join("weights.bin");
+        let absolute = load(&[("location", absolute.to_str().unwrap())], &[3]);
+        assert!(absolute.unwrap_err().to_string().contains("outside the model directory"));
+    }
+
+    #[test]
+    fn external_files_are_mapped_once() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/models/external");
+        let mut maps = HashMap::new();
+        for offset in &["4", "8"] {
+            let t = external(&[("location", "weights.bin"), ("offset", offset)], &[2]);
+            assert!(load_external(&t, &dir, &mut maps).unwrap().is_mapped());
+        }
+        assert_eq!(maps.len(), 1);
+    }
+
+    #[test]
+    fn type_round_trip() {
+        let fact = TensorFact::dt_shape(DatumType::F32, shapefact!(1, _, 3));
+        let proto = TypeProto_Tensor::try_from(&fact).unwrap();
+        assert_eq!(TensorFact::try_from(&proto).unwrap(), fact);
+        let fact = TensorFact::default();
+        let proto = TypeProto_Tensor::try_from(&fact).unwrap();
+        assert_eq!(TensorFact::try_from(&proto).unwrap(), fact);
+    }
+}
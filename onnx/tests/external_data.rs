@@ -0,0 +1,29 @@
+extern crate tract_core;
+extern crate tract_onnx;
+
+use std::path::{Path, PathBuf};
+use tract_core::prelude::*;
+
+fn model_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/models/external/add.onnx")
+}
+
+fn check(model: InferenceModel) {
+    let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+    let y = plan.run(tvec!(tensor1(&[1f32, 1.0, 1.0]))).unwrap();
+    assert_eq!(*y[0], tensor1(&[2f32, 3.0, 4.0]));
+}
+
+#[test]
+fn model_for_path_loads_external_data() {
+    check(tract_onnx::onnx().model_for_path(model_path()).unwrap());
+}
+
+#[test]
+fn external_data_is_resolved_relative_to_base_dir() {
+    let onnx = tract_onnx::onnx();
+    let proto = onnx.proto_model_for_path(model_path()).unwrap();
+    assert!(onnx.model_for_proto_model(&proto).is_err());
+    let dir = model_path().parent().unwrap().to_path_buf();
+    check(onnx.model_for_proto_model_with_base_dir(&proto, dir).unwrap());
+}
@@ -0,0 +1,59 @@
+"""Build the tract Python package, bundling the tract-ffi library.
+
+A wheel for the current platform is built from this directory with:
+
+    pip wheel .
+"""
+
+import os
+import shutil
+import subprocess
+import sys
+
+from setuptools import setup
+from setuptools.command.build_py import build_py
+from setuptools.dist import Distribution
+
+ROOT = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+
+
+def library_name():
+    if sys.platform == "darwin":
+        return "libtract_ffi.dylib"
+    if sys.platform == "win32":
+        return "tract_ffi.dll"
+    return "libtract_ffi.so"
+
+
+class BuildPyWithLibrary(build_py):
+    """Build tract-ffi in release mode, and ship it in the package."""
+
+    def run(self):
+        subprocess.check_call(["cargo", "build", "--release", "-p", "tract-ffi"], cwd=ROOT)
+        build_py.run(self)
+        target = os.environ.get("CARGO_TARGET_DIR", os.path.join(ROOT, "target"))
+        shutil.copy(os.path.join(target, "release", library_name()),
+                    os.path.join(self.build_lib, "tract"))
+
+
+class BinaryDistribution(Distribution):
+    """The native library makes the wheel platform specific."""
+
+    def has_ext_modules(self):
+        return True
+
+
+setup(
+    name="tract",
+    version="0.3.4.dev0",
+    description="Tiny, no-nonsense, self contained, TensorFlow and ONNX inference",
+    url="https://github.com/snipsco/tract",
+    author="Mathieu Poumeyrol",
+    author_email="kali@zoy.org",
+    license="MIT/Apache-2.0",
+    packages=["tract"],
+    install_requires=["numpy"],
+    cmdclass={"build_py": BuildPyWithLibrary},
+    distclass=BinaryDistribution,
+    zip_safe=False,
+)
@@ -0,0 +1,48 @@
+"""Run with the package installed, or with TRACT_FFI_LIBRARY pointing to a
+tract-ffi build, from the python directory:
+
+    python -m unittest discover tests
+"""
+
+import os
+import unittest
+
+import numpy
+
+import tract
+
+PLUS3 = os.path.join(os.path.dirname(os.path.abspath(__file__)),
+                     "..", "..", "tensorflow", "tests", "models", "plus3.pb")
+
+
+class Plus3(unittest.TestCase):
+    def plan(self, model):
+        model.set_input_fact(0, numpy.float32, [3])
+        return tract.SimplePlan(model.into_optimized())
+
+    def test_run(self):
+        plan = self.plan(tract.tensorflow().model_for_path(PLUS3))
+        [output] = plan.run([numpy.array([1.0, 2.5, 5.0], dtype=numpy.float32)])
+        self.assertEqual(output.dtype, numpy.float32)
+        self.assertEqual(output.tolist(), [4.0, 5.5, 8.0])
+
+    def test_run_from_bytes(self):
+        with open(PLUS3, "rb") as f:
+            plan = self.plan(tract.tensorflow().model_for_bytes(f.read()))
+        [output] = plan.run([numpy.zeros(3, dtype=numpy.float32)])
+        self.assertEqual(output.tolist(), [3.0, 3.0, 3.0])
+
+    def test_errors(self):
+        with self.assertRaisesRegex(tract.TractError, "does-not-exist.pb"):
+            tract.onnx().model_for_path("does-not-exist.pb")
+        plan = self.plan(tract.tensorflow().model_for_path(PLUS3))
+        with self.assertRaises(tract.TractError):
+            plan.run([])
+        with self.assertRaisesRegex(tract.TractError, "expected 3xF32, got 4xF32"):
+            plan.run([numpy.zeros(4, dtype=numpy.float32)])
+        with self.assertRaisesRegex(tract.TractError, "Impossible to unify F32 with I32"):
+            plan.run([numpy.zeros(3, dtype=numpy.int32)])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,236 @@
+"""Python bindings for tract, over the tract-ffi C API.
+
+    import numpy
+    import tract
+
+    model = tract.tensorflow().model_for_path("plus3.pb")
+    model.set_input_fact(0, numpy.float32, [3])
+    plan = tract.SimplePlan(model.into_optimized())
+    [output] = plan.run([numpy.array([1.0, 2.5, 5.0], dtype=numpy.float32)])
+
+The tract-ffi library is bundled in the package. The TRACT_FFI_LIBRARY
+environment variable can point to another build of it.
+"""
+
+import ctypes
+import os
+import sys
+
+import numpy
+
+__all__ = [
+    "TractError",
+    "onnx",
+    "tensorflow",
+    "Framework",
+    "InferenceModel",
+    "TypedModel",
+    "SimplePlan",
+]
+
+
+def _library_name():
+    if sys.platform == "darwin":
+        return "libtract_ffi.dylib"
+    if sys.platform == "win32":
+        return "tract_ffi.dll"
+    return "libtract_ffi.so"
+
+
+_lib = ctypes.CDLL(
+    os.environ.get("TRACT_FFI_LIBRARY")
+    or os.path.join(os.path.dirname(os.path.abspath(__file__)), _library_name())
+)
+
+_handle = ctypes.c_void_p
+_handle_p = ctypes.POINTER(ctypes.c_void_p)
+_size = ctypes.c_size_t
+_size_p = ctypes.POINTER(ctypes.c_size_t)
+
+for _name, _args in [
+    ("tract_onnx_model_for_path", [ctypes.c_char_p, _handle_p]),
+    ("tract_onnx_model_for_buffer", [ctypes.c_char_p, _size, _handle_p]),
+    ("tract_tensorflow_model_for_path", [ctypes.c_char_p, _handle_p]),
+    ("tract_tensorflow_model_for_buffer", [ctypes.c_char_p, _size, _handle_p]),
+    ("tract_inference_model_set_input_fact", [_handle, _size, ctypes.c_uint32, _size, _size_p]),
+    ("tract_inference_model_into_optimized", [_handle_p, _handle_p]),
+    ("tract_inference_model_destroy", [_handle_p]),
+    ("tract_model_input_count", [_handle, _size_p]),
+    ("tract_model_output_count", [_handle, _size_p]),
+    ("tract_model_plan", [_handle, _handle_p]),
+    ("tract_model_destroy", [_handle_p]),
+    ("tract_plan_run", [_handle, _handle_p, _handle_p]),
+    ("tract_plan_destroy", [_handle_p]),
+    ("tract_tensor_create", [ctypes.c_uint32, _size, _size_p, _handle, _handle_p]),
+    ("tract_tensor_inspect", [_handle, ctypes.POINTER(ctypes.c_int), _size_p,
+                              ctypes.POINTER(_size_p), _handle_p]),
+    ("tract_tensor_destroy", [_handle_p]),
+]:
+    getattr(_lib, _name).argtypes = _args
+    getattr(_lib, _name).restype = ctypes.c_int
+_lib.tract_get_last_error.argtypes = []
+_lib.tract_get_last_error.restype = ctypes.c_char_p
+
+# In TractDatumType order, starting at 1.
+_DATUM_TYPES = [numpy.dtype(t) for t in [
+    numpy.bool_,
+    numpy.uint8,
+    numpy.uint16,
+    numpy.int8,
+    numpy.int16,
+    numpy.int32,
+    numpy.int64,
+    numpy.float16,
+    numpy.float32,
+    numpy.float64,
+]]
+
+
+class TractError(Exception):
+    """Error reported by tract."""
+
+
+def _check(result):
+    if result != 0:
+        raise TractError(_lib.tract_get_last_error().decode("utf-8", "replace"))
+
+
+def _datum_type(dtype):
+    dtype = numpy.dtype(dtype)
+    if dtype not in _DATUM_TYPES:
+        raise TractError("Unsupported dtype {}".format(dtype))
+    return _DATUM_TYPES.index(dtype) + 1
+
+
+def _shape(shape):
+    shape = [int(d) for d in shape]
+    return len(shape), (_size * len(shape))(*shape)
+
+
+class _Tensor:
+    def __init__(self, handle):
+        self._handle = handle
+
+    def __del__(self):
+        _lib.tract_tensor_destroy(ctypes.byref(self._handle))
+
+    @staticmethod
+    def from_array(array):
+        rank, shape = _shape(array.shape)
+        handle = _handle()
+        _check(_lib.tract_tensor_create(_datum_type(array.dtype), rank, shape,
+                                        array.ctypes.data, ctypes.byref(handle)))
+        return _Tensor(handle)
+
+    def to_array(self):
+        datum_type = ctypes.c_int()
+        rank = _size()
+        shape = _size_p()
+        data = _handle()
+        _check(_lib.tract_tensor_inspect(self._handle, ctypes.byref(datum_type),
+                                         ctypes.byref(rank), ctypes.byref(shape),
+                                         ctypes.byref(data)))
+        array = numpy.empty([shape[i] for i in range(rank.value)],
+                            dtype=_DATUM_TYPES[datum_type.value - 1])
+        ctypes.memmove(array.ctypes.data, data, array.nbytes)
+        return array
+
+
+class Framework:
+    """Model loader for "onnx" or "tensorflow"."""
+
+    def __init__(self, name):
+        self._name = name
+
+    def model_for_path(self, path):
+        """Load a model from a file."""
+        model = _handle()
+        load = getattr(_lib, "tract_{}_model_for_path".format(self._name))
+        _check(load(os.fsencode(path), ctypes.byref(model)))
+        return InferenceModel(model)
+
+    def model_for_bytes(self, data):
+        """Load a model from its serialized form."""
+        data = bytes(data)
+        model = _handle()
+        load = getattr(_lib, "tract_{}_model_for_buffer".format(self._name))
+        _check(load(data, len(data), ctypes.byref(model)))
+        return InferenceModel(model)
+
+
+def onnx():
+    """The ONNX model loader."""
+    return Framework("onnx")
+
+
+def tensorflow():
+    """The TensorFlow model loader."""
+    return Framework("tensorflow")
+
+
+class InferenceModel:
+    """A model as loaded from TensorFlow or ONNX, before optimization."""
+
+    def __init__(self, handle):
+        self._handle = handle
+
+    def __del__(self):
+        _lib.tract_inference_model_destroy(ctypes.byref(self._handle))
+
+    def set_input_fact(self, input, dtype, shape):
+        """Set the element type and shape of the input-th model input."""
+        rank, shape = _shape(shape)
+        _check(_lib.tract_inference_model_set_input_fact(self._handle, input,
+                                                         _datum_type(dtype), rank, shape))
+
+    def into_optimized(self):
+        """Optimize the model. It can not be used afterwards."""
+        if not self._handle:
+            raise TractError("Model has already been optimized")
+        optimized = _handle()
+        _check(_lib.tract_inference_model_into_optimized(ctypes.byref(self._handle),
+                                                         ctypes.byref(optimized)))
+        return TypedModel(optimized)
+
+
+class TypedModel:
+    """An optimized model."""
+
+    def __init__(self, handle):
+        self._handle = handle
+
+    def __del__(self):
+        _lib.tract_model_destroy(ctypes.byref(self._handle))
+
+    def input_count(self):
+        count = _size()
+        _check(_lib.tract_model_input_count(self._handle, ctypes.byref(count)))
+        return count.value
+
+    def output_count(self):
+        count = _size()
+        _check(_lib.tract_model_output_count(self._handle, ctypes.byref(count)))
+        return count.value
+
+
+class SimplePlan:
+    """An execution plan for an optimized model."""
+
+    def __init__(self, model):
+        self._handle = _handle()
+        _check(_lib.tract_model_plan(model._handle, ctypes.byref(self._handle)))
+        self._input_count = model.input_count()
+        self._output_count = model.output_count()
+
+    def __del__(self):
+        _lib.tract_plan_destroy(ctypes.byref(self._handle))
+
+    def run(self, inputs):
+        """Run the model on a list of arrays, returning the list of outputs."""
+        if len(inputs) != self._input_count:
+            raise TractError("Expected {} inputs, got {}".format(self._input_count, len(inputs)))
+        tensors = [_Tensor.from_array(numpy.ascontiguousarray(i)) for i in inputs]
+        handles = (_handle * len(tensors))(*[t._handle.value for t in tensors])
+        outputs = (_handle * self._output_count)()
+        _check(_lib.tract_plan_run(self._handle, handles, outputs))
+        return [_Tensor(_handle(o)).to_array() for o in outputs]
@@ -47,6 +47,7 @@ extern crate num_traits;
 extern crate protobuf;
 #[macro_use]
 extern crate tract_core;
+extern crate tract_linalg;
 #[cfg(feature = "conform")]
 extern crate tensorflow;
 
@@ -55,6 +56,7 @@ pub mod conform;
 
 pub mod model;
 pub mod ops;
+pub mod saved_model;
 pub mod tensor;
 pub mod tfpb;
 
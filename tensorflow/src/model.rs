@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::tfpb::graph::GraphDef;
 use crate::tfpb::node_def::NodeDef;
 use tract_core::internal::*;
@@ -14,7 +16,7 @@ impl Tensorflow {
     // "src_output" indicating which output tensor to use from "node". If
     // "src_output" is 0 the ":0" suffix can be omitted. Regular inputs may
     // optionally be followed by control inputs that have the format "^node".
-    fn parse_input(i: &str) -> TractResult<(&str, usize)> {
+    pub(crate) fn parse_input(i: &str) -> TractResult<(&str, usize)> {
         let pair = if i.starts_with("^") {
             (&i[1..], 0)
         } else {
@@ -23,21 +25,19 @@ impl Tensorflow {
         };
         Ok(pair)
     }
-}
 
-impl Framework<NodeDef, GraphDef> for Tensorflow {
-    fn op_builder_for_name(&self, name: &str) -> Option<&OpBuilder<NodeDef>> {
-        self.op_register.get(name)
-    }
-    fn proto_model_for_read(&self, r: &mut std::io::Read) -> TractResult<GraphDef> {
-        Ok(::protobuf::parse_from_reader::<GraphDef>(r).map_err(|e| format!("{:?}", e))?)
-    }
+    fn model_for_nodes(&self, nodes: &[NodeDef]) -> TractResult<InferenceModel> {
+        use crate::ops::control_flow::loop_frames;
+        use tract_core::ops::control_flow::Loop;
 
-    fn model_for_proto_model(&self, graph: &GraphDef) -> TractResult<InferenceModel> {
         let mut model = InferenceModel::default();
+        let frames = loop_frames(nodes)?;
+        let in_loop: HashSet<&str> =
+            frames.iter().flat_map(|f| f.members.iter().map(|s| &**s)).collect();
+
         // compute min output arity for all nodes
         let mut arities = HashMap::new();
-        for pbnode in graph.get_node().iter() {
+        for pbnode in nodes.iter() {
             for i in pbnode.get_input().iter() {
                 let (node, slot) = Self::parse_input(i)?;
                 let arity = arities.entry(node).or_insert(1);
@@ -45,8 +45,11 @@ impl Framework<NodeDef, GraphDef> for Tensorflow {
             }
         }
 
-        for pbnode in graph.get_node().iter() {
+        for pbnode in nodes.iter() {
             let name = pbnode.get_name().to_string();
+            if in_loop.contains(&*name) {
+                continue;
+            }
             let output_arity = arities.get(&*name).cloned().unwrap_or(1);
             let facts = tvec!(TensorFact::default(); output_arity);
             let node_id = model.add_node(
@@ -66,16 +69,94 @@ impl Framework<NodeDef, GraphDef> for Tensorflow {
             }
         }
 
-        for (node_id, pbnode) in graph.get_node().iter().enumerate() {
-            for (ix, i) in pbnode.get_input().iter().enumerate() {
-                let input = Self::parse_input(i)?;
-                let prec = model.node_by_name(input.0)?.id;
-                let outlet = OutletId::new(prec, input.1);
-                let inlet = InletId::new(node_id, ix);
-                model.add_edge(outlet, inlet)?;
+        // while loop frames are replaced by a Loop node, the Switch outputs
+        // consumed by Exit nodes are redirected to the Loop outputs
+        let mut redirects = HashMap::new();
+        let mut loop_inputs = vec![];
+        for frame in &frames {
+            let mut body = self
+                .model_for_nodes(&frame.body_nodes(nodes)?)
+                .map_err(|e| format!("In loop {}, {}", frame.name, e.description()))?;
+            for (ix, enter) in
+                frame.states.iter().map(|s| &s.enter).chain(frame.invariants.iter()).enumerate()
+            {
+                let enter = nodes.iter().find(|n| n.get_name() == enter).unwrap();
+                if let Some(dt) = enter.get_attr_opt_datum_type("T")? {
+                    body.set_input_fact(ix, TensorFact::dt(dt))?;
+                }
             }
+            let outputs = frame
+                .body_outputs(nodes)?
+                .iter()
+                .map(|o| {
+                    let (node, slot) = Self::parse_input(o)?;
+                    Ok(OutletId::new(body.node_by_name(node)?.id, slot))
+                })
+                .collect::<TractResult<Vec<_>>>()?;
+            body.set_output_outlets(&outputs)?;
+            let id = model.add_node(
+                &*frame.name,
+                Loop::new(Arc::new(body), frame.states.len()),
+                tvec!(TensorFact::default(); frame.states.len()),
+            )?;
+            for (ix, state) in frame.states.iter().enumerate() {
+                redirects.insert((&*state.switch, 0), OutletId::new(id, ix));
+            }
+            loop_inputs.push((id, frame.inputs(nodes)?));
         }
 
+        let outlet = |model: &InferenceModel, input: &str| -> TractResult<Option<OutletId>> {
+            let (node, slot) = Self::parse_input(input)?;
+            if let Some(outlet) = redirects.get(&(node, slot)) {
+                Ok(Some(*outlet))
+            } else if !in_loop.contains(node) {
+                Ok(Some(OutletId::new(model.node_by_name(node)?.id, slot)))
+            } else if input.starts_with("^") {
+                Ok(None)
+            } else {
+                bail!("{} is used outside of its loop frame", input)
+            }
+        };
+
+        for pbnode in nodes.iter() {
+            if in_loop.contains(pbnode.get_name()) {
+                continue;
+            }
+            let node_id = model.node_by_name(pbnode.get_name())?.id;
+            let mut ix = 0;
+            for i in pbnode.get_input().iter() {
+                if let Some(prec) = outlet(&model, i)? {
+                    model.add_edge(prec, InletId::new(node_id, ix))?;
+                    ix += 1;
+                }
+            }
+        }
+
+        for (id, inputs) in loop_inputs {
+            for (ix, i) in inputs.iter().enumerate() {
+                let prec =
+                    outlet(&model, i)?.ok_or_else(|| format!("Unexpected loop input {}", i))?;
+                model.add_edge(prec, InletId::new(id, ix))?;
+            }
+        }
+
+        Ok(model)
+    }
+}
+
+impl Framework<NodeDef, GraphDef> for Tensorflow {
+    fn op_builder_for_name(&self, name: &str) -> Option<&OpBuilder<NodeDef>> {
+        self.op_register.get(name)
+    }
+    fn proto_model_for_read(&self, r: &mut std::io::Read) -> TractResult<GraphDef> {
+        Ok(::protobuf::parse_from_reader::<GraphDef>(r).map_err(|e| format!("{:?}", e))?)
+    }
+
+    fn model_for_proto_model(&self, graph: &GraphDef) -> TractResult<InferenceModel> {
+        let annotated = crate::ops::tensor_array::with_element_shapes(graph.get_node())?;
+        let nodes = annotated.as_ref().map(|n| &**n).unwrap_or(graph.get_node());
+        let mut model = self.model_for_nodes(nodes)?;
+
         // variable -> assign rewire
         // in protobuf:
         //  * VariableV2 has a single output (a byref tensor)
@@ -0,0 +1,150 @@
+use tract_core::internal::*;
+use tract_core::ops::array::ScatterReduction;
+
+#[derive(Debug, Clone, new)]
+pub struct ScatterNd {
+    dt: DatumType,
+}
+
+pub fn scatter_nd(pb: &crate::tfpb::node_def::NodeDef) -> TractResult<Box<Op>> {
+    let dtype = pb.get_attr_datum_type("T")?;
+    Ok(Box::new(ScatterNd::new(dtype)))
+}
+
+pub fn tensor_scatter_update(_pb: &crate::tfpb::node_def::NodeDef) -> TractResult<Box<Op>> {
+    Ok(Box::new(tract_core::ops::array::ScatterNd::new(ScatterReduction::None)))
+}
+
+pub fn tensor_scatter_add(_pb: &crate::tfpb::node_def::NodeDef) -> TractResult<Box<Op>> {
+    Ok(Box::new(tract_core::ops::array::ScatterNd::new(ScatterReduction::Add)))
+}
+
+pub fn tensor_scatter_max(_pb: &crate::tfpb::node_def::NodeDef) -> TractResult<Box<Op>> {
+    Ok(Box::new(tract_core::ops::array::ScatterNd::new(ScatterReduction::Max)))
+}
+
+pub fn tensor_scatter_min(_pb: &crate::tfpb::node_def::NodeDef) -> TractResult<Box<Op>> {
+    Ok(Box::new(tract_core::ops::array::ScatterNd::new(ScatterReduction::Min)))
+}
+
+impl ScatterNd {
+    fn zeros_t<T: Datum>(shape: &[usize]) -> TractResult<Arc<Tensor>> {
+        Ok(::ndarray::ArrayD::<T>::default(shape).into_arc_tensor())
+    }
+}
+
+impl Op for ScatterNd {
+    fn name(&self) -> Cow<str> {
+        "tf.ScatterNd".into()
+    }
+}
+
+impl StatelessOp for ScatterNd {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (indices, updates, shape) = args_3!(inputs);
+        let shape = shape.cast_to::<i64>()?;
+        let shape = shape.as_slice::<i64>()?;
+        if shape.iter().any(|&d| d < 0) {
+            bail!("ScatterNd shape must be non-negative, got {:?}", shape)
+        }
+        let shape: TVec<usize> = shape.iter().map(|&d| d as usize).collect();
+        let zeros = dispatch_numbers!(Self::zeros_t(self.dt)(&shape))?;
+        tract_core::ops::array::ScatterNd::new(ScatterReduction::Add)
+            .eval(tvec!(zeros, indices, updates))
+    }
+}
+
+impl InferenceRulesOp for ScatterNd {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[1].datum_type, self.dt)?;
+        s.equals(&outputs[0].datum_type, self.dt)?;
+        s.equals(&inputs[2].rank, 1)?;
+        s.equals(outputs[0].rank.bex().to_dim(), &inputs[2].shape[0])?;
+        s.given(&outputs[0].rank, move |s, rank| {
+            for dim in 0..(rank as usize) {
+                s.equals(&outputs[0].shape[dim], inputs[2].value[dim].bex().to_dim())?;
+            }
+            Ok(())
+        })?;
+        // updates is indices.shape[..-1] + shape[indices.shape[-1]..]
+        s.given(&inputs[0].rank, move |s, indices_rank| {
+            if indices_rank < 1 {
+                bail!("ScatterNd indices must be at least of rank 1")
+            }
+            let indices_rank = indices_rank as usize;
+            for i in 0..(indices_rank - 1) {
+                s.equals(&inputs[1].shape[i], &inputs[0].shape[i])?;
+            }
+            s.given_2(&inputs[0].shape[indices_rank - 1], &outputs[0].rank, move |s, n, rank| {
+                if let Ok(n) = n.to_integer() {
+                    if n as i32 > rank {
+                        bail!("ScatterNd index tuples are longer than the output rank")
+                    }
+                    s.equals(&inputs[1].rank, (indices_rank as i32) - 1 + rank - n as i32)?;
+                    for i in 0..(rank - n as i32) as usize {
+                        s.equals(
+                            &inputs[1].shape[indices_rank - 1 + i],
+                            &outputs[0].shape[n as usize + i],
+                        )?;
+                    }
+                }
+                Ok(())
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // https://www.tensorflow.org/api_docs/python/tf/scatter_nd
+    #[test]
+    fn scatter_nd_vector() {
+        let op = ScatterNd::new(DatumType::I32);
+        assert_eq!(
+            op.eval(tvec!(
+                rctensor2(&[[4], [3], [1], [7]]),
+                rctensor1(&[9, 10, 11, 12]),
+                rctensor1(&[8])
+            ))
+            .unwrap(),
+            tvec!(rctensor1(&[0, 11, 0, 10, 9, 0, 0, 12]))
+        );
+    }
+
+    #[test]
+    fn scatter_nd_rejects_negative_shape() {
+        let op = ScatterNd::new(DatumType::I32);
+        assert!(op.eval(tvec!(rctensor2(&[[1]]), rctensor1(&[2]), rctensor1(&[-3]))).is_err());
+    }
+
+    #[test]
+    fn scatter_nd_infers_updates_shape() {
+        let op = ScatterNd::new(DatumType::I32);
+        let indices = TensorFact::dt_shape(DatumType::I32, shapefact!(5, 1));
+        let updates = TensorFact::dt_shape(DatumType::I32, shapefact!(..));
+        let shape = TensorFact::from(tensor1(&[4i32, 3, 2]));
+        let any = TensorFact::default();
+        let (inputs, outputs) =
+            op.infer_facts(tvec!(&indices, &updates, &shape), tvec!(&any)).unwrap();
+        assert_eq!(inputs[1].shape, shapefact!(5, 3, 2));
+        assert_eq!(outputs[0].shape, shapefact!(4, 3, 2));
+    }
+
+    #[test]
+    fn scatter_nd_accumulates_duplicates() {
+        let op = ScatterNd::new(DatumType::I32);
+        assert_eq!(
+            op.eval(tvec!(rctensor2(&[[1], [1]]), rctensor1(&[2, 3]), rctensor1(&[3]))).unwrap(),
+            tvec!(rctensor1(&[0, 5, 0]))
+        );
+    }
+}
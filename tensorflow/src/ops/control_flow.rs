@@ -0,0 +1,413 @@
+//! TensorFlow 1 while loops.
+//!
+//! `tf.while_loop` does not produce a single node, but a "frame": a subgraph
+//! delimited by `Enter` and `Exit` nodes, with a back edge for each loop
+//! variable:
+//!
+//! ```text
+//!  Enter -> Merge -> Switch -(false)-> Exit
+//!             ^        |(true)
+//!             |      Identity -> body ... -> NextIteration
+//!             +-------------------------------------+
+//! ```
+//!
+//! The `Switch` nodes are all controlled by a `LoopCond` node, computed from
+//! the `Merge` outputs. Loop invariants come in through `Enter` nodes with
+//! `is_constant` set and no `Merge`.
+//!
+//! The loader finds these frames and replaces each of them with a core `Loop`
+//! node whose body model is built from the nodes inside the frame. `Exit`
+//! nodes are kept as identities plugged on the loop outputs.
+
+use std::collections::HashSet;
+
+use tract_core::internal::*;
+
+use crate::model::{Tensorflow, TfOpRegister};
+use crate::tfpb::node_def::NodeDef;
+
+pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("Exit", |_| Ok(Box::new(tract_core::ops::identity::Identity::default())));
+}
+
+/// A loop-carried variable.
+#[derive(Debug, Clone)]
+pub struct LoopVar {
+    pub enter: String,
+    pub merge: String,
+    pub switch: String,
+    pub next_iteration: String,
+}
+
+/// An outermost while loop frame, and its structure.
+#[derive(Debug, Clone)]
+pub struct LoopFrame {
+    pub name: String,
+    /// All nodes inside the frame, including nested frames.
+    pub members: HashSet<String>,
+    pub states: Vec<LoopVar>,
+    /// Enter nodes for loop invariants.
+    pub invariants: Vec<String>,
+    pub loop_cond: String,
+}
+
+/// Find the outermost loop frames.
+pub fn loop_frames(nodes: &[NodeDef]) -> TractResult<Vec<LoopFrame>> {
+    if !nodes.iter().any(|n| n.get_op() == "Enter") {
+        return Ok(vec![]);
+    }
+    let paths = frame_paths(nodes)?;
+    let by_name: HashMap<&str, &NodeDef> = nodes.iter().map(|n| (n.get_name(), n)).collect();
+    let mut frames: Vec<LoopFrame> = vec![];
+    for (node, path) in nodes.iter().zip(paths.iter()) {
+        if path.is_empty() {
+            continue;
+        }
+        let ix = if let Some(ix) = frames.iter().position(|f| f.name == path[0]) {
+            ix
+        } else {
+            frames.push(LoopFrame {
+                name: path[0].clone(),
+                members: HashSet::new(),
+                states: vec![],
+                invariants: vec![],
+                loop_cond: String::new(),
+            });
+            frames.len() - 1
+        };
+        let frame = &mut frames[ix];
+        frame.members.insert(node.get_name().to_string());
+        if path.len() > 1 {
+            continue;
+        }
+        match node.get_op() {
+            "LoopCond" => {
+                if !frame.loop_cond.is_empty() {
+                    bail!("Loop frame {} has more than one LoopCond", frame.name)
+                }
+                frame.loop_cond = node.get_name().to_string();
+            }
+            "Enter" if node.get_attr_opt_bool("is_constant")?.unwrap_or(false) => {
+                frame.invariants.push(node.get_name().to_string())
+            }
+            "Enter" => {
+                let merge = nodes
+                    .iter()
+                    .find(|n| {
+                        n.get_op() == "Merge"
+                            && n.get_input().iter().any(|i| input_node(i) == node.get_name())
+                    })
+                    .ok_or_else(|| {
+                        format!("Enter {} is not followed by a Merge", node.get_name())
+                    })?;
+                let next_iteration = merge
+                    .get_input()
+                    .iter()
+                    .map(|i| input_node(i))
+                    .find(|i| {
+                        by_name.get(i).map(|n| n.get_op() == "NextIteration").unwrap_or(false)
+                    })
+                    .ok_or_else(|| format!("Merge {} has no NextIteration", merge.get_name()))?;
+                let switch = nodes
+                    .iter()
+                    .find(|n| {
+                        n.get_op() == "Switch"
+                            && n.get_input().get(0).map(|i| input_node(i)) == Some(merge.get_name())
+                    })
+                    .ok_or_else(|| {
+                        format!("Merge {} is not followed by a Switch", merge.get_name())
+                    })?;
+                frame.states.push(LoopVar {
+                    enter: node.get_name().to_string(),
+                    merge: merge.get_name().to_string(),
+                    switch: switch.get_name().to_string(),
+                    next_iteration: next_iteration.to_string(),
+                });
+            }
+            _ => (),
+        }
+    }
+    for frame in &frames {
+        if frame.loop_cond.is_empty() {
+            bail!("Loop frame {} has no LoopCond", frame.name)
+        }
+    }
+    Ok(frames)
+}
+
+fn input_node(input: &str) -> &str {
+    let input = input.trim_start_matches('^');
+    input.splitn(2, ':').next().unwrap()
+}
+
+/// Compute the stack of frame names each node belongs to.
+///
+/// An Enter output is in the frame it enters, an Exit output in the parent of
+/// the frame it leaves, other nodes are in the frame of their inputs (Merge
+/// ignoring its NextIteration back edge). Nodes without data inputs follow
+/// their control inputs.
+fn frame_paths(nodes: &[NodeDef]) -> TractResult<Vec<Vec<String>>> {
+    let ids: HashMap<&str, usize> =
+        nodes.iter().enumerate().map(|(ix, n)| (n.get_name(), ix)).collect();
+    let mut reference = vec![];
+    for node in nodes {
+        let data = node.get_input().iter().filter(|i| !i.starts_with("^"));
+        let control = node.get_input().iter().filter(|i| i.starts_with("^"));
+        let mut inputs = data.chain(control).map(|i| input_node(i));
+        let prec = if node.get_op() == "Merge" {
+            inputs
+                .find(|i| ids.get(i).map(|&p| nodes[p].get_op() != "NextIteration").unwrap_or(true))
+        } else {
+            inputs.next()
+        };
+        let prec = prec
+            .map(|p| ids.get(p).cloned().ok_or_else(|| format!("Node {} not found", p)))
+            .transpose()?;
+        reference.push(prec);
+    }
+    let mut paths: Vec<Option<Vec<String>>> = vec![None; nodes.len()];
+    let mut done = 0;
+    while done < nodes.len() {
+        let before = done;
+        for (ix, node) in nodes.iter().enumerate() {
+            if paths[ix].is_some() {
+                continue;
+            }
+            let mut path = match reference[ix] {
+                None => vec![],
+                Some(prec) => {
+                    if let Some(path) = &paths[prec] {
+                        path.clone()
+                    } else {
+                        continue;
+                    }
+                }
+            };
+            match node.get_op() {
+                "Enter" => path.push(node.get_attr_str("frame_name")?),
+                "Exit" => {
+                    if path.pop().is_none() {
+                        bail!("Exit {} is not in a loop frame", node.get_name())
+                    }
+                }
+                _ => (),
+            }
+            paths[ix] = Some(path);
+            done += 1;
+        }
+        if done == before {
+            bail!("Could not resolve loop frames (cycle not going through a Merge)")
+        }
+    }
+    Ok(paths.into_iter().map(|p| p.unwrap()).collect())
+}
+
+impl LoopFrame {
+    /// Outer node inputs feeding the loop: initial states, then invariants.
+    pub fn inputs(&self, nodes: &[NodeDef]) -> TractResult<Vec<String>> {
+        let by_name: HashMap<&str, &NodeDef> = nodes.iter().map(|n| (n.get_name(), n)).collect();
+        self.states
+            .iter()
+            .map(|s| &s.enter)
+            .chain(self.invariants.iter())
+            .map(|e| {
+                by_name[&**e]
+                    .get_input()
+                    .get(0)
+                    .cloned()
+                    .ok_or_else(|| format!("Enter {} has no input", e).into())
+            })
+            .collect()
+    }
+
+    /// Nodes of the body graph, with placeholders standing for the states and
+    /// the invariants.
+    ///
+    /// Control inputs are dropped: the body order is the data flow order.
+    pub fn body_nodes(&self, nodes: &[NodeDef]) -> TractResult<Vec<NodeDef>> {
+        let mut renames = HashMap::new();
+        let mut skipped = HashSet::new();
+        skipped.insert(self.loop_cond.clone());
+        for state in &self.states {
+            renames.insert(format!("{}:1", state.switch), state.merge.clone());
+            skipped.insert(state.enter.clone());
+            skipped.insert(state.merge.clone());
+            skipped.insert(state.switch.clone());
+            skipped.insert(state.next_iteration.clone());
+        }
+        let by_name: HashMap<&str, &NodeDef> = nodes.iter().map(|n| (n.get_name(), n)).collect();
+        let mut body = vec![];
+        for state in &self.states {
+            body.push(placeholder(&state.merge, by_name[&*state.enter]));
+        }
+        for invariant in &self.invariants {
+            body.push(placeholder(invariant, by_name[&**invariant]));
+            skipped.insert(invariant.clone());
+        }
+        for node in nodes {
+            if !self.members.contains(node.get_name()) || skipped.contains(node.get_name()) {
+                continue;
+            }
+            let mut node = node.clone();
+            let inputs = node
+                .get_input()
+                .iter()
+                .filter(|i| !i.starts_with("^"))
+                .map(|i| self.rename(i, &renames))
+                .collect::<TractResult<Vec<String>>>()?;
+            node.set_input(protobuf::RepeatedField::from_vec(inputs));
+            body.push(node);
+        }
+        Ok(body)
+    }
+
+    /// Body outputs: the condition, then the next value of each state.
+    pub fn body_outputs(&self, nodes: &[NodeDef]) -> TractResult<Vec<String>> {
+        let mut renames = HashMap::new();
+        for state in &self.states {
+            renames.insert(format!("{}:1", state.switch), state.merge.clone());
+        }
+        let by_name: HashMap<&str, &NodeDef> = nodes.iter().map(|n| (n.get_name(), n)).collect();
+        std::iter::once(&self.loop_cond)
+            .chain(self.states.iter().map(|s| &s.next_iteration))
+            .map(|n| self.rename(&by_name[&**n].get_input()[0], &renames))
+            .collect()
+    }
+
+    fn rename(&self, input: &str, renames: &HashMap<String, String>) -> TractResult<String> {
+        if let Some(renamed) = renames.get(input) {
+            return Ok(renamed.clone());
+        }
+        let (node, slot) = Tensorflow::parse_input(input)?;
+        if self.states.iter().any(|s| s.merge == node && slot != 0) {
+            bail!("Loop {}: use of Merge value_index ({}) is not supported", self.name, input)
+        }
+        if self.states.iter().any(|s| s.switch == node) {
+            bail!("Loop {}: unexpected use of {} in loop body", self.name, input)
+        }
+        Ok(input.to_string())
+    }
+}
+
+fn placeholder(name: &str, enter: &NodeDef) -> NodeDef {
+    let mut node = crate::tfpb::node().name(name).op("Placeholder");
+    if let Some(dt) = enter.get_attr().get("T") {
+        node.mut_attr().insert("dtype".to_string(), dt.clone());
+    }
+    node
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::tfpb;
+    use crate::tfpb::types::DataType::*;
+
+    pub(crate) fn konst(
+        name: &str,
+        t: tfpb::tensor::TensorProto,
+        dt: tfpb::types::DataType,
+    ) -> NodeDef {
+        tfpb::node().name(name).op("Const").attr("dtype", dt).attr("value", t)
+    }
+
+    pub(crate) fn enter(
+        name: &str,
+        input: &str,
+        dt: tfpb::types::DataType,
+        is_constant: bool,
+    ) -> NodeDef {
+        tfpb::node()
+            .name(name)
+            .op("Enter")
+            .input(input)
+            .attr("T", dt)
+            .attr("frame_name", "while/while_context")
+            .attr("is_constant", is_constant)
+    }
+
+    pub(crate) fn node(
+        name: &str,
+        op: &str,
+        inputs: &[&str],
+        dt: tfpb::types::DataType,
+    ) -> NodeDef {
+        let mut node = tfpb::node().name(name).op(op).attr("T", dt);
+        for i in inputs {
+            node = node.input(i);
+        }
+        node
+    }
+
+    // i, acc = 0, x; while i < n: i, acc = i + 1, acc * two
+    fn pow2_graph() -> tfpb::graph::GraphDef {
+        tfpb::graph()
+            .node(tfpb::node().name("x").op("Placeholder").attr("dtype", DT_FLOAT))
+            .node(konst("zero", tfpb::tensor_i32(vec![], vec![0]), DT_INT32))
+            .node(konst("n", tfpb::tensor_i32(vec![], vec![3]), DT_INT32))
+            .node(konst("two", tfpb::tensor_f32(vec![], vec![2.0]), DT_FLOAT))
+            .node(enter("while/Enter", "zero", DT_INT32, false))
+            .node(enter("while/Enter_1", "x", DT_FLOAT, false))
+            .node(enter("while/Enter_2", "n", DT_INT32, true))
+            .node(enter("while/Enter_3", "two", DT_FLOAT, true))
+            .node(
+                node("while/Merge", "Merge", &["while/Enter", "while/NextIteration"], DT_INT32)
+                    .attr("N", 2i64),
+            )
+            .node(
+                node(
+                    "while/Merge_1",
+                    "Merge",
+                    &["while/Enter_1", "while/NextIteration_1"],
+                    DT_FLOAT,
+                )
+                .attr("N", 2i64),
+            )
+            .node(node("while/Less", "Less", &["while/Merge", "while/Enter_2"], DT_INT32))
+            .node(tfpb::node().name("while/LoopCond").op("LoopCond").input("while/Less"))
+            .node(node("while/Switch", "Switch", &["while/Merge", "while/LoopCond"], DT_INT32))
+            .node(node("while/Switch_1", "Switch", &["while/Merge_1", "while/LoopCond"], DT_FLOAT))
+            .node(node("while/Identity", "Identity", &["while/Switch:1"], DT_INT32))
+            .node(node("while/Identity_1", "Identity", &["while/Switch_1:1"], DT_FLOAT))
+            .node(
+                konst("while/add/y", tfpb::tensor_i32(vec![], vec![1]), DT_INT32)
+                    .input("^while/Identity"),
+            )
+            .node(node("while/add", "Add", &["while/Identity", "while/add/y"], DT_INT32))
+            .node(node("while/mul", "Mul", &["while/Identity_1", "while/Enter_3"], DT_FLOAT))
+            .node(node("while/NextIteration", "NextIteration", &["while/add"], DT_INT32))
+            .node(node("while/NextIteration_1", "NextIteration", &["while/mul"], DT_FLOAT))
+            .node(node("while/Exit", "Exit", &["while/Switch"], DT_INT32))
+            .node(node("while/Exit_1", "Exit", &["while/Switch_1"], DT_FLOAT))
+    }
+
+    #[test]
+    fn frame_structure() {
+        let graph = pow2_graph();
+        let frames = loop_frames(graph.get_node()).unwrap();
+        assert_eq!(frames.len(), 1);
+        let frame = &frames[0];
+        assert_eq!(frame.name, "while/while_context");
+        assert_eq!(frame.loop_cond, "while/LoopCond");
+        assert_eq!(frame.states.len(), 2);
+        assert_eq!(frame.states[1].switch, "while/Switch_1");
+        assert_eq!(frame.invariants, vec!("while/Enter_2", "while/Enter_3"));
+        assert!(frame.members.contains("while/add/y"));
+        assert!(!frame.members.contains("while/Exit"));
+        assert_eq!(frame.inputs(graph.get_node()).unwrap(), vec!("zero", "x", "n", "two"));
+        assert_eq!(
+            frame.body_outputs(graph.get_node()).unwrap(),
+            vec!("while/Less", "while/add", "while/mul")
+        );
+    }
+
+    #[test]
+    fn run_while_loop() {
+        let mut model = crate::tensorflow().model_for_proto_model(&pow2_graph()).unwrap();
+        model.set_output_names(&["while/Exit", "while/Exit_1"]).unwrap();
+        model.set_input_fact(0, TensorFact::dt_shape(DatumType::F32, shapefact!(2))).unwrap();
+        let model = model.into_optimized().unwrap();
+        let outputs = SimplePlan::new(&model).unwrap().run(tvec!(tensor1(&[1.0f32, 0.5]))).unwrap();
+        assert_eq!(outputs, tvec!(rctensor0(3i32), rctensor1(&[8.0f32, 4.0])));
+    }
+}
@@ -4,28 +4,34 @@ use tract_core::ops as tractops;
 use crate::model::TfOpRegister;
 use crate::tfpb::node_def::NodeDef;
 
-mod max;
+mod reduce;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Abs", with_T!(tractops::math::Abs));
     reg.insert("Add", with_T!(tractops::math::Add::Bin));
     reg.insert("AddN", add_n);
+    reg.insert("All", reduce::all);
+    reg.insert("Any", reduce::any);
     reg.insert("BiasAdd", with_T!(tractops::math::Add::Bin));
     reg.insert("Ceil", with_T!(tractops::math::Ceil));
     reg.insert("Div", with_T!(tractops::math::Div::Bin));
     reg.insert("FloorMod", with_T!(tractops::math::Rem::Bin));
     reg.insert("MatMul", mat_mul);
-    reg.insert("Max", max::max);
+    reg.insert("Max", reduce::max);
     reg.insert("Maximum", with_T!(tractops::math::Max::Bin));
+    reg.insert("Mean", reduce::mean);
+    reg.insert("Min", reduce::min);
     reg.insert("Minimum", with_T!(tractops::math::Min::Bin));
     reg.insert("Less", with_T!(tractops::logic::Lesser::Bin));
     reg.insert("Log", with_T!(tractops::math::Ln));
     reg.insert("Mul", with_T!(tractops::math::Mul::Bin));
     reg.insert("Pow", with_T!(tractops::math::Pow::Bin));
     reg.insert("Neg", with_T!(tractops::math::Neg));
+    reg.insert("Prod", reduce::prod);
     reg.insert("RealDiv", with_T!(tractops::math::Div::Bin));
     reg.insert("Rsqrt", with_T!(tractops::math::Rsqrt));
     reg.insert("Sub", with_T!(tractops::math::Sub::Bin));
+    reg.insert("Sum", reduce::sum);
     reg.insert("Tanh", with_T!(tractops::math::Tanh));
 }
 
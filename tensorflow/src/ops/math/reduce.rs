@@ -0,0 +1,199 @@
+use tract_core::internal::*;
+use tract_core::ops::nn::{Reduce, Reducer};
+
+use crate::tfpb::node_def::NodeDef;
+
+/// TensorFlow reductions, taking the axes to reduce as a second input.
+///
+/// Once the axes are known to be constant, the op is decluttered to a core
+/// unary `Reduce`.
+#[derive(Debug, Clone, new)]
+pub struct TfReduce {
+    keep_dims: bool,
+    reducer: Reducer,
+}
+
+pub fn all(pb: &NodeDef) -> TractResult<Box<Op>> {
+    build(pb, Reducer::All)
+}
+
+pub fn any(pb: &NodeDef) -> TractResult<Box<Op>> {
+    build(pb, Reducer::Any)
+}
+
+pub fn max(pb: &NodeDef) -> TractResult<Box<Op>> {
+    build(pb, Reducer::Max)
+}
+
+pub fn mean(pb: &NodeDef) -> TractResult<Box<Op>> {
+    build(pb, Reducer::Mean)
+}
+
+pub fn min(pb: &NodeDef) -> TractResult<Box<Op>> {
+    build(pb, Reducer::Min)
+}
+
+pub fn prod(pb: &NodeDef) -> TractResult<Box<Op>> {
+    build(pb, Reducer::Prod)
+}
+
+pub fn sum(pb: &NodeDef) -> TractResult<Box<Op>> {
+    build(pb, Reducer::Sum)
+}
+
+fn build(pb: &NodeDef, reducer: Reducer) -> TractResult<Box<Op>> {
+    let keep_dims = pb.get_attr_opt_bool("keep_dims")?.unwrap_or(false);
+    Ok(Box::new(TfReduce::new(keep_dims, reducer)))
+}
+
+impl TfReduce {
+    fn core_op(&self, axes: &Tensor, rank: usize) -> TractResult<Reduce> {
+        Ok(Reduce::new(Some(resolve_axes(axes, rank)?), self.keep_dims, self.reducer))
+    }
+}
+
+/// Check the axes against the input rank, and make them positive.
+fn resolve_axes(axes: &Tensor, rank: usize) -> TractResult<Vec<i64>> {
+    let axes = axes.cast_to::<i64>()?;
+    axes.as_slice::<i64>()?
+        .iter()
+        .map(|&ax| {
+            let rank = rank as i64;
+            if ax < -rank || ax >= rank {
+                bail!("Reduction axis {} is out of range for rank {}", ax, rank)
+            }
+            Ok(if ax < 0 { ax + rank } else { ax })
+        })
+        .collect()
+}
+
+impl Op for TfReduce {
+    fn name(&self) -> Cow<str> {
+        format!("tf.Reduce<{:?}>", self.reducer).into()
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let mut inputs = model.node_input_facts(node.id)?;
+        let (input, axes) = args_2!(inputs);
+        if let Some(ref axes) = axes.konst {
+            let op = self.core_op(axes, input.shape.rank())?;
+            return Ok(Some(TypedModelPatch::single_unary_op(model, node, op)?));
+        }
+        Ok(None)
+    }
+}
+
+impl StatelessOp for TfReduce {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, axes) = args_2!(inputs);
+        self.core_op(&axes, input.shape().len())?.eval(tvec!(input))
+    }
+}
+
+impl InferenceRulesOp for TfReduce {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, &inputs[0].datum_type)?;
+        s.given(&inputs[1].rank, move |_, rank| {
+            if rank > 1 {
+                bail!("Reduction axes must be a scalar or a vector")
+            }
+            Ok(())
+        })?;
+        if self.keep_dims {
+            s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        }
+        s.given_2(&inputs[0].rank, &inputs[1].value, move |s, irank, axes| {
+            let axes: TVec<usize> =
+                resolve_axes(&axes, irank as usize)?.into_iter().map(|ax| ax as usize).collect();
+            let mut od = 0;
+            for id in 0..(irank as usize) {
+                if axes.contains(&id) {
+                    if self.keep_dims {
+                        s.equals(&outputs[0].shape[od], 1.to_dim())?;
+                        od += 1;
+                    }
+                } else {
+                    s.equals(&outputs[0].shape[od], &inputs[0].shape[id])?;
+                    od += 1;
+                }
+            }
+            s.equals(&outputs[0].rank, od as i32)?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum_keep_dims() {
+        let op = TfReduce::new(true, Reducer::Sum);
+        assert_eq!(
+            op.eval(tvec!(rctensor2(&[[1.0f32, 2.0], [3.0, 4.0]]), rctensor1(&[1]))).unwrap(),
+            tvec!(rctensor2(&[[3.0f32], [7.0]]))
+        );
+    }
+
+    #[test]
+    fn mean_negative_axis() {
+        let op = TfReduce::new(false, Reducer::Mean);
+        assert_eq!(
+            op.eval(tvec!(rctensor2(&[[1.0f32, 2.0], [3.0, 4.0]]), rctensor1(&[-2]))).unwrap(),
+            tvec!(rctensor1(&[2.0f32, 3.0]))
+        );
+    }
+
+    #[test]
+    fn any_scalar_axis() {
+        let op = TfReduce::new(false, Reducer::Any);
+        assert_eq!(
+            op.eval(tvec!(rctensor2(&[[true, false], [false, false]]), rctensor0(1))).unwrap(),
+            tvec!(rctensor1(&[true, false]))
+        );
+    }
+
+    #[test]
+    fn out_of_range_axis() {
+        let op = TfReduce::new(false, Reducer::Sum);
+        let input = rctensor2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+        assert!(op.eval(tvec!(input.clone(), rctensor1(&[2]))).is_err());
+        assert!(op.eval(tvec!(input, rctensor1(&[-3]))).is_err());
+        let input = TensorFact::dt_shape(f32::datum_type(), shapefact!(2, 2));
+        let axes = TensorFact::from(tensor1(&[2]));
+        let any = TensorFact::default();
+        assert!(op.infer_facts(tvec!(&input, &axes), tvec!(&any)).is_err());
+    }
+
+    #[test]
+    fn declutter_to_core_reduce() {
+        let mut model = InferenceModel::default();
+        let input = model
+            .add_source("input", TensorFact::dt_shape(f32::datum_type(), shapefact!(2, 3)))
+            .unwrap();
+        let axes = model.add_const("axes", tensor1(&[0i32])).unwrap();
+        let reduce = model.add_node_default("reduce", TfReduce::new(false, Reducer::Prod)).unwrap();
+        model.add_edge(OutletId::new(input, 0), InletId::new(reduce, 0)).unwrap();
+        model.add_edge(OutletId::new(axes, 0), InletId::new(reduce, 1)).unwrap();
+        let model = model.into_typed().unwrap().declutter().unwrap();
+        assert!(model.node_by_name("reduce").unwrap().op_is::<Reduce>());
+        let output = SimplePlan::new(&model)
+            .unwrap()
+            .run(tvec!(tensor2(&[[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]])))
+            .unwrap();
+        assert_eq!(output, tvec!(rctensor1(&[4.0f32, 10.0, 18.0])));
+    }
+}
@@ -7,20 +7,24 @@ use crate::tfpb::node_def::NodeDef;
 mod macros;
 
 pub mod array;
+pub mod control_flow;
 pub mod logic;
 pub mod math;
 pub mod nn;
 pub mod quant;
 pub mod rec;
+pub mod tensor_array;
 pub mod vars;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     array::register_all_ops(reg);
+    control_flow::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
     nn::register_all_ops(reg);
     quant::register_all_ops(reg);
     rec::register_all_ops(reg);
+    tensor_array::register_all_ops(reg);
     vars::register_all_ops(reg);
     reg.insert("Cast", cast);
     reg.insert("Const", konst);
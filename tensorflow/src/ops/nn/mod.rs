@@ -17,6 +17,7 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("DepthwiseConv2dNative", dw_conv2d::depthwise_conv2d);
     reg.insert("FusedBatchNorm", fused_batch_norm::fused_batch_norm);
     reg.insert("MaxPool", pools::maxpool);
+    reg.insert("MaxPoolWithArgmax", pools::maxpool_with_argmax);
     reg.insert("Relu", with_T!(::tract_core::ops::nn::Relu));
     reg.insert("Relu6", |_| Ok(Box::new(Relu6::default())));
     reg.insert("Sigmoid", with_T!(::tract_core::ops::nn::Sigmoid));
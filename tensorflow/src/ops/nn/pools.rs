@@ -3,35 +3,29 @@ use tract_core::internal::*;
 use tract_core::ops::cnn::*;
 
 pub fn avgpool(pb: &NodeDef) -> TractResult<Box<Op>> {
-    let ksize: Vec<usize> = pb.get_attr_list_int("ksize")?;
-    let data_format = super::data_format(pb)?;
-    let kshape = data_format.shape(ksize);
-    let strides = super::strides(pb)?;
-    let padding = super::padding(pb)?;
-    Ok(Box::new(AvgPool::new(
-        PoolSpec::new(
-            data_format,
-            kshape.hw_dims().into(),
-            padding,
-            Some(strides[kshape.hw_axes()].into()),
-        ),
-        false,
-    )))
+    Ok(Box::new(AvgPool::new(pool_spec(pb)?, false)))
 }
 
 pub fn maxpool(pb: &NodeDef) -> TractResult<Box<Op>> {
+    Ok(Box::new(MaxPool::new(pool_spec(pb)?, None, false)))
+}
+
+pub fn maxpool_with_argmax(pb: &NodeDef) -> TractResult<Box<Op>> {
+    let index_dt = pb.get_attr_opt_datum_type("Targmax")?.unwrap_or(DatumType::I64);
+    let include_batch = pb.get_attr_opt_bool("include_batch_in_index")?.unwrap_or(false);
+    Ok(Box::new(MaxPool::new(pool_spec(pb)?, Some(index_dt), include_batch)))
+}
+
+fn pool_spec(pb: &NodeDef) -> TractResult<PoolSpec> {
     let ksize: Vec<usize> = pb.get_attr_list_int("ksize")?;
     let data_format = super::data_format(pb)?;
     let kshape = data_format.shape(ksize);
     let strides = super::strides(pb)?;
     let padding = super::padding(pb)?;
-    Ok(Box::new(MaxPool::new(
-        PoolSpec::new(
-            data_format,
-            kshape.hw_dims().into(),
-            padding,
-            Some(strides[kshape.hw_axes()].into()),
-        ),
-        None,
-    )))
+    Ok(PoolSpec::new(
+        data_format,
+        kshape.hw_dims().into(),
+        padding,
+        Some(strides[kshape.hw_axes()].into()),
+    ))
 }
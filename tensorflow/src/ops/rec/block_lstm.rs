@@ -2,8 +2,6 @@ use ndarray::*;
 
 use crate::tfpb::node_def::NodeDef;
 use tract_core::internal::*;
-use tract_core::ops::nn::sigmoid::sigmoid_f32;
-use tract_core::ops::nn::tanh::tanh_f32;
 
 pub fn block_lstm(node: &NodeDef) -> TractResult<Box<Op>> {
     let forget_bias = node.get_attr_opt_float("forget_bias")?.unwrap_or(1.0);
@@ -48,6 +46,8 @@ impl StatelessOp for BlockLSTM {
         let mut h = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
         let mut h_prev = h_prev.to_owned();
         let mut cs_prev = cs_prev.to_owned();
+        let sigmoid = (tract_linalg::ops().ssigmoid)();
+        let tanh = (tract_linalg::ops().stanh)();
 
         /*
         dbg!(&h_prev);
@@ -74,17 +74,18 @@ impl StatelessOp for BlockLSTM {
             //            dbg!(&i_ci_f_o);
 
             i.assign(&i_ci_f_o.slice_axis(Axis(1), (0..cell_size).into()));
-            i.mapv_inplace(sigmoid_f32); // TODO: peepholes
-                                         // dbg!(&i);
+            sigmoid.run(i.as_slice_mut().unwrap()); // TODO: peepholes
+                                                    // dbg!(&i);
 
             //            println!("i: {:?}", i.iter().take(6).collect::<Vec<_>>());
 
             f.assign(&i_ci_f_o.slice_axis(Axis(1), (2 * cell_size..3 * cell_size).into()));
-            f.mapv_inplace(|x| sigmoid_f32(x + self.forget_bias)); // TODO: peepholes
-                                                                   //            println!("f: {:?}", f.iter().take(6).collect::<Vec<_>>());
+            f.mapv_inplace(|x| x + self.forget_bias);
+            sigmoid.run(f.as_slice_mut().unwrap()); // TODO: peepholes
+                                                    //            println!("f: {:?}", f.iter().take(6).collect::<Vec<_>>());
 
             ci.assign(&i_ci_f_o.slice_axis(Axis(1), (cell_size..2 * cell_size).into()));
-            ci.mapv_inplace(tanh_f32);
+            tanh.run(ci.as_slice_mut().unwrap());
             //            println!("ci: {:?}", ci.iter().take(6).collect::<Vec<_>>());
             /*
                         dbg!(&f);
@@ -99,12 +100,12 @@ impl StatelessOp for BlockLSTM {
             //            dbg!(&cs);
 
             o.assign(&i_ci_f_o.slice_axis(Axis(1), (3 * cell_size..4 * cell_size).into()));
-            o.mapv_inplace(sigmoid_f32); // TODO: peephole
-                                         // dbg!(&o);
-                                         //            println!("o: {:?}", o.iter().take(6).collect::<Vec<_>>());
+            sigmoid.run(o.as_slice_mut().unwrap()); // TODO: peephole
+                                                    // dbg!(&o);
+                                                    //            println!("o: {:?}", o.iter().take(6).collect::<Vec<_>>());
 
             co.assign(&cs);
-            co.mapv_inplace(tanh_f32);
+            tanh.run(co.as_slice_mut().unwrap());
             //            println!("co: {:?}", co.iter().take(6).collect::<Vec<_>>());
 
             h_prev.assign(&co);
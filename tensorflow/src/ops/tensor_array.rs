@@ -0,0 +1,603 @@
+//! TensorArrayV3 and friends.
+//!
+//! A tensor array lives in the SessionState: its size is stored under the
+//! array id, and each written element under "{id}#{index}". The array handle
+//! is a String scalar holding the id, and the "flow" tensors TensorFlow uses
+//! to sequence accesses are dummy float scalars.
+//!
+//! Writing past the end of the array grows it, whether it was created with
+//! dynamic_size or not.
+
+use std::collections::HashSet;
+
+use ndarray::*;
+use tract_core::internal::*;
+
+use crate::model::TfOpRegister;
+use crate::tfpb::node_def::NodeDef;
+
+pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("TensorArrayGatherV3", gather);
+    reg.insert("TensorArrayReadV3", read);
+    reg.insert("TensorArrayScatterV3", |_| Ok(Box::new(TensorArrayScatterV3)));
+    reg.insert("TensorArraySizeV3", |_| Ok(Box::new(TensorArraySizeV3)));
+    reg.insert("TensorArrayV3", tensor_array);
+    reg.insert("TensorArrayWriteV3", |_| Ok(Box::new(TensorArrayWriteV3)));
+}
+
+fn tensor_array(node: &NodeDef) -> TractResult<Box<Op>> {
+    let dtype = node.get_attr_datum_type("dtype")?;
+    let element_shape = node.get_attr_opt_shape_fact("element_shape")?.unwrap_or_default();
+    Ok(Box::new(TensorArrayV3::new(node.get_name().to_string(), dtype, element_shape)))
+}
+
+fn read(node: &NodeDef) -> TractResult<Box<Op>> {
+    let dtype = node.get_attr_datum_type("dtype")?;
+    let element_shape = node.get_attr_opt_shape_fact("element_shape")?.unwrap_or_default();
+    Ok(Box::new(TensorArrayReadV3::new(dtype, element_shape)))
+}
+
+fn gather(node: &NodeDef) -> TractResult<Box<Op>> {
+    let dtype = node.get_attr_datum_type("dtype")?;
+    let element_shape = node.get_attr_opt_shape_fact("element_shape")?.unwrap_or_default();
+    Ok(Box::new(TensorArrayGatherV3::new(dtype, element_shape)))
+}
+
+/// Propagate the element shapes of the TensorArrayV3 nodes to the Read and
+/// Gather nodes accessing them, following handles through Enter and Identity.
+///
+/// Returns None if there is nothing to propagate.
+pub fn with_element_shapes(nodes: &[NodeDef]) -> TractResult<Option<Vec<NodeDef>>> {
+    let arrays = nodes
+        .iter()
+        .filter(|n| n.get_op() == "TensorArrayV3" && n.get_attr().contains_key("element_shape"))
+        .collect::<Vec<_>>();
+    if arrays.is_empty() {
+        return Ok(None);
+    }
+    let mut nodes = nodes.to_vec();
+    for array in arrays {
+        let shape = array.get_attr()["element_shape"].clone();
+        let mut handles = HashSet::new();
+        handles.insert(array.get_name().to_string());
+        loop {
+            let before = handles.len();
+            for node in &nodes {
+                if (node.get_op() == "Enter" || node.get_op() == "Identity")
+                    && node.get_input().get(0).map(|i| is_handle(i, &handles)).unwrap_or(false)
+                {
+                    handles.insert(node.get_name().to_string());
+                }
+            }
+            if handles.len() == before {
+                break;
+            }
+        }
+        for node in &mut nodes {
+            if (node.get_op() == "TensorArrayReadV3" || node.get_op() == "TensorArrayGatherV3")
+                && node.get_input().get(0).map(|i| is_handle(i, &handles)).unwrap_or(false)
+            {
+                let known = node.get_attr_opt_shape_fact("element_shape")?.unwrap_or_default();
+                if known == ShapeFact::default() {
+                    node.mut_attr().insert("element_shape".to_string(), shape.clone());
+                }
+            }
+        }
+    }
+    Ok(Some(nodes))
+}
+
+fn is_handle(input: &str, handles: &HashSet<String>) -> bool {
+    handles.contains(input.trim_end_matches(":0"))
+}
+
+fn handle(tensor: &Tensor) -> TractResult<String> {
+    Ok(tensor.to_scalar::<String>()?.clone())
+}
+
+fn index(tensor: &Tensor) -> TractResult<usize> {
+    let ix = *tensor.cast_to::<i32>()?.to_scalar::<i32>()?;
+    if ix < 0 {
+        bail!("Negative index in tensor array: {}", ix)
+    }
+    Ok(ix as usize)
+}
+
+fn flow() -> Arc<Tensor> {
+    rctensor0(0f32)
+}
+
+fn size(session: &SessionState, id: &str) -> TractResult<usize> {
+    let size = session.tensors.get(id).ok_or_else(|| format!("Unknown tensor array {}", id))?;
+    Ok(*size.to_scalar::<i32>()? as usize)
+}
+
+fn write(session: &mut SessionState, id: &str, ix: usize, value: Tensor) -> TractResult<()> {
+    if ix >= size(session, id)? {
+        session.tensors.insert(id.to_string(), tensor0(ix as i32 + 1));
+    }
+    session.tensors.insert(format!("{}#{}", id, ix), value);
+    Ok(())
+}
+
+fn read_element<'s>(session: &'s SessionState, id: &str, ix: usize) -> TractResult<&'s Tensor> {
+    Ok(session
+        .tensors
+        .get(&format!("{}#{}", id, ix))
+        .ok_or_else(|| format!("Reading unwritten element {} of tensor array {}", ix, id))?)
+}
+
+fn split<T: Datum>(value: &Tensor) -> TractResult<Vec<Tensor>> {
+    Ok(value.to_array_view::<T>()?.outer_iter().map(|v| v.to_owned().into()).collect())
+}
+
+fn stack<T: Datum + Copy>(elements: &[&Tensor]) -> TractResult<Tensor> {
+    let views = elements
+        .iter()
+        .map(|t| Ok(t.to_array_view::<T>()?.insert_axis(Axis(0))))
+        .collect::<TractResult<Vec<_>>>()?;
+    Ok(ndarray::stack(Axis(0), &views)?.into())
+}
+
+// The ops hold no state of their own: this only makes them StatefullOps so
+// they get access to the SessionState.
+#[derive(Clone, Debug)]
+struct TensorArrayState;
+
+impl OpState for TensorArrayState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        if let Some(op) = op.downcast_ref::<TensorArrayV3>() {
+            op.eval(session, inputs)
+        } else if let Some(op) = op.downcast_ref::<TensorArrayWriteV3>() {
+            op.eval(session, inputs)
+        } else if let Some(op) = op.downcast_ref::<TensorArrayReadV3>() {
+            op.eval(session, inputs)
+        } else if let Some(op) = op.downcast_ref::<TensorArrayScatterV3>() {
+            op.eval(session, inputs)
+        } else if let Some(op) = op.downcast_ref::<TensorArrayGatherV3>() {
+            op.eval(session, inputs)
+        } else if let Some(op) = op.downcast_ref::<TensorArraySizeV3>() {
+            op.eval(session, inputs)
+        } else {
+            bail!("wrong op for tensor array state")
+        }
+    }
+}
+
+macro_rules! tensor_array_op {
+    ($op:ident) => {
+        impl Op for $op {
+            fn name(&self) -> Cow<str> {
+                concat!("tf.", stringify!($op)).into()
+            }
+        }
+
+        impl StatefullOp for $op {
+            fn state(&self, _session: &mut SessionState) -> TractResult<Option<Box<OpState>>> {
+                Ok(Some(Box::new(TensorArrayState)))
+            }
+        }
+    };
+}
+
+/// Create a tensor array. Inputs: size. Outputs: handle, flow.
+#[derive(Clone, Debug, new)]
+pub struct TensorArrayV3 {
+    pub id: String,
+    pub dtype: DatumType,
+    pub element_shape: ShapeFact,
+}
+
+tensor_array_op!(TensorArrayV3);
+
+impl TensorArrayV3 {
+    fn eval(
+        &self,
+        session: &mut SessionState,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let size = index(&inputs[0])?;
+        let prefix = format!("{}#", self.id);
+        session.tensors.retain(|k, _| !k.starts_with(&prefix));
+        session.tensors.insert(self.id.clone(), tensor0(size as i32));
+        Ok(tvec!(rctensor0(self.id.clone()), flow()))
+    }
+}
+
+impl InferenceRulesOp for TensorArrayV3 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 2)?;
+        s.equals(&inputs[0].rank, 0)?;
+        s.equals(&outputs[0].datum_type, DatumType::String)?;
+        s.equals(&outputs[0].rank, 0)?;
+        s.equals(&outputs[1].datum_type, DatumType::F32)?;
+        s.equals(&outputs[1].rank, 0)?;
+        Ok(())
+    }
+}
+
+/// Inputs: handle, index, value, flow. Outputs: flow.
+#[derive(Clone, Debug, new)]
+pub struct TensorArrayWriteV3;
+
+tensor_array_op!(TensorArrayWriteV3);
+
+impl TensorArrayWriteV3 {
+    fn eval(
+        &self,
+        session: &mut SessionState,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let (array, ix, value, _flow) = args_4!(inputs);
+        write(session, &handle(&array)?, index(&ix)?, value.into_tensor())?;
+        Ok(tvec!(flow()))
+    }
+}
+
+impl InferenceRulesOp for TensorArrayWriteV3 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 4)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::String)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].rank, 0)?;
+        Ok(())
+    }
+}
+
+/// Inputs: handle, index, flow. Outputs: value.
+#[derive(Clone, Debug, new)]
+pub struct TensorArrayReadV3 {
+    dtype: DatumType,
+    element_shape: ShapeFact,
+}
+
+tensor_array_op!(TensorArrayReadV3);
+
+impl TensorArrayReadV3 {
+    fn eval(
+        &self,
+        session: &mut SessionState,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let (array, ix, _flow) = args_3!(inputs);
+        let element = read_element(session, &handle(&array)?, index(&ix)?)?;
+        Ok(tvec!(element.clone().into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for TensorArrayReadV3 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::String)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&outputs[0].datum_type, self.dtype)?;
+        s.equals(&outputs[0].shape, self.element_shape.clone())?;
+        Ok(())
+    }
+}
+
+/// Inputs: handle, indices, value, flow. Outputs: flow.
+///
+/// Writes the slices of value along its first axis at the given indices.
+#[derive(Clone, Debug, new)]
+pub struct TensorArrayScatterV3;
+
+tensor_array_op!(TensorArrayScatterV3);
+
+impl TensorArrayScatterV3 {
+    fn eval(
+        &self,
+        session: &mut SessionState,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let (array, indices, value, _flow) = args_4!(inputs);
+        let id = handle(&array)?;
+        let indices = indices.cast_to::<i32>()?;
+        let indices = indices.as_slice::<i32>()?;
+        let elements = dispatch_datum!(self::split(value.datum_type())(&value))?;
+        if indices.len() != elements.len() {
+            bail!("Scattering {} elements at {} indices", elements.len(), indices.len())
+        }
+        for (&ix, element) in indices.iter().zip(elements.into_iter()) {
+            write(session, &id, index(&tensor0(ix))?, element)?;
+        }
+        Ok(tvec!(flow()))
+    }
+}
+
+impl InferenceRulesOp for TensorArrayScatterV3 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 4)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::String)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], &inputs[2].shape[0])?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&outputs[0].rank, 0)?;
+        Ok(())
+    }
+}
+
+/// Inputs: handle, indices, flow. Outputs: value.
+///
+/// Stacks the elements at the given indices along a new first axis.
+#[derive(Clone, Debug, new)]
+pub struct TensorArrayGatherV3 {
+    dtype: DatumType,
+    element_shape: ShapeFact,
+}
+
+tensor_array_op!(TensorArrayGatherV3);
+
+impl TensorArrayGatherV3 {
+    fn eval(
+        &self,
+        session: &mut SessionState,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let (array, indices, _flow) = args_3!(inputs);
+        let id = handle(&array)?;
+        let indices = indices.cast_to::<i32>()?;
+        let elements = indices
+            .as_slice::<i32>()?
+            .iter()
+            .map(|&ix| read_element(session, &id, index(&tensor0(ix))?))
+            .collect::<TractResult<Vec<&Tensor>>>()?;
+        if elements.is_empty() {
+            bail!("Gathering no element from tensor array {}", id)
+        }
+        Ok(tvec!(dispatch_copy!(self::stack(self.dtype)(&elements))?.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for TensorArrayGatherV3 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::String)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&outputs[0].datum_type, self.dtype)?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        if !self.element_shape.is_open() {
+            let dims = self.element_shape.dims().collect::<Vec<_>>();
+            s.equals(&outputs[0].rank, dims.len() as i32 + 1)?;
+            for (ix, dim) in dims.into_iter().enumerate() {
+                if let GenericFact::Only(dim) = dim {
+                    s.equals(&outputs[0].shape[ix + 1], dim)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Inputs: handle, flow. Outputs: size.
+#[derive(Clone, Debug, new)]
+pub struct TensorArraySizeV3;
+
+tensor_array_op!(TensorArraySizeV3);
+
+impl TensorArraySizeV3 {
+    fn eval(
+        &self,
+        session: &mut SessionState,
+        mut inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let (array, _flow) = args_2!(inputs);
+        Ok(tvec!(rctensor0(size(session, &handle(&array)?)? as i32)))
+    }
+}
+
+impl InferenceRulesOp for TensorArraySizeV3 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::String)?;
+        s.equals(&outputs[0].datum_type, DatumType::I32)?;
+        s.equals(&outputs[0].rank, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::control_flow::tests::{enter, konst, node};
+    use crate::tfpb;
+    use crate::tfpb::types::DataType::*;
+
+    fn array(name: &str, size: &str, element_shape: &[usize]) -> NodeDef {
+        tfpb::node()
+            .name(name)
+            .op("TensorArrayV3")
+            .input(size)
+            .attr("dtype", DT_FLOAT)
+            .attr("element_shape", shape(element_shape))
+    }
+
+    fn shape(dims: &[usize]) -> tfpb::tensor_shape::TensorShapeProto {
+        let mut shape = tfpb::tensor_shape::TensorShapeProto::new();
+        for &d in dims {
+            let mut dim = tfpb::tensor_shape::TensorShapeProto_Dim::new();
+            dim.set_size(d as i64);
+            shape.mut_dim().push(dim);
+        }
+        shape
+    }
+
+    fn run(graph: tfpb::graph::GraphDef, outputs: &[&str], x: Tensor) -> TVec<Arc<Tensor>> {
+        let mut model = crate::tensorflow().model_for_proto_model(&graph).unwrap();
+        model.set_input_names(&["x"]).unwrap();
+        model.set_output_names(outputs).unwrap();
+        model.set_input_fact(0, TensorFact::dt_shape(x.datum_type(), x.shape())).unwrap();
+        let model = model.into_optimized().unwrap();
+        SimplePlan::new(&model).unwrap().run(tvec!(x)).unwrap()
+    }
+
+    #[test]
+    fn scatter_read_gather() {
+        let graph = tfpb::graph()
+            .node(tfpb::node().name("x").op("Placeholder").attr("dtype", DT_FLOAT))
+            .node(konst("size", tfpb::tensor_i32(vec![], vec![3]), DT_INT32))
+            .node(konst("range", tfpb::tensor_i32(vec![3], vec![0, 1, 2]), DT_INT32))
+            .node(konst("one", tfpb::tensor_i32(vec![], vec![1]), DT_INT32))
+            .node(konst("indices", tfpb::tensor_i32(vec![2], vec![2, 0]), DT_INT32))
+            .node(array("ta", "size", &[2]))
+            .node(node("scatter", "TensorArrayScatterV3", &["ta", "range", "x", "ta:1"], DT_FLOAT))
+            .node(
+                tfpb::node()
+                    .name("read")
+                    .op("TensorArrayReadV3")
+                    .input("ta")
+                    .input("one")
+                    .input("scatter")
+                    .attr("dtype", DT_FLOAT),
+            )
+            .node(
+                tfpb::node()
+                    .name("gather")
+                    .op("TensorArrayGatherV3")
+                    .input("ta")
+                    .input("indices")
+                    .input("scatter")
+                    .attr("dtype", DT_FLOAT),
+            )
+            .node(
+                tfpb::node().name("size_out").op("TensorArraySizeV3").input("ta").input("scatter"),
+            );
+        let x = tensor2(&[[1.0f32, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let outputs = run(graph, &["read", "gather", "size_out"], x);
+        assert_eq!(
+            outputs,
+            tvec!(
+                rctensor1(&[3.0f32, 4.0]),
+                rctensor2(&[[5.0f32, 6.0], [1.0, 2.0]]),
+                rctensor0(3i32)
+            )
+        );
+    }
+
+    // time = 0
+    // while time < 3:
+    //     output_ta = output_ta.write(time, input_ta.read(time) * 2)
+    //     time += 1
+    #[test]
+    fn dynamic_rnn_like_loop() {
+        let graph = tfpb::graph()
+            .node(tfpb::node().name("x").op("Placeholder").attr("dtype", DT_FLOAT))
+            .node(konst("size", tfpb::tensor_i32(vec![], vec![3]), DT_INT32))
+            .node(konst("range", tfpb::tensor_i32(vec![3], vec![0, 1, 2]), DT_INT32))
+            .node(konst("zero", tfpb::tensor_i32(vec![], vec![0]), DT_INT32))
+            .node(konst("two", tfpb::tensor_f32(vec![], vec![2.0]), DT_FLOAT))
+            .node(array("input_ta", "size", &[2]))
+            .node(array("output_ta", "size", &[2]))
+            .node(node(
+                "scatter",
+                "TensorArrayScatterV3",
+                &["input_ta", "range", "x", "input_ta:1"],
+                DT_FLOAT,
+            ))
+            .node(enter("while/Enter", "zero", DT_INT32, false))
+            .node(enter("while/Enter_1", "output_ta:1", DT_FLOAT, false))
+            .node(enter("while/Enter_2", "size", DT_INT32, true))
+            .node(enter("while/Enter_3", "input_ta", DT_STRING, true))
+            .node(enter("while/Enter_4", "scatter", DT_FLOAT, true))
+            .node(enter("while/Enter_5", "output_ta", DT_STRING, true))
+            .node(enter("while/Enter_6", "two", DT_FLOAT, true))
+            .node(
+                node("while/Merge", "Merge", &["while/Enter", "while/NextIteration"], DT_INT32)
+                    .attr("N", 2i64),
+            )
+            .node(
+                node(
+                    "while/Merge_1",
+                    "Merge",
+                    &["while/Enter_1", "while/NextIteration_1"],
+                    DT_FLOAT,
+                )
+                .attr("N", 2i64),
+            )
+            .node(node("while/Less", "Less", &["while/Merge", "while/Enter_2"], DT_INT32))
+            .node(tfpb::node().name("while/LoopCond").op("LoopCond").input("while/Less"))
+            .node(node("while/Switch", "Switch", &["while/Merge", "while/LoopCond"], DT_INT32))
+            .node(node("while/Switch_1", "Switch", &["while/Merge_1", "while/LoopCond"], DT_FLOAT))
+            .node(node("while/Identity", "Identity", &["while/Switch:1"], DT_INT32))
+            .node(node("while/Identity_1", "Identity", &["while/Switch_1:1"], DT_FLOAT))
+            .node(
+                tfpb::node()
+                    .name("while/read")
+                    .op("TensorArrayReadV3")
+                    .input("while/Enter_3")
+                    .input("while/Identity")
+                    .input("while/Enter_4")
+                    .attr("dtype", DT_FLOAT),
+            )
+            .node(node("while/mul", "Mul", &["while/read", "while/Enter_6"], DT_FLOAT))
+            .node(node(
+                "while/write",
+                "TensorArrayWriteV3",
+                &["while/Enter_5", "while/Identity", "while/mul", "while/Identity_1"],
+                DT_FLOAT,
+            ))
+            .node(
+                konst("while/add/y", tfpb::tensor_i32(vec![], vec![1]), DT_INT32)
+                    .input("^while/Identity"),
+            )
+            .node(node("while/add", "Add", &["while/Identity", "while/add/y"], DT_INT32))
+            .node(node("while/NextIteration", "NextIteration", &["while/add"], DT_INT32))
+            .node(node("while/NextIteration_1", "NextIteration", &["while/write"], DT_FLOAT))
+            .node(node("while/Exit", "Exit", &["while/Switch"], DT_INT32))
+            .node(node("while/Exit_1", "Exit", &["while/Switch_1"], DT_FLOAT))
+            .node(
+                tfpb::node()
+                    .name("output")
+                    .op("TensorArrayGatherV3")
+                    .input("output_ta")
+                    .input("range")
+                    .input("while/Exit_1")
+                    .attr("dtype", DT_FLOAT),
+            );
+        let x = tensor2(&[[1.0f32, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let outputs = run(graph, &["output"], x);
+        assert_eq!(outputs, tvec!(rctensor2(&[[2.0f32, 4.0], [6.0, 8.0], [10.0, 12.0]])));
+    }
+}
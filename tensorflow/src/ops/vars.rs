@@ -8,13 +8,24 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("VariableV2", variable_v2);
 }
 
-fn variable_v2(node: &NodeDef) -> TractResult<Box<Op>> {
+fn container_and_shared_name(node: &NodeDef) -> TractResult<(Option<String>, Option<String>)> {
     let shared_name = node.get_attr_str("shared_name")?;
     let shared_name = if shared_name != "" { Some(shared_name) } else { None };
     let container = node.get_attr_str("container")?;
     let container = if container != "" { Some(container) } else { None };
+    Ok((container, shared_name))
+}
+
+/// The key of a VariableV2 node state in SessionState::tensors.
+pub fn variable_id(node: &NodeDef) -> TractResult<String> {
+    let (container, shared_name) = container_and_shared_name(node)?;
+    Ok(format!("{:?}#{:?}#{}", container, shared_name, node.get_name()))
+}
+
+fn variable_v2(node: &NodeDef) -> TractResult<Box<Op>> {
+    let (container, shared_name) = container_and_shared_name(node)?;
     let name = node.get_name().to_string();
-    let id = format!("{:?}#{:?}#{}", container, shared_name, name);
+    let id = variable_id(node)?;
     let shape = node.get_attr_shape("shape")?;
     let dt = node.get_attr_datum_type("dtype")?;
     Ok(Box::new(VariableV2::new(container, shared_name, name, id, shape, dt)))
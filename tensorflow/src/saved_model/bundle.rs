@@ -0,0 +1,326 @@
+//! Reader for TensorFlow checkpoints in the tensor bundle format.
+//!
+//! A bundle with prefix `p` is made of an index, `p.index`, and data shards,
+//! `p.data-00000-of-00001` and so on. The index is a LevelDB table mapping
+//! tensor names to BundleEntryProto locating the raw tensor content in the
+//! shards. The empty key holds the BundleHeaderProto.
+//!
+//! Entries tract can not represent, like resources or string tensors that are
+//! not valid UTF-8, are skipped: only the values of VariableV2 nodes are used.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use tract_core::internal::*;
+
+use super::proto::{BundleEntryProto, BundleHeaderProto};
+
+const TABLE_MAGIC: u64 = 0xdb4775248b80fb57;
+const FOOTER_LEN: usize = 48;
+
+/// Read all tensors from the bundle at `prefix`.
+pub fn read_bundle(prefix: impl AsRef<Path>) -> TractResult<HashMap<String, Tensor>> {
+    let prefix = prefix.as_ref().to_string_lossy().to_string();
+    let index = fs::read(format!("{}.index", prefix))?;
+    let entries = table_entries(&index)?;
+    let header = entries
+        .iter()
+        .find(|(k, _)| k.is_empty())
+        .ok_or_else(|| format!("No header in bundle {}", prefix))?;
+    let header = BundleHeaderProto::parse(&header.1)?;
+    if header.big_endian != cfg!(target_endian = "big") {
+        bail!("Bundle {} endianness does not match", prefix)
+    }
+    let mut shards = vec![];
+    for shard in 0..header.num_shards {
+        shards.push(fs::File::open(format!(
+            "{}.data-{:05}-of-{:05}",
+            prefix, shard, header.num_shards
+        ))?);
+    }
+    let mut tensors = HashMap::new();
+    for (key, value) in entries {
+        if key.is_empty() {
+            continue;
+        }
+        let name = String::from_utf8(key).map_err(|e| format!("{:?}", e))?;
+        let entry = BundleEntryProto::parse(&value)?;
+        if entry.sliced {
+            bail!("Sliced tensor {} is not supported", name)
+        }
+        let shard = shards
+            .get_mut(entry.shard_id)
+            .ok_or_else(|| format!("Tensor {} refers to missing shard {}", name, entry.shard_id))?;
+        let mut content = vec![0u8; entry.size];
+        shard.seek(SeekFrom::Start(entry.offset))?;
+        shard.read_exact(&mut content)?;
+        let tensor = match entry.dtype {
+            Some(DatumType::String) => string_tensor(&entry.shape, &content),
+            Some(dt) => dispatch_copy!(self::tensor_from_raw(dt)(&entry.shape, &content)).map(Some),
+            None => Ok(None),
+        }
+        .map_err(|e| format!("Reading tensor {}: {}", name, e))?;
+        match tensor {
+            Some(tensor) => {
+                tensors.insert(name, tensor);
+            }
+            None => debug!("Skipping checkpoint entry {}", name),
+        }
+    }
+    Ok(tensors)
+}
+
+/// String tensors are stored as the varint lengths of all items, a checksum
+/// of the lengths, then the bytes. None if some item is not valid UTF-8.
+fn string_tensor(shape: &[usize], content: &[u8]) -> TractResult<Option<Tensor>> {
+    let len = shape.iter().product::<usize>();
+    let mut pos = 0;
+    let lengths = (0..len).map(|_| varint(content, &mut pos)).collect::<TractResult<Vec<_>>>()?;
+    pos += 4;
+    let mut items = Vec::with_capacity(len);
+    for length in lengths {
+        let end = pos + length as usize;
+        if end > content.len() {
+            bail!("Truncated string tensor")
+        }
+        match String::from_utf8(content[pos..end].to_vec()) {
+            Ok(item) => items.push(item),
+            Err(_) => return Ok(None),
+        }
+        pos = end;
+    }
+    if pos != content.len() {
+        bail!("Expected {} bytes of strings, got {}", pos, content.len())
+    }
+    Ok(Some(ndarray::Array::from_shape_vec(shape, items)?.into()))
+}
+
+fn tensor_from_raw<T: Datum + Copy>(shape: &[usize], content: &[u8]) -> TractResult<Tensor> {
+    let len = shape.iter().product::<usize>();
+    if content.len() != len * std::mem::size_of::<T>() {
+        bail!("Expected {} items of {:?}, got {} bytes", len, T::datum_type(), content.len())
+    }
+    unsafe { Tensor::from_raw::<T>(shape, content) }
+}
+
+fn varint(data: &[u8], pos: &mut usize) -> TractResult<u64> {
+    let mut result = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos).ok_or("Truncated varint in table")?;
+        *pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    bail!("Invalid varint in table")
+}
+
+fn block_handle(data: &[u8], pos: &mut usize) -> TractResult<(usize, usize)> {
+    let offset = varint(data, pos)? as usize;
+    let size = varint(data, pos)? as usize;
+    Ok((offset, size))
+}
+
+fn u32_at(data: &[u8], pos: usize) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&data[pos..pos + 4]);
+    u32::from_le_bytes(bytes)
+}
+
+/// A block is followed by a 5 bytes trailer: compression type and crc.
+fn block(table: &[u8], (offset, size): (usize, usize)) -> TractResult<Cow<[u8]>> {
+    if offset + size + 5 > table.len() {
+        bail!("Table block out of bounds")
+    }
+    let data = &table[offset..offset + size];
+    match table[offset + size] {
+        0 => Ok(Cow::Borrowed(data)),
+        1 => Ok(Cow::Owned(snappy(data)?)),
+        c => bail!("Unsupported table block compression {}", c),
+    }
+}
+
+/// Decompress a raw snappy buffer: the uncompressed length, then a series of
+/// literals and back references.
+fn snappy(data: &[u8]) -> TractResult<Vec<u8>> {
+    let mut pos = 0;
+    let len = varint(data, &mut pos)? as usize;
+    let mut output = Vec::with_capacity(len);
+    let le = |pos: usize, n: usize| -> TractResult<usize> {
+        let bytes = data.get(pos..pos + n).ok_or("Truncated snappy block")?;
+        Ok(bytes.iter().rev().fold(0, |acc, &b| (acc << 8) | b as usize))
+    };
+    while pos < data.len() {
+        let tag = data[pos] as usize;
+        pos += 1;
+        let (length, offset) = match tag & 3 {
+            0 => {
+                let mut length = tag >> 2;
+                if length >= 60 {
+                    let n = length - 59;
+                    length = le(pos, n)?;
+                    pos += n;
+                }
+                let literal = data.get(pos..pos + length + 1).ok_or("Truncated snappy literal")?;
+                output.extend_from_slice(literal);
+                pos += length + 1;
+                continue;
+            }
+            1 => {
+                let offset = ((tag >> 5) << 8) | le(pos, 1)?;
+                pos += 1;
+                (4 + ((tag >> 2) & 7), offset)
+            }
+            n => {
+                let n = if n == 2 { 2 } else { 4 };
+                let offset = le(pos, n)?;
+                pos += n;
+                ((tag >> 2) + 1, offset)
+            }
+        };
+        if offset == 0 || offset > output.len() {
+            bail!("Invalid snappy back reference")
+        }
+        let start = output.len() - offset;
+        for i in 0..length {
+            let byte = output[start + i];
+            output.push(byte);
+        }
+    }
+    if output.len() != len {
+        bail!("Snappy block expands to {} bytes, expected {}", output.len(), len)
+    }
+    Ok(output)
+}
+
+/// Entries of a block: keys are prefix-compressed with the previous key, a
+/// list of restart points ends the block.
+fn block_entries(block: &[u8]) -> TractResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    if block.len() < 4 {
+        bail!("Truncated table block")
+    }
+    let restarts = u32_at(block, block.len() - 4) as usize;
+    let end = block.len().checked_sub(4 * (restarts + 1)).ok_or("Truncated table block")?;
+    let mut entries = vec![];
+    let mut key: Vec<u8> = vec![];
+    let mut pos = 0;
+    while pos < end {
+        let shared = varint(block, &mut pos)? as usize;
+        let non_shared = varint(block, &mut pos)? as usize;
+        let value_len = varint(block, &mut pos)? as usize;
+        if shared > key.len() || pos + non_shared + value_len > end {
+            bail!("Corrupted table block")
+        }
+        key.truncate(shared);
+        key.extend_from_slice(&block[pos..pos + non_shared]);
+        pos += non_shared;
+        entries.push((key.clone(), block[pos..pos + value_len].to_vec()));
+        pos += value_len;
+    }
+    Ok(entries)
+}
+
+/// All entries of a LevelDB table, in key order.
+fn table_entries(table: &[u8]) -> TractResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    if table.len() < FOOTER_LEN {
+        bail!("Truncated table")
+    }
+    let footer = &table[table.len() - FOOTER_LEN..];
+    let magic = u32_at(footer, 40) as u64 | ((u32_at(footer, 44) as u64) << 32);
+    if magic != TABLE_MAGIC {
+        bail!("Invalid table magic number")
+    }
+    let mut pos = 0;
+    let _metaindex = block_handle(footer, &mut pos)?;
+    let index = block_handle(footer, &mut pos)?;
+    let mut entries = vec![];
+    for (_, handle) in block_entries(&block(table, index)?)? {
+        let handle = block_handle(&handle, &mut 0)?;
+        entries.extend(block_entries(&block(table, handle)?)?);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    fn put_varint(buf: &mut Vec<u8>, mut v: u64) {
+        while v >= 0x80 {
+            buf.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        buf.push(v as u8);
+    }
+
+    fn put_block(table: &mut Vec<u8>, entries: &[(Vec<u8>, Vec<u8>)]) -> (usize, usize) {
+        let offset = table.len();
+        let mut previous: &[u8] = &[];
+        for (k, v) in entries {
+            let shared = previous.iter().zip(k.iter()).take_while(|(a, b)| a == b).count();
+            put_varint(table, shared as u64);
+            put_varint(table, (k.len() - shared) as u64);
+            put_varint(table, v.len() as u64);
+            table.extend_from_slice(&k[shared..]);
+            table.extend_from_slice(v);
+            previous = k;
+        }
+        table.extend_from_slice(&0u32.to_le_bytes());
+        table.extend_from_slice(&1u32.to_le_bytes());
+        let size = table.len() - offset;
+        table.extend_from_slice(&[0, 0, 0, 0, 0]);
+        (offset, size)
+    }
+
+    /// A single data block table, enough for tests.
+    pub(crate) fn table(entries: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+        let mut table = vec![];
+        let data = put_block(&mut table, entries);
+        let metaindex = put_block(&mut table, &[]);
+        let mut handle = vec![];
+        put_varint(&mut handle, data.0 as u64);
+        put_varint(&mut handle, data.1 as u64);
+        let last_key = entries.last().map(|e| e.0.clone()).unwrap_or(vec![]);
+        let index = put_block(&mut table, &[(last_key, handle)]);
+        let mut footer = vec![];
+        for h in &[metaindex, index] {
+            put_varint(&mut footer, h.0 as u64);
+            put_varint(&mut footer, h.1 as u64);
+        }
+        footer.resize(40, 0);
+        footer.extend_from_slice(&TABLE_MAGIC.to_le_bytes());
+        table.extend(footer);
+        table
+    }
+
+    #[test]
+    fn table_roundtrip() {
+        let entries = vec![
+            (b"".to_vec(), b"header".to_vec()),
+            (b"dense/bias".to_vec(), b"b".to_vec()),
+            (b"dense/kernel".to_vec(), b"k".to_vec()),
+        ];
+        assert_eq!(table_entries(&table(&entries)).unwrap(), entries);
+    }
+
+    #[test]
+    fn snappy_block() {
+        // "abc" literal, then a 9 bytes back reference at offset 3
+        let data = [12, 0x08, b'a', b'b', b'c', 0x15, 3];
+        assert_eq!(snappy(&data).unwrap(), b"abcabcabcabc".to_vec());
+        assert!(snappy(&data[..6]).is_err());
+        assert!(snappy(&[4, 0x05, 1]).is_err());
+    }
+
+    #[test]
+    fn string_tensors() {
+        let content = [2, 1, 0, 0, 0, 0, b'a', b'b', b'c'];
+        let tensor = string_tensor(&[2], &content).unwrap().unwrap();
+        assert_eq!(tensor, Tensor::from(ndarray::arr1(&["ab".to_string(), "c".to_string()])));
+        assert!(string_tensor(&[2], &content[..8]).is_err());
+        assert!(string_tensor(&[1], &[1, 0, 0, 0, 0, 0xff]).unwrap().is_none());
+    }
+}
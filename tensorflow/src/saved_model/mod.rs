@@ -0,0 +1,239 @@
+//! Loading of TensorFlow SavedModel directories.
+//!
+//! A SavedModel directory contains `saved_model.pb`, holding one or more
+//! MetaGraphDef (selected by their tags), and a `variables` checkpoint
+//! bundle with the values of the graph variables.
+//!
+//! ```no_run
+//! # use tract_core::internal::*;
+//! let tf = tract_tensorflow::tensorflow();
+//! let saved = tf.saved_model_for_path("my_model", &["serve"]).unwrap();
+//! let model = saved.model_for_signature(&tf, "serving_default").unwrap();
+//! let model = model.into_optimized().unwrap();
+//! let plan = SimplePlan::new(&model).unwrap();
+//! let mut state = SimpleState::new(&plan).unwrap();
+//! saved.init_variables(&mut state.session_state).unwrap();
+//! ```
+
+use std::convert::TryFrom;
+use std::path::Path;
+
+use tract_core::internal::*;
+
+use crate::model::Tensorflow;
+
+pub mod bundle;
+pub mod proto;
+
+pub use self::proto::{MetaGraphDef, SignatureDef, TensorInfo};
+
+/// A MetaGraphDef from a SavedModel, and the checkpointed values of its
+/// variables.
+#[derive(Clone, Debug)]
+pub struct SavedModel {
+    pub meta_graph: MetaGraphDef,
+    /// Checkpoint tensors, by name.
+    pub variables: HashMap<String, Tensor>,
+}
+
+impl Tensorflow {
+    /// Load the MetaGraphDef matching exactly `tags` from the SavedModel in
+    /// `dir`, and its variables.
+    pub fn saved_model_for_path(
+        &self,
+        dir: impl AsRef<Path>,
+        tags: &[&str],
+    ) -> TractResult<SavedModel> {
+        let dir = dir.as_ref();
+        let proto = proto::SavedModelProto::parse(&std::fs::read(dir.join("saved_model.pb"))?)?;
+        let meta_graph = proto
+            .meta_graphs
+            .into_iter()
+            .find(|mg| {
+                let mut found: Vec<&str> = mg.tags.iter().map(|s| &**s).collect();
+                let mut wanted = tags.to_vec();
+                found.sort();
+                wanted.sort();
+                found == wanted
+            })
+            .ok_or_else(|| format!("No MetaGraphDef with tags {:?} in {:?}", tags, dir))?;
+        let prefix = dir.join("variables").join("variables");
+        let variables = if prefix.with_extension("index").exists() {
+            bundle::read_bundle(prefix)?
+        } else {
+            HashMap::new()
+        };
+        Ok(SavedModel { meta_graph, variables })
+    }
+}
+
+impl SavedModel {
+    /// The whole graph, without inputs and outputs set up.
+    pub fn model(&self, tf: &Tensorflow) -> TractResult<InferenceModel> {
+        tf.model_for_proto_model(&self.meta_graph.graph_def)
+    }
+
+    /// The graph, with inputs and outputs set up after a signature.
+    ///
+    /// Inputs and outputs are ordered by their key in the signature.
+    pub fn model_for_signature(
+        &self,
+        tf: &Tensorflow,
+        signature: &str,
+    ) -> TractResult<InferenceModel> {
+        let signature = self.meta_graph.signature_def.get(signature).ok_or_else(|| {
+            format!(
+                "No signature {}, found: {:?}",
+                signature,
+                self.meta_graph.signature_def.keys().collect::<Vec<_>>()
+            )
+        })?;
+        let mut model = self.model(tf)?;
+        let inputs = Self::outlets(&model, &signature.inputs)?;
+        model.set_input_outlets(&inputs.iter().map(|p| p.0).collect::<Vec<_>>())?;
+        for (ix, (_, info)) in inputs.iter().enumerate() {
+            let mut fact = TensorFact::default();
+            if let Some(dt) = info.dtype {
+                fact = fact.with_datum_type(dt);
+            }
+            if let Some(shape) = &info.tensor_shape {
+                fact = fact.with_shape(ShapeFact::try_from(shape)?);
+            }
+            model.set_input_fact(ix, fact)?;
+        }
+        let outputs = Self::outlets(&model, &signature.outputs)?;
+        model.set_output_outlets(&outputs.iter().map(|p| p.0).collect::<Vec<_>>())?;
+        Ok(model)
+    }
+
+    fn outlets<'a>(
+        model: &InferenceModel,
+        infos: &'a HashMap<String, TensorInfo>,
+    ) -> TractResult<Vec<(OutletId, &'a TensorInfo)>> {
+        let mut keys: Vec<&String> = infos.keys().collect();
+        keys.sort();
+        keys.into_iter()
+            .map(|k| {
+                let info = &infos[k];
+                let (node, slot) = Tensorflow::parse_input(&info.name)?;
+                Ok((OutletId::new(model.node_by_name(node)?.id, slot), info))
+            })
+            .collect()
+    }
+
+    /// Fill the state of the VariableV2 nodes with the checkpointed values.
+    ///
+    /// Must be called after the op states are created, as creating them
+    /// resets the variables.
+    pub fn init_variables(&self, session: &mut SessionState) -> TractResult<()> {
+        for node in self.meta_graph.graph_def.get_node() {
+            if node.get_op() == "VariableV2" {
+                let value = self
+                    .variables
+                    .get(node.get_name())
+                    .ok_or_else(|| format!("No value for {} in checkpoint", node.get_name()))?;
+                session.tensors.insert(crate::ops::vars::variable_id(node)?, value.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tfpb;
+    use crate::tfpb::tensor_shape::TensorShapeProto;
+    use crate::tfpb::types::DataType::DT_FLOAT;
+    use protobuf::{CodedOutputStream, Message};
+
+    #[derive(Default)]
+    struct Pb(Vec<u8>);
+
+    impl Pb {
+        fn bytes(mut self, number: u32, bytes: &[u8]) -> Pb {
+            let mut os = CodedOutputStream::vec(&mut self.0);
+            os.write_bytes(number, bytes).unwrap();
+            os.flush().unwrap();
+            drop(os);
+            self
+        }
+
+        fn varint(mut self, number: u32, v: u64) -> Pb {
+            let mut os = CodedOutputStream::vec(&mut self.0);
+            os.write_uint64(number, v).unwrap();
+            os.flush().unwrap();
+            drop(os);
+            self
+        }
+    }
+
+    fn shape_attr(dims: &[i64]) -> tfpb::attr_value::AttrValue {
+        let mut shape = TensorShapeProto::new();
+        for &d in dims {
+            let mut dim = tfpb::tensor_shape::TensorShapeProto_Dim::new();
+            dim.set_size(d);
+            shape.mut_dim().push(dim);
+        }
+        let mut value = tfpb::attr_value::AttrValue::new();
+        value.set_shape(shape);
+        value
+    }
+
+    // y = x + w, w being a variable with value [1, 2] in the checkpoint
+    fn write_saved_model(dir: &Path) {
+        let graph = tfpb::graph()
+            .node(tfpb::node().name("x").op("Placeholder").attr("dtype", DT_FLOAT))
+            .node(
+                tfpb::node()
+                    .name("w")
+                    .op("VariableV2")
+                    .attr("dtype", DT_FLOAT)
+                    .attr("shape", shape_attr(&[2]))
+                    .attr("container", "")
+                    .attr("shared_name", ""),
+            )
+            .node(tfpb::node().name("y").op("Add").input("x").input("w").attr("T", DT_FLOAT));
+        let info = |name: &str| Pb::default().bytes(1, name.as_bytes()).varint(2, 1).0;
+        let signature = Pb::default()
+            .bytes(1, &Pb::default().bytes(1, b"x").bytes(2, &info("x:0")).0)
+            .bytes(2, &Pb::default().bytes(1, b"y").bytes(2, &info("y")).0)
+            .bytes(3, b"tensorflow/serving/predict");
+        let meta_graph = Pb::default()
+            .bytes(1, &Pb::default().bytes(4, b"serve").0)
+            .bytes(2, &graph.write_to_bytes().unwrap())
+            .bytes(5, &Pb::default().bytes(1, b"serving_default").bytes(2, &signature.0).0);
+        let saved_model = Pb::default().varint(1, 1).bytes(2, &meta_graph.0);
+        std::fs::create_dir_all(dir.join("variables")).unwrap();
+        std::fs::write(dir.join("saved_model.pb"), &saved_model.0).unwrap();
+
+        let w_shape = Pb::default().bytes(2, &Pb::default().varint(1, 2).0);
+        let w_entry = Pb::default().varint(1, 1).bytes(2, &w_shape.0).varint(4, 0).varint(5, 8);
+        let index = bundle::test::table(&[
+            (vec![], Pb::default().varint(1, 1).0),
+            (b"w".to_vec(), w_entry.0),
+        ]);
+        std::fs::write(dir.join("variables/variables.index"), index).unwrap();
+        let data: Vec<u8> = [1.0f32, 2.0].iter().flat_map(|f| f.to_ne_bytes().to_vec()).collect();
+        std::fs::write(dir.join("variables/variables.data-00000-of-00001"), data).unwrap();
+    }
+
+    #[test]
+    fn run_saved_model_signature() {
+        let dir = std::env::temp_dir().join(format!("tract-saved-model-{}", std::process::id()));
+        write_saved_model(&dir);
+        let tf = crate::tensorflow();
+        let saved = tf.saved_model_for_path(&dir, &["serve"]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(tf.saved_model_for_path(&dir, &["train"]).is_err());
+        assert_eq!(saved.variables["w"], tensor1(&[1.0f32, 2.0]));
+
+        let model = saved.model_for_signature(&tf, "serving_default").unwrap();
+        assert_eq!(model.input_fact(0).unwrap().datum_type, GenericFact::Only(DatumType::F32));
+        let plan = SimplePlan::new(&model).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        saved.init_variables(&mut state.session_state).unwrap();
+        let outputs = state.run(tvec!(tensor1(&[10.0f32, 20.0]))).unwrap();
+        assert_eq!(outputs[0], rctensor1(&[11.0f32, 22.0]));
+    }
+}
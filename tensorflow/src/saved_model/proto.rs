@@ -0,0 +1,235 @@
+//! Decoding of the SavedModel and tensor bundle messages.
+//!
+//! tfpb only covers the GraphDef side of TensorFlow protos, so the few
+//! messages needed here are decoded by hand, skipping the fields we do not
+//! use.
+
+use std::convert::TryInto;
+
+use protobuf::wire_format::WireType;
+use protobuf::{CodedInputStream, ProtobufEnum};
+use tract_core::internal::*;
+
+use crate::tfpb::graph::GraphDef;
+use crate::tfpb::tensor_shape::TensorShapeProto;
+use crate::tfpb::types::DataType;
+
+enum Field {
+    Varint(u64),
+    Bytes(Vec<u8>),
+    Skipped,
+}
+
+fn pb_error(e: protobuf::ProtobufError) -> TractError {
+    format!("{:?}", e).into()
+}
+
+fn fields(bytes: &[u8]) -> TractResult<Vec<(u32, Field)>> {
+    let mut is = CodedInputStream::from_bytes(bytes);
+    let mut fields = vec![];
+    while !is.eof().map_err(pb_error)? {
+        let (number, wire_type) = is.read_tag_unpack().map_err(pb_error)?;
+        let field = match wire_type {
+            WireType::WireTypeVarint => Field::Varint(is.read_raw_varint64().map_err(pb_error)?),
+            WireType::WireTypeLengthDelimited => Field::Bytes(is.read_bytes().map_err(pb_error)?),
+            _ => {
+                is.skip_field(wire_type).map_err(pb_error)?;
+                Field::Skipped
+            }
+        };
+        fields.push((number, field));
+    }
+    Ok(fields)
+}
+
+fn string(bytes: Vec<u8>) -> TractResult<String> {
+    Ok(String::from_utf8(bytes).map_err(|e| format!("{:?}", e))?)
+}
+
+fn data_type(v: u64) -> TractResult<DatumType> {
+    DataType::from_i32(v as i32).ok_or_else(|| format!("Unknown DataType {}", v))?.try_into()
+}
+
+fn shape(bytes: &[u8]) -> TractResult<TensorShapeProto> {
+    Ok(protobuf::parse_from_bytes(bytes).map_err(pb_error)?)
+}
+
+fn map_entry<V>(bytes: &[u8], value: impl Fn(&[u8]) -> TractResult<V>) -> TractResult<(String, V)> {
+    let mut key = String::new();
+    let mut val = None;
+    for (number, field) in fields(bytes)? {
+        match (number, field) {
+            (1, Field::Bytes(b)) => key = string(b)?,
+            (2, Field::Bytes(b)) => val = Some(value(&b)?),
+            _ => (),
+        }
+    }
+    let val = match val {
+        Some(val) => val,
+        None => value(&[])?,
+    };
+    Ok((key, val))
+}
+
+/// SavedModel, as stored in saved_model.pb.
+#[derive(Clone, Debug, Default)]
+pub struct SavedModelProto {
+    pub meta_graphs: Vec<MetaGraphDef>,
+}
+
+impl SavedModelProto {
+    pub fn parse(bytes: &[u8]) -> TractResult<SavedModelProto> {
+        let mut saved_model = SavedModelProto::default();
+        for (number, field) in fields(bytes)? {
+            if let (2, Field::Bytes(b)) = (number, field) {
+                saved_model.meta_graphs.push(MetaGraphDef::parse(&b)?);
+            }
+        }
+        Ok(saved_model)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MetaGraphDef {
+    pub tags: Vec<String>,
+    pub graph_def: GraphDef,
+    pub signature_def: HashMap<String, SignatureDef>,
+}
+
+impl MetaGraphDef {
+    pub fn parse(bytes: &[u8]) -> TractResult<MetaGraphDef> {
+        let mut meta_graph = MetaGraphDef::default();
+        for (number, field) in fields(bytes)? {
+            match (number, field) {
+                (1, Field::Bytes(b)) => {
+                    // MetaInfoDef
+                    for (number, field) in fields(&b)? {
+                        if let (4, Field::Bytes(tag)) = (number, field) {
+                            meta_graph.tags.push(string(tag)?);
+                        }
+                    }
+                }
+                (2, Field::Bytes(b)) => {
+                    meta_graph.graph_def = protobuf::parse_from_bytes(&b).map_err(pb_error)?
+                }
+                (5, Field::Bytes(b)) => {
+                    let (name, signature) = map_entry(&b, SignatureDef::parse)?;
+                    meta_graph.signature_def.insert(name, signature);
+                }
+                _ => (),
+            }
+        }
+        Ok(meta_graph)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SignatureDef {
+    pub inputs: HashMap<String, TensorInfo>,
+    pub outputs: HashMap<String, TensorInfo>,
+    pub method_name: String,
+}
+
+impl SignatureDef {
+    pub fn parse(bytes: &[u8]) -> TractResult<SignatureDef> {
+        let mut signature = SignatureDef::default();
+        for (number, field) in fields(bytes)? {
+            match (number, field) {
+                (1, Field::Bytes(b)) => {
+                    let (name, info) = map_entry(&b, TensorInfo::parse)?;
+                    signature.inputs.insert(name, info);
+                }
+                (2, Field::Bytes(b)) => {
+                    let (name, info) = map_entry(&b, TensorInfo::parse)?;
+                    signature.outputs.insert(name, info);
+                }
+                (3, Field::Bytes(b)) => signature.method_name = string(b)?,
+                _ => (),
+            }
+        }
+        Ok(signature)
+    }
+}
+
+/// A dense tensor of a signature. Sparse and composite tensors are not
+/// supported.
+#[derive(Clone, Debug, Default)]
+pub struct TensorInfo {
+    pub name: String,
+    pub dtype: Option<DatumType>,
+    pub tensor_shape: Option<TensorShapeProto>,
+}
+
+impl TensorInfo {
+    pub fn parse(bytes: &[u8]) -> TractResult<TensorInfo> {
+        let mut info = TensorInfo::default();
+        for (number, field) in fields(bytes)? {
+            match (number, field) {
+                (1, Field::Bytes(b)) => info.name = string(b)?,
+                (2, Field::Varint(v)) => info.dtype = Some(data_type(v)?),
+                (3, Field::Bytes(b)) => info.tensor_shape = Some(shape(&b)?),
+                (4, _) | (5, _) => bail!("Only dense tensors are supported in signatures"),
+                _ => (),
+            }
+        }
+        Ok(info)
+    }
+}
+
+/// Header of a tensor bundle, stored under the empty key of the index.
+#[derive(Clone, Debug, Default)]
+pub struct BundleHeaderProto {
+    pub num_shards: usize,
+    pub big_endian: bool,
+}
+
+impl BundleHeaderProto {
+    pub fn parse(bytes: &[u8]) -> TractResult<BundleHeaderProto> {
+        let mut header = BundleHeaderProto::default();
+        for (number, field) in fields(bytes)? {
+            match (number, field) {
+                (1, Field::Varint(v)) => header.num_shards = v as usize,
+                (2, Field::Varint(v)) => header.big_endian = v == 1,
+                _ => (),
+            }
+        }
+        Ok(header)
+    }
+}
+
+/// Location of a tensor in the data shards of a bundle.
+#[derive(Clone, Debug)]
+pub struct BundleEntryProto {
+    /// None for types tract has no equivalent for, like resources.
+    pub dtype: Option<DatumType>,
+    pub shape: TVec<usize>,
+    pub shard_id: usize,
+    pub offset: u64,
+    pub size: usize,
+    pub sliced: bool,
+}
+
+impl BundleEntryProto {
+    pub fn parse(bytes: &[u8]) -> TractResult<BundleEntryProto> {
+        let mut entry = BundleEntryProto {
+            dtype: None,
+            shape: tvec!(),
+            shard_id: 0,
+            offset: 0,
+            size: 0,
+            sliced: false,
+        };
+        for (number, field) in fields(bytes)? {
+            match (number, field) {
+                (1, Field::Varint(v)) => entry.dtype = data_type(v).ok(),
+                (2, Field::Bytes(b)) => entry.shape = (&shape(&b)?).try_into()?,
+                (3, Field::Varint(v)) => entry.shard_id = v as usize,
+                (4, Field::Varint(v)) => entry.offset = v,
+                (5, Field::Varint(v)) => entry.size = v as usize,
+                (7, _) => entry.sliced = true,
+                _ => (),
+            }
+        }
+        Ok(entry)
+    }
+}
@@ -31,6 +31,42 @@ impl<'a> TryFrom<&'a TensorShapeProto> for TVec<usize> {
     }
 }
 
+impl<'a> TryFrom<&'a TensorShapeProto> for ShapeFact {
+    type Error = TractError;
+    fn try_from(t: &'a TensorShapeProto) -> TractResult<ShapeFact> {
+        if t.get_unknown_rank() {
+            return Ok(ShapeFact::default());
+        }
+        Ok(ShapeFact::closed(
+            t.get_dim()
+                .iter()
+                .map(|d| if d.size < 0 { DimFact::default() } else { (d.size as usize).to_dim().into() })
+                .collect(),
+        ))
+    }
+}
+
+impl<'a> TryFrom<&'a ShapeFact> for TensorShapeProto {
+    type Error = TractError;
+    fn try_from(fact: &'a ShapeFact) -> TractResult<TensorShapeProto> {
+        let mut shape = TensorShapeProto::new();
+        if fact.is_open() {
+            shape.set_unknown_rank(true);
+            return Ok(shape);
+        }
+        let dims = fact
+            .dims()
+            .map(|d| {
+                let mut dim = TensorShapeProto_Dim::new();
+                dim.size = d.concretize().and_then(|d| d.to_integer().ok()).unwrap_or(-1) as _;
+                dim
+            })
+            .collect();
+        shape.set_dim(::protobuf::RepeatedField::from_vec(dims));
+        Ok(shape)
+    }
+}
+
 impl TryFrom<DatumType> for DataType  {
     type Error = TractError;
     fn try_from(dt: DatumType) -> TractResult<DataType> {
@@ -79,6 +115,17 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
                 DataType::DT_FLOAT => {
                     Array::from_shape_vec(&*dims, t.get_float_val().to_vec())?.into()
                 }
+                DataType::DT_DOUBLE => {
+                    Array::from_shape_vec(&*dims, t.get_double_val().to_vec())?.into()
+                }
+                DataType::DT_STRING => {
+                    let strings = t
+                        .get_string_val()
+                        .iter()
+                        .map(|s| Ok(String::from_utf8(s.to_vec()).map_err(|e| format!("{:?}", e))?))
+                        .collect::<TractResult<Vec<String>>>()?;
+                    Array::from_shape_vec(&*dims, strings)?.into()
+                }
                 _ => unimplemented!("missing type {:?}", dtype),
             }
         };
@@ -125,3 +172,32 @@ impl<'a> TryFrom<&'a Tensor> for TensorProto {
         Ok(tensor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(t: Tensor) {
+        let proto = TensorProto::try_from(&t).unwrap();
+        assert_eq!(Tensor::try_from(&proto).unwrap(), t);
+    }
+
+    #[test]
+    fn tensor_round_trip() {
+        round_trip(tensor0(3f32));
+        round_trip(tensor2(&[[1f32, 2.0, 3.0], [4.0, 5.0, 6.0]]));
+        round_trip(tensor1(&[1f64, -2.0]));
+        round_trip(tensor1(&[1i32, -2]));
+        round_trip(tensor1(&[1i64, -2]));
+    }
+
+    #[test]
+    fn shape_round_trip() {
+        let closed = shapefact!(1, _, 3);
+        let proto = TensorShapeProto::try_from(&closed).unwrap();
+        assert_eq!(ShapeFact::try_from(&proto).unwrap(), closed);
+        let open = ShapeFact::default();
+        let proto = TensorShapeProto::try_from(&open).unwrap();
+        assert_eq!(ShapeFact::try_from(&proto).unwrap(), open);
+    }
+}
@@ -165,6 +165,14 @@ impl node_def::NodeDef {
         }
     }
 
+    pub fn get_attr_opt_shape_fact(&self, name: &str) -> TractResult<Option<ShapeFact>> {
+        if let Some(t) = self.get_attr().get(name).map(|v| v.get_shape()) {
+            Ok(Some(t.try_into()?))
+        } else {
+            Ok(None)
+        }
+    }
+
     pub fn get_attr_tensor(&self, name: &str) -> TractResult<tract_core::internal::Tensor> {
         Ok(self.get_attr_opt_tensor(name)?
             .ok_or_else(|| format!("Node {} ({}) expected tensor attribute '{}'", self.get_name(), self.get_op(), name))?)
@@ -238,6 +246,14 @@ impl<'a> From<&'a str> for AttrValue {
     }
 }
 
+impl From<bool> for AttrValue {
+    fn from(t: bool) -> AttrValue {
+        let mut value = attr_value::AttrValue::new();
+        value.set_b(t);
+        value
+    }
+}
+
 impl From<i64> for AttrValue {
     fn from(t: i64) -> AttrValue {
         let mut value = attr_value::AttrValue::new();
@@ -0,0 +1,38 @@
+extern crate tract_core;
+extern crate tract_tensorflow;
+
+use std::path::{Path, PathBuf};
+use tract_core::internal::*;
+
+// Both models are the regression examples of the TensorFlow Rust bindings
+// (https://github.com/tensorflow/rust), saved by TensorFlow 1 and 2.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/models/saved_model").join(name)
+}
+
+#[test]
+fn run_tf1_saved_model() {
+    let tf = tract_tensorflow::tensorflow();
+    let saved = tf.saved_model_for_path(fixture("tf1"), &["serve", "train"]).unwrap();
+    let w = saved.variables["w"].as_slice::<f32>().unwrap()[0];
+    assert_eq!(saved.variables["b"], tensor1(&[0f32]));
+    let model = saved.model_for_signature(&tf, "tensorflow/serving/regress").unwrap();
+    let plan = SimplePlan::new(&model).unwrap();
+    let mut state = SimpleState::new(&plan).unwrap();
+    saved.init_variables(&mut state.session_state).unwrap();
+    let outputs = state.run(tvec!(tensor1(&[2f32]), tensor1(&[0f32]))).unwrap();
+    assert_eq!(outputs[0], rctensor1(&[2.0 * w]));
+}
+
+#[test]
+fn read_tf2_checkpoint() {
+    let tf = tract_tensorflow::tensorflow();
+    let saved = tf.saved_model_for_path(fixture("tf2"), &["serve"]).unwrap();
+    let value = |name: &str| &saved.variables[&format!("{}/.ATTRIBUTES/VARIABLE_VALUE", name)];
+    assert_eq!(value("b"), &tensor1(&[0f32]));
+    assert_eq!(value("optimizer/learning_rate"), &tensor0(0.5f32));
+    assert_eq!(value("optimizer/iter"), &tensor0(0i64));
+    let graph = &saved.variables["_CHECKPOINTABLE_OBJECT_GRAPH"];
+    assert_eq!(graph.datum_type(), DatumType::String);
+    assert_eq!(graph.shape(), &[] as &[usize]);
+}